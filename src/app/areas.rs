@@ -0,0 +1,30 @@
+use ratatui::layout::Rect;
+
+use super::RequestTab;
+
+/// Where each part of the screen was drawn last, to find what the mouse
+/// points at
+#[derive(Clone, Default)]
+pub struct Areas {
+    pub method: Rect,
+    pub endpoint: Rect,
+    pub request_tabs: Rect,
+    /// Title of each request tab
+    pub tab_titles: Vec<(RequestTab, Rect)>,
+    /// Everything below the request tabs
+    pub request_content: Rect,
+    /// The body editor, empty when not shown
+    pub body_editor: Rect,
+    /// Rows of the headers, query or form table, empty when not shown
+    pub table_rows: Rect,
+    /// Index of the first row shown by the table
+    pub table_offset: usize,
+    pub response_filter: Rect,
+    pub response: Rect,
+    /// Options of the method popup or fields of a form popup
+    pub popup_items: Vec<Rect>,
+}
+
+pub fn contains(area: Rect, column: u16, row: u16) -> bool {
+    (area.left()..area.right()).contains(&column) && (area.top()..area.bottom()).contains(&row)
+}
@@ -0,0 +1,345 @@
+use serde_json::Value;
+
+use crate::{
+    content_type::ContentKind,
+    diff::{self, Change, PathChange},
+};
+
+use super::Response;
+
+/// Unchanged lines shown around a change, longer runs are folded
+const CONTEXT: usize = 3;
+
+/// Response headers that depend on the exchange rather than the API, left
+/// out of examples and comparisons
+pub const TRANSPORT_HEADERS: [&str; 6] = [
+    "date",
+    "content-length",
+    "transfer-encoding",
+    "connection",
+    "keep-alive",
+    "content-encoding",
+];
+
+pub enum DiffLine {
+    Section(String),
+    Change(Change),
+    /// Unchanged lines not shown
+    Folded(usize),
+    /// A value that differs between two JSON bodies
+    Path(PathChange),
+}
+
+/// A line of the side by side view
+pub enum Row<'a> {
+    /// Shown across both sides
+    Full(&'a DiffLine),
+    /// The old line on the left and the new one on the right
+    Sides(Option<&'a Change>, Option<&'a Change>),
+}
+
+/// A response that can be compared with another
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Source {
+    /// The response shown
+    Response,
+    History(usize),
+    /// An example of the request of the collection being edited
+    Example(usize),
+}
+
+/// Two responses compared, shown in a popup
+pub struct Diff {
+    pub title: String,
+    pub lines: Vec<DiffLine>,
+    pub side_by_side: bool,
+    pub scroll: u16,
+    /// Updated on render to the number of lines that fit
+    pub height: usize,
+}
+
+/// `name: value` lines, sorted by name
+fn header_lines(response: &Response) -> String {
+    let mut headers: Vec<String> = response
+        .headers
+        .iter()
+        .filter(|(name, _)| !TRANSPORT_HEADERS.contains(&name.to_lowercase().as_str()))
+        .map(|(name, value)| format!("{}: {value}", name.to_lowercase()))
+        .collect();
+
+    headers.sort();
+    headers.join("\n")
+}
+
+fn body_json(response: &Response) -> Option<Value> {
+    match response.kind() {
+        ContentKind::Json => serde_json::from_str(&response.text).ok(),
+        _ => None,
+    }
+}
+
+fn body_text(response: &Response) -> String {
+    match response.is_binary {
+        true => format!("{} bytes of binary data", response.body.len()),
+        false => response.pretty_text(),
+    }
+}
+
+/// A run of unchanged lines, keeping `start` and `end` lines of context
+fn flush(same: &mut Vec<Change>, lines: &mut Vec<DiffLine>, start: usize, end: usize) {
+    if same.len() <= start + end + 1 {
+        lines.extend(same.drain(..).map(DiffLine::Change));
+        return;
+    }
+
+    let folded = same.len() - start - end;
+    let tail = same.split_off(same.len() - end);
+
+    same.truncate(start);
+
+    lines.extend(same.drain(..).map(DiffLine::Change));
+    lines.push(DiffLine::Folded(folded));
+    lines.extend(tail.into_iter().map(DiffLine::Change));
+}
+
+/// Removed lines facing the added ones, as many rows as the longest run
+fn pair<'a>(rows: &mut Vec<Row<'a>>, removed: &mut Vec<&'a Change>, added: &mut Vec<&'a Change>) {
+    let count = removed.len().max(added.len());
+    let mut removed = removed.drain(..);
+    let mut added = added.drain(..);
+
+    for _ in 0..count {
+        rows.push(Row::Sides(removed.next(), added.next()));
+    }
+}
+
+/// Changes with the unchanged runs folded to their context
+fn fold(changes: Vec<Change>) -> Vec<DiffLine> {
+    let mut lines = Vec::new();
+    let mut same = Vec::new();
+
+    for change in changes {
+        match change.is_same() {
+            true => same.push(change),
+            false => {
+                let start = if lines.is_empty() { 0 } else { CONTEXT };
+
+                flush(&mut same, &mut lines, start, CONTEXT);
+                lines.push(DiffLine::Change(change));
+            }
+        }
+    }
+
+    let start = if lines.is_empty() { 0 } else { CONTEXT };
+
+    flush(&mut same, &mut lines, start, 0);
+
+    lines
+}
+
+impl Diff {
+    /// Status, headers and body of `old` against `new`, each named
+    pub fn new(old: (&str, &Response), new: (&str, &Response)) -> Self {
+        let (old_name, old) = old;
+        let (new_name, new) = new;
+
+        // JSON bodies are compared with their keys sorted, and by path
+        let json = body_json(old).zip(body_json(new));
+
+        let (old_body, new_body) = match &json {
+            Some((old, new)) => (
+                serde_json::to_string_pretty(&diff::canonical(old)).unwrap_or_default(),
+                serde_json::to_string_pretty(&diff::canonical(new)).unwrap_or_default(),
+            ),
+            None => (body_text(old), body_text(new)),
+        };
+
+        let sections = [
+            (
+                "Status",
+                old.status_code.to_string(),
+                new.status_code.to_string(),
+            ),
+            ("Headers", header_lines(old), header_lines(new)),
+            ("Body", old_body, new_body),
+        ];
+
+        let mut lines = Vec::new();
+        let mut is_same = true;
+
+        for (title, old, new) in sections {
+            let changes = diff::lines(&old, &new);
+
+            is_same &= changes.iter().all(Change::is_same);
+
+            lines.push(DiffLine::Section(title.to_string()));
+            lines.extend(fold(changes));
+            lines.push(DiffLine::Change(Change::Same(String::new())));
+        }
+
+        let paths = json
+            .map(|(old, new)| diff::json(&old, &new))
+            .unwrap_or_default();
+
+        if !paths.is_empty() {
+            lines.push(DiffLine::Section("Changed paths".to_string()));
+            lines.extend(paths.iter().cloned().map(DiffLine::Path));
+        }
+
+        let title = match (is_same, paths.len()) {
+            (true, _) => format!("{old_name} and {new_name} are the same"),
+            (false, 0) => format!("{old_name} → {new_name}"),
+            (false, 1) => format!("{old_name} → {new_name} · 1 changed path"),
+            (false, count) => format!("{old_name} → {new_name} · {count} changed paths"),
+        };
+
+        Self {
+            title,
+            lines,
+            side_by_side: false,
+            scroll: 0,
+            height: 0,
+        }
+    }
+
+    /// The lines paired old and new, removed lines facing the lines added
+    /// in their place
+    pub fn rows(&self) -> Vec<Row<'_>> {
+        let mut rows = Vec::new();
+        let mut removed = Vec::new();
+        let mut added = Vec::new();
+
+        for line in &self.lines {
+            match line {
+                DiffLine::Change(change @ Change::Removed(_)) => removed.push(change),
+                DiffLine::Change(change @ Change::Added(_)) => added.push(change),
+                DiffLine::Change(change) => {
+                    pair(&mut rows, &mut removed, &mut added);
+                    rows.push(Row::Sides(Some(change), Some(change)));
+                }
+                line => {
+                    pair(&mut rows, &mut removed, &mut added);
+                    rows.push(Row::Full(line));
+                }
+            }
+        }
+
+        pair(&mut rows, &mut removed, &mut added);
+
+        rows
+    }
+
+    fn line_count(&self) -> usize {
+        match self.side_by_side {
+            true => self.rows().len(),
+            false => self.lines.len(),
+        }
+    }
+
+    pub fn toggle_side_by_side(&mut self) {
+        self.side_by_side = !self.side_by_side;
+        self.scroll = 0;
+    }
+
+    pub fn scroll_down(&mut self) {
+        let max = self.line_count().saturating_sub(self.height);
+
+        self.scroll = (self.scroll + 1).min(u16::try_from(max).unwrap_or(u16::MAX));
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered(count: usize) -> Vec<Change> {
+        (1..=count).map(|i| Change::Same(i.to_string())).collect()
+    }
+
+    fn describe(lines: &[DiffLine]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| match line {
+                DiffLine::Change(Change::Same(line)) => format!(" {line}"),
+                DiffLine::Change(Change::Removed(line)) => format!("-{line}"),
+                DiffLine::Change(Change::Added(line)) => format!("+{line}"),
+                DiffLine::Folded(count) => format!("… {count}"),
+                DiffLine::Section(title) => format!("# {title}"),
+                DiffLine::Path(_) => "path".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn folds_unchanged_runs_to_their_context() {
+        let mut changes = numbered(10);
+        changes.push(Change::Removed("old".to_string()));
+        changes.push(Change::Added("new".to_string()));
+        changes.extend(numbered(10));
+
+        assert_eq!(
+            describe(&fold(changes)),
+            vec!["… 7", " 8", " 9", " 10", "-old", "+new", " 1", " 2", " 3", "… 7",]
+        );
+    }
+
+    #[test]
+    fn keeps_short_unchanged_runs() {
+        let mut changes = vec![Change::Added("a".to_string())];
+        changes.extend(numbered(7));
+        changes.push(Change::Added("b".to_string()));
+
+        assert_eq!(
+            describe(&fold(changes)),
+            vec!["+a", " 1", " 2", " 3", " 4", " 5", " 6", " 7", "+b"]
+        );
+    }
+
+    #[test]
+    fn folds_unchanged_sections_entirely() {
+        assert_eq!(describe(&fold(numbered(5))), vec!["… 5"]);
+        assert_eq!(describe(&fold(numbered(1))), vec![" 1"]);
+    }
+
+    #[test]
+    fn pairs_removed_lines_with_added_ones() {
+        let diff = Diff {
+            title: String::new(),
+            lines: vec![
+                DiffLine::Section("Body".to_string()),
+                DiffLine::Change(Change::Same("a".to_string())),
+                DiffLine::Change(Change::Removed("b".to_string())),
+                DiffLine::Change(Change::Removed("c".to_string())),
+                DiffLine::Change(Change::Added("d".to_string())),
+                DiffLine::Folded(4),
+            ],
+            side_by_side: true,
+            scroll: 0,
+            height: 0,
+        };
+
+        let rows: Vec<String> = diff
+            .rows()
+            .iter()
+            .map(|row| match row {
+                Row::Full(line) => describe(std::slice::from_ref(*line)).concat(),
+                Row::Sides(old, new) => {
+                    let side = |change: &Option<&Change>| match change {
+                        Some(Change::Same(line) | Change::Removed(line) | Change::Added(line)) => {
+                            line.clone()
+                        }
+                        None => String::new(),
+                    };
+
+                    format!("{}|{}", side(old), side(new))
+                }
+            })
+            .collect();
+
+        assert_eq!(rows, vec!["# Body", "a|a", "b|d", "c|", "… 4"]);
+    }
+}
@@ -0,0 +1,747 @@
+use serde_json::Value;
+
+/// A single step of a path expression
+#[derive(Clone, Debug)]
+enum Segment {
+    Key(String),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>),
+    Union(Vec<Segment>),
+    Wildcard,
+    Descendants,
+    Filter(Condition),
+}
+
+#[derive(Clone, Debug)]
+enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Debug)]
+struct Condition {
+    path: Vec<Segment>,
+    comparison: Option<(Operator, Value)>,
+}
+
+/// A stage of a jq pipeline (`stage | stage | ...`)
+#[derive(Clone, Debug)]
+enum Stage {
+    Path(Vec<Segment>),
+    Keys,
+    Length,
+    Select(Condition),
+}
+
+#[derive(Clone, Debug)]
+enum Expression {
+    /// `$.store.book[*].author`
+    JsonPath(Vec<Segment>),
+    /// `.store.book[] | .author`
+    Jq(Vec<Stage>),
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(source: &str) -> Self {
+        Self {
+            chars: source.chars().collect(),
+            position: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.position + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.position += 1;
+        c
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+
+            self.position += 1;
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.position >= self.chars.len()
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, String> {
+        match self.peek() {
+            Some(c) => Err(format!(
+                "{message}: unexpected '{c}' at column {}",
+                self.position + 1
+            )),
+            None => Err(format!("{message}: unexpected end of filter")),
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            return Ok(());
+        }
+
+        self.error(&format!("expected '{expected}'"))
+    }
+
+    fn consume_keyword(&mut self, keyword: &str) -> bool {
+        let end = self.position + keyword.chars().count();
+
+        if end > self.chars.len() {
+            return false;
+        }
+
+        let matches = self.chars[self.position..end]
+            .iter()
+            .copied()
+            .eq(keyword.chars());
+
+        let followed_by_identifier = self
+            .chars
+            .get(end)
+            .map(|c| is_identifier_char(*c))
+            .unwrap_or(false);
+
+        if matches && !followed_by_identifier {
+            self.position = end;
+            return true;
+        }
+
+        false
+    }
+
+    fn parse_expression(&mut self) -> Result<Expression, String> {
+        self.skip_whitespace();
+
+        if self.peek() == Some('$') {
+            self.bump();
+
+            let segments = self.parse_segments()?;
+
+            self.skip_whitespace();
+
+            if !self.is_done() {
+                return self.error("invalid JSONPath");
+            }
+
+            return Ok(Expression::JsonPath(segments));
+        }
+
+        let mut stages = vec![self.parse_stage()?];
+
+        loop {
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some('|') => {
+                    self.bump();
+                    stages.push(self.parse_stage()?);
+                }
+                None => break,
+                _ => return self.error("invalid filter"),
+            }
+        }
+
+        Ok(Expression::Jq(stages))
+    }
+
+    fn parse_stage(&mut self) -> Result<Stage, String> {
+        self.skip_whitespace();
+
+        if self.consume_keyword("keys") {
+            return Ok(Stage::Keys);
+        }
+
+        if self.consume_keyword("length") {
+            return Ok(Stage::Length);
+        }
+
+        if self.consume_keyword("select") {
+            self.skip_whitespace();
+            self.expect('(')?;
+
+            let condition = self.parse_condition()?;
+
+            self.skip_whitespace();
+            self.expect(')')?;
+
+            return Ok(Stage::Select(condition));
+        }
+
+        if self.peek() != Some('.') {
+            return self.error("expected a path starting with '.'");
+        }
+
+        Ok(Stage::Path(self.parse_segments()?))
+    }
+
+    fn parse_segments(&mut self) -> Result<Vec<Segment>, String> {
+        let mut segments = Vec::new();
+
+        loop {
+            match self.peek() {
+                Some('.') if self.peek_at(1) == Some('.') => {
+                    self.position += 2;
+                    segments.push(Segment::Descendants);
+
+                    if let Some(segment) = self.parse_dot_member()? {
+                        segments.push(segment);
+                    }
+                }
+                Some('.') => {
+                    self.bump();
+
+                    if let Some(segment) = self.parse_dot_member()? {
+                        segments.push(segment);
+                    }
+                }
+                Some('[') => {
+                    self.bump();
+                    segments.push(self.parse_bracket()?);
+                }
+                _ => break,
+            }
+        }
+
+        Ok(segments)
+    }
+
+    /// Parses what follows a `.`, which may be nothing at all (jq's identity)
+    fn parse_dot_member(&mut self) -> Result<Option<Segment>, String> {
+        match self.peek() {
+            Some('*') => {
+                self.bump();
+                Ok(Some(Segment::Wildcard))
+            }
+            Some('"') => Ok(Some(Segment::Key(self.parse_string()?))),
+            Some(c) if is_identifier_char(c) => {
+                let mut key = String::new();
+
+                while let Some(c) = self.peek() {
+                    if !is_identifier_char(c) {
+                        break;
+                    }
+
+                    key.push(c);
+                    self.bump();
+                }
+
+                Ok(Some(Segment::Key(key)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn parse_bracket(&mut self) -> Result<Segment, String> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(']') => {
+                self.bump();
+                return Ok(Segment::Wildcard);
+            }
+            Some('*') => {
+                self.bump();
+                self.skip_whitespace();
+                self.expect(']')?;
+                return Ok(Segment::Wildcard);
+            }
+            Some('?') => {
+                self.bump();
+                self.skip_whitespace();
+                self.expect('(')?;
+
+                let condition = self.parse_condition()?;
+
+                self.skip_whitespace();
+                self.expect(')')?;
+                self.skip_whitespace();
+                self.expect(']')?;
+
+                return Ok(Segment::Filter(condition));
+            }
+            _ => {}
+        }
+
+        let mut items = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+
+            let item = match self.peek() {
+                Some('"') | Some('\'') => Segment::Key(self.parse_string()?),
+                Some(c) if c == ':' || c == '-' || c.is_ascii_digit() => {
+                    let start = self.parse_integer()?;
+
+                    self.skip_whitespace();
+
+                    if self.peek() == Some(':') {
+                        self.bump();
+                        self.skip_whitespace();
+
+                        Segment::Slice(start, self.parse_integer()?)
+                    } else {
+                        match start {
+                            Some(index) => Segment::Index(index),
+                            None => return self.error("expected an index"),
+                        }
+                    }
+                }
+                _ => return self.error("invalid subscript"),
+            };
+
+            items.push(item);
+
+            self.skip_whitespace();
+
+            match self.bump() {
+                Some(',') => continue,
+                Some(']') => break,
+                _ => {
+                    self.position -= 1;
+                    return self.error("expected ',' or ']'");
+                }
+            }
+        }
+
+        if items.len() == 1 {
+            return Ok(items.remove(0));
+        }
+
+        Ok(Segment::Union(items))
+    }
+
+    fn parse_integer(&mut self) -> Result<Option<i64>, String> {
+        let mut digits = String::new();
+
+        if self.peek() == Some('-') {
+            digits.push('-');
+            self.bump();
+        }
+
+        while let Some(c) = self.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+
+            digits.push(c);
+            self.bump();
+        }
+
+        if digits.is_empty() {
+            return Ok(None);
+        }
+
+        match digits.parse::<i64>() {
+            Ok(n) => Ok(Some(n)),
+            Err(_) => self.error("invalid number"),
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        let quote = self.bump().unwrap();
+        let mut value = String::new();
+
+        loop {
+            match self.bump() {
+                Some('\\') => match self.bump() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some(c) => value.push(c),
+                    None => return self.error("unterminated string"),
+                },
+                Some(c) if c == quote => break,
+                Some(c) => value.push(c),
+                None => return self.error("unterminated string"),
+            }
+        }
+
+        Ok(value)
+    }
+
+    fn parse_condition(&mut self) -> Result<Condition, String> {
+        self.skip_whitespace();
+
+        // JSONPath filters are relative to `@`, jq ones to `.`
+        if self.peek() == Some('@') {
+            self.bump();
+        } else if self.peek() != Some('.') {
+            return self.error("expected a path starting with '@' or '.'");
+        }
+
+        let path = self.parse_segments()?;
+
+        self.skip_whitespace();
+
+        let operator = match (self.peek(), self.peek_at(1)) {
+            (Some('='), Some('=')) => Some((Operator::Eq, 2)),
+            (Some('!'), Some('=')) => Some((Operator::Ne, 2)),
+            (Some('<'), Some('=')) => Some((Operator::Le, 2)),
+            (Some('>'), Some('=')) => Some((Operator::Ge, 2)),
+            (Some('<'), _) => Some((Operator::Lt, 1)),
+            (Some('>'), _) => Some((Operator::Gt, 1)),
+            _ => None,
+        };
+
+        let comparison = match operator {
+            Some((operator, length)) => {
+                self.position += length;
+                self.skip_whitespace();
+
+                Some((operator, self.parse_literal()?))
+            }
+            None => None,
+        };
+
+        Ok(Condition { path, comparison })
+    }
+
+    fn parse_literal(&mut self) -> Result<Value, String> {
+        if let Some('"') | Some('\'') = self.peek() {
+            return Ok(Value::String(self.parse_string()?));
+        }
+
+        let start = self.position;
+        let mut raw = String::new();
+
+        while let Some(c) = self.peek() {
+            if c == ')' || c == ']' || c.is_whitespace() {
+                break;
+            }
+
+            raw.push(c);
+            self.bump();
+        }
+
+        serde_json::from_str(&raw).or_else(|_| {
+            self.position = start;
+            self.error("invalid literal")
+        })
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let resolved = if index < 0 { len as i64 + index } else { index };
+
+    if resolved < 0 || resolved >= len as i64 {
+        return None;
+    }
+
+    Some(resolved as usize)
+}
+
+fn children(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => map.values().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn descendants<'a>(value: &'a Value, output: &mut Vec<&'a Value>) {
+    output.push(value);
+
+    for child in children(value) {
+        descendants(child, output);
+    }
+}
+
+fn apply_segment<'a>(segment: &Segment, value: &'a Value, output: &mut Vec<&'a Value>) {
+    match segment {
+        Segment::Key(key) => {
+            if let Some(child) = value.get(key) {
+                output.push(child);
+            }
+        }
+        Segment::Index(index) => {
+            if let Value::Array(items) = value {
+                if let Some(i) = resolve_index(*index, items.len()) {
+                    output.push(&items[i]);
+                }
+            }
+        }
+        Segment::Slice(start, end) => {
+            if let Value::Array(items) = value {
+                let len = items.len() as i64;
+                let clamp = |n: i64| (if n < 0 { len + n } else { n }).clamp(0, len) as usize;
+
+                let start = start.map(clamp).unwrap_or(0);
+                let end = end.map(clamp).unwrap_or(items.len());
+
+                if start < end {
+                    output.extend(items[start..end].iter());
+                }
+            }
+        }
+        Segment::Union(segments) => {
+            for segment in segments {
+                apply_segment(segment, value, output);
+            }
+        }
+        Segment::Wildcard => output.extend(children(value)),
+        Segment::Descendants => descendants(value, output),
+        Segment::Filter(condition) => output.extend(
+            children(value)
+                .into_iter()
+                .filter(|child| condition.matches(child)),
+        ),
+    }
+}
+
+fn select<'a>(segments: &[Segment], root: &'a Value) -> Vec<&'a Value> {
+    let mut current = vec![root];
+
+    for segment in segments {
+        let mut next = Vec::new();
+
+        for value in current {
+            apply_segment(segment, value, &mut next);
+        }
+
+        current = next;
+    }
+
+    current
+}
+
+fn compare(left: &Value, right: &Value) -> Option<std::cmp::Ordering> {
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+impl Condition {
+    fn matches(&self, value: &Value) -> bool {
+        let Some(found) = select(&self.path, value).into_iter().next() else {
+            return false;
+        };
+
+        let Some((operator, expected)) = &self.comparison else {
+            return !matches!(found, Value::Null | Value::Bool(false));
+        };
+
+        let equal = match (found, expected) {
+            (Value::Number(_), Value::Number(_)) => {
+                compare(found, expected) == Some(std::cmp::Ordering::Equal)
+            }
+            _ => found == expected,
+        };
+
+        match operator {
+            Operator::Eq => equal,
+            Operator::Ne => !equal,
+            Operator::Lt => compare(found, expected).is_some_and(|o| o.is_lt()),
+            Operator::Le => compare(found, expected).is_some_and(|o| o.is_le()),
+            Operator::Gt => compare(found, expected).is_some_and(|o| o.is_gt()),
+            Operator::Ge => compare(found, expected).is_some_and(|o| o.is_ge()),
+        }
+    }
+}
+
+impl Stage {
+    fn apply(&self, value: Value) -> Vec<Value> {
+        match self {
+            Self::Path(segments) => select(segments, &value).into_iter().cloned().collect(),
+            Self::Keys => match value {
+                Value::Object(map) => vec![Value::Array(
+                    map.keys().map(|key| Value::String(key.clone())).collect(),
+                )],
+                Value::Array(items) => vec![Value::Array(
+                    (0..items.len()).map(|i| Value::from(i as u64)).collect(),
+                )],
+                _ => Vec::new(),
+            },
+            Self::Length => vec![match &value {
+                Value::Array(items) => Value::from(items.len() as u64),
+                Value::Object(map) => Value::from(map.len() as u64),
+                Value::String(s) => Value::from(s.chars().count() as u64),
+                Value::Number(n) => Value::from(n.as_f64().unwrap_or(0.0).abs()),
+                Value::Null => Value::from(0),
+                Value::Bool(_) => return Vec::new(),
+            }],
+            Self::Select(condition) => {
+                if condition.matches(&value) {
+                    vec![value]
+                } else {
+                    Vec::new()
+                }
+            }
+        }
+    }
+}
+
+/// Evaluates a jq-like (`.items[0].name`) or JSONPath (`$.items[*].name`)
+/// expression against a JSON document
+fn evaluate(expression: &str, document: &Value) -> Result<String, String> {
+    let expression = Parser::new(expression).parse_expression()?;
+
+    match expression {
+        Expression::JsonPath(segments) => {
+            let results: Vec<Value> = select(&segments, document).into_iter().cloned().collect();
+
+            Ok(format!("{:#}\n", Value::Array(results)))
+        }
+        Expression::Jq(stages) => {
+            let mut values = vec![document.clone()];
+
+            for stage in stages {
+                values = values.into_iter().flat_map(|v| stage.apply(v)).collect();
+            }
+
+            Ok(values
+                .iter()
+                .map(|value| format!("{:#}\n", value))
+                .collect::<String>())
+        }
+    }
+}
+
+/// Filters a JSON response body, returning the text to display or an error
+/// to show next to the filter input
+pub fn filter_response(text: String, expression: String) -> Result<String, String> {
+    if expression.trim().is_empty() {
+        return Ok(text);
+    }
+
+    let document: Value =
+        serde_json::from_str(&text).map_err(|e| format!("invalid JSON response: {e}"))?;
+
+    evaluate(expression.trim(), &document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = r#"{
+        "items": [
+            {"name": "a", "price": 1},
+            {"name": "b", "price": 2},
+            {"name": "c", "price": 3}
+        ],
+        "meta": {"name": "list", "total": 3}
+    }"#;
+
+    fn filter(expression: &str) -> Result<String, String> {
+        filter_response(DOCUMENT.to_string(), expression.to_string())
+    }
+
+    fn values(expression: &str) -> Value {
+        let output = filter(expression).unwrap();
+
+        serde_json::Deserializer::from_str(&output)
+            .into_iter::<Value>()
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    #[test]
+    fn empty_expression_keeps_the_text() {
+        assert_eq!(filter("  ").unwrap(), DOCUMENT);
+    }
+
+    #[test]
+    fn jsonpath_wildcards_and_slices() {
+        assert_eq!(
+            values("$.items[*].name"),
+            serde_json::json!([["a", "b", "c"]])
+        );
+        assert_eq!(values("$.items[1:3].price"), serde_json::json!([[2, 3]]));
+        assert_eq!(values("$.items[:1].name"), serde_json::json!([["a"]]));
+        assert_eq!(values("$.items[-1].name"), serde_json::json!([["c"]]));
+        assert_eq!(values("$.items[0,2].name"), serde_json::json!([["a", "c"]]));
+    }
+
+    #[test]
+    fn jsonpath_descendants_and_filters() {
+        assert_eq!(
+            values("$..name"),
+            serde_json::json!([["a", "b", "c", "list"]])
+        );
+        assert_eq!(
+            values("$.items[?(@.price > 1)].name"),
+            serde_json::json!([["b", "c"]])
+        );
+        assert_eq!(
+            values("$.items[?(@.name == 'a')].price"),
+            serde_json::json!([[1]])
+        );
+    }
+
+    #[test]
+    fn jq_paths_and_functions() {
+        assert_eq!(values(".meta.name"), serde_json::json!(["list"]));
+        assert_eq!(values(".items[].price"), serde_json::json!([1, 2, 3]));
+        assert_eq!(values(".items | length"), serde_json::json!([3]));
+        assert_eq!(
+            values(".meta | keys"),
+            serde_json::json!([["name", "total"]])
+        );
+        assert_eq!(
+            values(".items[] | select(.price >= 2) | .name"),
+            serde_json::json!(["b", "c"])
+        );
+        assert_eq!(
+            values("."),
+            Value::Array(vec![serde_json::from_str(DOCUMENT).unwrap()])
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        assert_eq!(
+            filter("$.items[").unwrap_err(),
+            "invalid subscript: unexpected end of filter"
+        );
+        assert_eq!(
+            filter("$.items[0").unwrap_err(),
+            "expected ',' or ']': unexpected end of filter"
+        );
+        assert_eq!(
+            filter("items").unwrap_err(),
+            "expected a path starting with '.': unexpected 'i' at column 1"
+        );
+        assert_eq!(
+            filter(".items | select(.price >)").unwrap_err(),
+            "invalid literal: unexpected ')' at column 25"
+        );
+        assert_eq!(
+            filter(".items ]").unwrap_err(),
+            "invalid filter: unexpected ']' at column 8"
+        );
+    }
+
+    #[test]
+    fn reports_invalid_json() {
+        let error = filter_response("{".to_string(), ".".to_string()).unwrap_err();
+
+        assert!(error.starts_with("invalid JSON response"));
+    }
+}
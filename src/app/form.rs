@@ -43,6 +43,10 @@ pub enum FormKind {
     EditQueryParam,
     AddBodyFormField,
     EditBodyFormField,
+    SaveResponse,
+    ExportHar,
+    SaveExample,
+    GoToOffset,
 }
 
 #[derive(Clone)]
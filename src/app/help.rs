@@ -0,0 +1,37 @@
+use super::AppPopup;
+
+pub struct HelpSection {
+    pub title: String,
+    /// Keys and what they do
+    pub entries: Vec<(String, String)>,
+}
+
+/// The keys available where the help was opened
+pub struct Help {
+    pub sections: Vec<HelpSection>,
+    pub scroll: u16,
+    /// Updated on render to the number of lines that fit
+    pub height: usize,
+    /// Popup the help was opened over, shown again when it closes
+    pub previous: Option<Box<AppPopup>>,
+}
+
+impl Help {
+    /// Lines taken by the sections, with a title and a gap for each
+    pub fn line_count(&self) -> usize {
+        self.sections
+            .iter()
+            .map(|section| section.entries.len() + 2)
+            .sum()
+    }
+
+    pub fn scroll_down(&mut self) {
+        let max = self.line_count().saturating_sub(self.height);
+
+        self.scroll = (self.scroll + 1).min(u16::try_from(max).unwrap_or(u16::MAX));
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+}
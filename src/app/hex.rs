@@ -0,0 +1,147 @@
+/// Scroll state of the hex dump shown for binary responses
+#[derive(Clone)]
+pub struct HexView {
+    /// Offset of the first byte shown, always a multiple of `bytes_per_row`
+    pub offset: usize,
+    /// Updated on render to fit the available width
+    pub bytes_per_row: usize,
+    /// Updated on render to the number of rows that fit
+    pub page_rows: usize,
+}
+
+impl Default for HexView {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            bytes_per_row: 16,
+            page_rows: 1,
+        }
+    }
+}
+
+impl HexView {
+    /// Offset of the last row that still fills the viewport
+    fn max_offset(&self, len: usize) -> usize {
+        let rows = len.div_ceil(self.bytes_per_row);
+
+        rows.saturating_sub(self.page_rows) * self.bytes_per_row
+    }
+
+    pub fn clamp(&mut self, len: usize) {
+        self.offset = (self.offset - self.offset % self.bytes_per_row).min(self.max_offset(len));
+    }
+
+    pub fn scroll_down(&mut self, rows: usize, len: usize) {
+        self.offset += rows * self.bytes_per_row;
+        self.clamp(len);
+    }
+
+    pub fn scroll_up(&mut self, rows: usize) {
+        self.offset = self.offset.saturating_sub(rows * self.bytes_per_row);
+    }
+
+    pub fn first(&mut self) {
+        self.offset = 0;
+    }
+
+    pub fn last(&mut self, len: usize) {
+        self.offset = self.max_offset(len);
+    }
+
+    /// Jump to the row containing `offset`
+    pub fn go_to(&mut self, offset: usize, len: usize) {
+        self.offset = offset.min(len.saturating_sub(1));
+        self.clamp(len);
+    }
+}
+
+/// Parse an offset typed by the user, either decimal or `0x` prefixed hex
+pub fn parse_offset(value: &str) -> Option<usize> {
+    let value = value.trim();
+
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(page_rows: usize) -> HexView {
+        HexView {
+            page_rows,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_decimal_and_hex_offsets() {
+        assert_eq!(parse_offset(" 42 "), Some(42));
+        assert_eq!(parse_offset("0x1F"), Some(31));
+        assert_eq!(parse_offset("0XfF"), Some(255));
+        assert_eq!(parse_offset("0x"), None);
+        assert_eq!(parse_offset("-1"), None);
+        assert_eq!(parse_offset("1f"), None);
+    }
+
+    #[test]
+    fn scrolls_by_rows_up_to_the_last_page() {
+        // 10 rows of 16 bytes, the last one partial
+        let len = 150;
+        let mut view = view(4);
+
+        view.scroll_down(3, len);
+        assert_eq!(view.offset, 48);
+
+        view.scroll_down(10, len);
+        assert_eq!(view.offset, 96);
+
+        view.scroll_up(2);
+        assert_eq!(view.offset, 64);
+
+        view.scroll_up(10);
+        assert_eq!(view.offset, 0);
+
+        view.last(len);
+        assert_eq!(view.offset, 96);
+    }
+
+    #[test]
+    fn goes_to_the_row_of_an_offset() {
+        let mut view = view(2);
+
+        view.go_to(37, 150);
+        assert_eq!(view.offset, 32);
+
+        // Past the end, the last page
+        view.go_to(1000, 150);
+        assert_eq!(view.offset, 128);
+
+        view.go_to(5, 0);
+        assert_eq!(view.offset, 0);
+    }
+
+    #[test]
+    fn clamps_to_the_row_width() {
+        let mut view = view(1);
+
+        view.offset = 40;
+        view.bytes_per_row = 8;
+        view.clamp(100);
+        assert_eq!(view.offset, 40);
+
+        view.bytes_per_row = 16;
+        view.clamp(100);
+        assert_eq!(view.offset, 32);
+
+        // Everything fits, no scrolling
+        view.page_rows = 20;
+        view.clamp(100);
+        assert_eq!(view.offset, 0);
+    }
+}
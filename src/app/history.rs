@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::collection::{Body, KeyValue, SavedRequest};
+
+use super::{BodyContentType, Request, Response};
+
+/// Exchanges kept for the session, the oldest are dropped first
+pub const HISTORY_LIMIT: usize = 200;
+
+/// How long the phases of an exchange took
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Timings {
+    /// Until the response headers arrived
+    pub wait: Duration,
+    /// Reading the response body
+    pub receive: Duration,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.wait + self.receive
+    }
+}
+
+/// A request as it was sent and the response it got
+#[derive(Clone, Debug)]
+pub struct Exchange {
+    /// With the variables replaced
+    pub request: SavedRequest,
+    pub response: Response,
+    pub started: DateTime<Utc>,
+    pub timings: Timings,
+}
+
+impl Exchange {
+    /// `200 GET https://example.com`
+    pub fn label(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.response.status_code, self.request.method, self.request.url
+        )
+    }
+}
+
+/// Sorted, as they come from a map
+fn sorted<'a>(values: impl Iterator<Item = (&'a String, &'a String)>) -> Vec<KeyValue> {
+    let mut values: Vec<KeyValue> = values
+        .map(|(name, value)| KeyValue::new(name, value))
+        .collect();
+
+    values.sort_by(|a, b| a.name.cmp(&b.name));
+    values
+}
+
+impl Request {
+    /// The request in the form of a collection request
+    pub fn to_saved(&self) -> SavedRequest {
+        let headers = sorted(self.headers.iter());
+
+        let body = match self.body_content_type {
+            BodyContentType::Text(_) if self.body.trim().is_empty() => None,
+            BodyContentType::Text(_) => Some(Body::Raw {
+                content_type: headers
+                    .iter()
+                    .find(|header| header.name.eq_ignore_ascii_case("content-type"))
+                    .map(|header| header.value.clone()),
+                text: self.body.clone(),
+            }),
+            BodyContentType::Form => Some(Body::Form {
+                fields: sorted(self.body_form.iter()),
+            }),
+        };
+
+        SavedRequest {
+            name: format!("{} {}", self.method, self.endpoint),
+            method: self.method.to_string(),
+            url: self.endpoint.clone(),
+            description: None,
+            headers,
+            query: self
+                .query_params
+                .iter()
+                .map(|(name, value)| KeyValue::new(name, value))
+                .collect(),
+            body,
+            auth: None,
+            operation: None,
+            examples: Vec::new(),
+        }
+    }
+}
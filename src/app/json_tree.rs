@@ -0,0 +1,404 @@
+use serde_json::Value;
+
+/// Past this many nodes only the first levels start expanded
+const EXPAND_ALL_LIMIT: usize = 2000;
+const INITIAL_DEPTH: usize = 2;
+
+#[derive(Clone)]
+pub enum NodeKey {
+    Root,
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Clone)]
+pub enum NodeKind {
+    Object(usize),
+    Array(usize),
+    String(String),
+    Number(String),
+    Bool(bool),
+    Null,
+}
+
+#[derive(Clone)]
+pub struct Node {
+    pub key: NodeKey,
+    pub kind: NodeKind,
+    pub depth: usize,
+    pub parent: Option<usize>,
+    /// Index right after the last descendant of this node
+    pub subtree_end: usize,
+}
+
+impl Node {
+    pub fn is_container(&self) -> bool {
+        matches!(self.kind, NodeKind::Object(_) | NodeKind::Array(_))
+    }
+}
+
+/// A foldable, flattened view of a JSON document
+#[derive(Clone)]
+pub struct JsonTree {
+    pub document: Value,
+    pub nodes: Vec<Node>,
+    pub collapsed: Vec<bool>,
+    /// Indexes into `nodes` of the rows currently shown
+    pub visible: Vec<usize>,
+    /// Selected row, as an index into `visible`
+    pub selected: usize,
+    pub offset: usize,
+}
+
+fn flatten(
+    value: &Value,
+    key: NodeKey,
+    depth: usize,
+    parent: Option<usize>,
+    nodes: &mut Vec<Node>,
+) {
+    let index = nodes.len();
+
+    let kind = match value {
+        Value::Object(map) => NodeKind::Object(map.len()),
+        Value::Array(items) => NodeKind::Array(items.len()),
+        Value::String(s) => NodeKind::String(s.clone()),
+        Value::Number(n) => NodeKind::Number(n.to_string()),
+        Value::Bool(b) => NodeKind::Bool(*b),
+        Value::Null => NodeKind::Null,
+    };
+
+    nodes.push(Node {
+        key,
+        kind,
+        depth,
+        parent,
+        subtree_end: index + 1,
+    });
+
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                flatten(
+                    child,
+                    NodeKey::Key(key.clone()),
+                    depth + 1,
+                    Some(index),
+                    nodes,
+                );
+            }
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                flatten(child, NodeKey::Index(i), depth + 1, Some(index), nodes);
+            }
+        }
+        _ => {}
+    }
+
+    nodes[index].subtree_end = nodes.len();
+}
+
+impl JsonTree {
+    pub fn new(document: Value) -> Self {
+        let mut nodes = Vec::new();
+
+        flatten(&document, NodeKey::Root, 0, None, &mut nodes);
+
+        let collapsed = nodes
+            .iter()
+            .map(|node| {
+                node.is_container() && nodes.len() > EXPAND_ALL_LIMIT && node.depth >= INITIAL_DEPTH
+            })
+            .collect();
+
+        let mut tree = Self {
+            document,
+            nodes,
+            collapsed,
+            visible: Vec::new(),
+            selected: 0,
+            offset: 0,
+        };
+
+        tree.refresh_visible();
+
+        tree
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        serde_json::from_str(text).ok().map(Self::new)
+    }
+
+    fn refresh_visible(&mut self) {
+        let selected_node = self.visible.get(self.selected).copied();
+
+        self.visible.clear();
+
+        let mut i = 0;
+
+        while i < self.nodes.len() {
+            self.visible.push(i);
+
+            i = if self.collapsed[i] {
+                self.nodes[i].subtree_end
+            } else {
+                i + 1
+            };
+        }
+
+        if let Some(node) = selected_node {
+            self.select_node(node);
+        }
+    }
+
+    fn select_node(&mut self, node: usize) {
+        if let Ok(position) = self.visible.binary_search(&node) {
+            self.selected = position;
+        }
+    }
+
+    pub fn selected_node(&self) -> usize {
+        self.visible[self.selected]
+    }
+
+    pub fn next(&mut self) {
+        if self.selected + 1 < self.visible.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn first(&mut self) {
+        self.selected = 0;
+    }
+
+    pub fn last(&mut self) {
+        self.selected = self.visible.len() - 1;
+    }
+
+    /// Fold the selected node, or go to its parent if it can't be folded
+    pub fn collapse(&mut self) {
+        let node = self.selected_node();
+
+        if self.nodes[node].is_container() && !self.collapsed[node] {
+            self.collapsed[node] = true;
+            self.refresh_visible();
+        } else if let Some(parent) = self.nodes[node].parent {
+            self.select_node(parent);
+        }
+    }
+
+    /// Unfold the selected node, or go to its first child if already unfolded
+    pub fn expand(&mut self) {
+        let node = self.selected_node();
+
+        if !self.nodes[node].is_container() {
+            return;
+        }
+
+        if self.collapsed[node] {
+            self.collapsed[node] = false;
+            self.refresh_visible();
+        } else if self.nodes[node].subtree_end > node + 1 {
+            self.select_node(node + 1);
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        let node = self.selected_node();
+
+        if self.nodes[node].is_container() {
+            self.collapsed[node] = !self.collapsed[node];
+            self.refresh_visible();
+        }
+    }
+
+    /// Fold every container except the root
+    pub fn collapse_all(&mut self) {
+        let selected = self.selected_node();
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            self.collapsed[i] = node.is_container() && node.depth > 0;
+        }
+
+        let mut top_level = selected;
+
+        while self.nodes[top_level].depth > 1 {
+            top_level = self.nodes[top_level].parent.unwrap();
+        }
+
+        self.refresh_visible();
+        self.select_node(top_level);
+    }
+
+    pub fn expand_all(&mut self) {
+        self.collapsed.iter_mut().for_each(|c| *c = false);
+        self.refresh_visible();
+    }
+
+    fn ancestry(&self, node: usize) -> Vec<usize> {
+        let mut path = vec![node];
+        let mut current = node;
+
+        while let Some(parent) = self.nodes[current].parent {
+            path.push(parent);
+            current = parent;
+        }
+
+        path.reverse();
+        path
+    }
+
+    /// JSONPath of a node, e.g. `$.items[0].name`
+    pub fn path(&self, node: usize) -> String {
+        self.ancestry(node)
+            .iter()
+            .map(|i| match &self.nodes[*i].key {
+                NodeKey::Root => "$".to_string(),
+                NodeKey::Index(index) => format!("[{index}]"),
+                NodeKey::Key(key)
+                    if !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_') =>
+                {
+                    format!(".{key}")
+                }
+                NodeKey::Key(key) => format!("[{}]", Value::String(key.clone())),
+            })
+            .collect()
+    }
+
+    /// The value of a node, pretty printed
+    pub fn value(&self, node: usize) -> String {
+        let pointer: String = self
+            .ancestry(node)
+            .iter()
+            .map(|i| match &self.nodes[*i].key {
+                NodeKey::Root => String::new(),
+                NodeKey::Index(index) => format!("/{index}"),
+                NodeKey::Key(key) => format!("/{}", key.replace('~', "~0").replace('/', "~1")),
+            })
+            .collect();
+
+        match self.document.pointer(&pointer) {
+            Some(Value::String(s)) => s.clone(),
+            Some(value) => format!("{:#}", value),
+            None => String::new(),
+        }
+    }
+
+    /// Keep the selected row inside a viewport of `height` rows
+    pub fn scroll_to_selected(&mut self, height: usize) {
+        if height == 0 {
+            return;
+        }
+
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + height {
+            self.offset = self.selected + 1 - height;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = r#"{"items": [{"id": 1, "tags": ["a"]}, {"id": 2}], "x y": null}"#;
+
+    fn tree() -> JsonTree {
+        JsonTree::parse(DOCUMENT).unwrap()
+    }
+
+    /// Paths of the rows shown
+    fn rows(tree: &JsonTree) -> Vec<String> {
+        tree.visible.iter().map(|node| tree.path(*node)).collect()
+    }
+
+    fn select(tree: &mut JsonTree, path: &str) {
+        tree.selected = rows(tree).iter().position(|row| row == path).unwrap();
+    }
+
+    #[test]
+    fn starts_expanded() {
+        assert_eq!(
+            rows(&tree()),
+            vec![
+                "$",
+                "$.items",
+                "$.items[0]",
+                "$.items[0].id",
+                "$.items[0].tags",
+                "$.items[0].tags[0]",
+                "$.items[1]",
+                "$.items[1].id",
+                "$[\"x y\"]",
+            ]
+        );
+    }
+
+    #[test]
+    fn collapses_and_expands_the_selected_node() {
+        let mut tree = tree();
+
+        select(&mut tree, "$.items[0]");
+        tree.collapse();
+
+        assert_eq!(rows(&tree)[2..4], ["$.items[0]", "$.items[1]"]);
+        assert_eq!(tree.path(tree.selected_node()), "$.items[0]");
+
+        // Already folded, goes to the parent
+        tree.collapse();
+        assert_eq!(tree.path(tree.selected_node()), "$.items");
+
+        select(&mut tree, "$.items[0]");
+        tree.expand();
+        assert_eq!(rows(&tree).len(), 9);
+
+        // Already unfolded, goes to the first child
+        tree.expand();
+        assert_eq!(tree.path(tree.selected_node()), "$.items[0].id");
+    }
+
+    #[test]
+    fn collapse_all_keeps_the_top_level_selected() {
+        let mut tree = tree();
+
+        select(&mut tree, "$.items[0].tags[0]");
+        tree.collapse_all();
+
+        assert_eq!(rows(&tree), vec!["$", "$.items", "$[\"x y\"]"]);
+        assert_eq!(tree.path(tree.selected_node()), "$.items");
+
+        tree.expand_all();
+        assert_eq!(rows(&tree).len(), 9);
+    }
+
+    #[test]
+    fn copies_values() {
+        let tree = tree();
+
+        assert_eq!(
+            tree.value(2),
+            "{\n  \"id\": 1,\n  \"tags\": [\n    \"a\"\n  ]\n}"
+        );
+        assert_eq!(tree.value(5), "a");
+        assert_eq!(tree.value(8), "null");
+    }
+
+    #[test]
+    fn scrolls_to_the_selected_row() {
+        let mut tree = tree();
+
+        tree.last();
+        tree.scroll_to_selected(4);
+        assert_eq!(tree.offset, 5);
+
+        tree.first();
+        tree.scroll_to_selected(4);
+        assert_eq!(tree.offset, 0);
+    }
+}
@@ -1,12 +1,49 @@
+pub mod areas;
+pub mod diff;
+pub mod filter;
 pub mod form;
+pub mod help;
+pub mod hex;
+pub mod history;
+pub mod json_tree;
+pub mod palette;
+pub mod picker;
+pub mod search;
 
 use crate::event::input::Input;
+use areas::Areas;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use cached::proc_macro::cached;
+use diff::{Diff, Source, TRANSPORT_HEADERS};
+use filter::filter_response;
 use form::Form;
-use std::{collections::HashMap, str::FromStr};
+use help::Help;
+use hex::HexView;
+use history::{Exchange, HISTORY_LIMIT};
+use json_tree::JsonTree;
+use palette::Palette;
+use percent_encoding::percent_decode_str;
+use picker::Picker;
+use search::{find_matches, Search, SearchMatch};
+use std::{collections::HashMap, fmt::Display, path::PathBuf, str::FromStr};
 
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 
-use crate::request;
+use crate::{
+    collection::{
+        substitute, ApiKeyLocation, Auth, Body, Collection, Example, KeyValue, SavedRequest,
+    },
+    config::{
+        keymap::{Key, KeyMap},
+        layout::PaneLayout,
+        theme::Theme,
+    },
+    content_type::{self, ContentKind},
+    contract::Contract,
+    http_file::HttpFile,
+    pretty, request,
+    validation::{validate_body, BodyError},
+};
 
 #[derive(PartialEq)]
 pub enum InputMode {
@@ -23,14 +60,14 @@ pub enum RequestMethod {
     Delete,
 }
 
-impl ToString for RequestMethod {
-    fn to_string(&self) -> String {
+impl Display for RequestMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Get => "GET".to_string(),
-            Self::Post => "POST".to_string(),
-            Self::Put => "PUT".to_string(),
-            Self::Delete => "DELETE".to_string(),
-            Self::Patch => "PATCH".to_string(),
+            Self::Get => write!(f, "GET"),
+            Self::Post => write!(f, "POST"),
+            Self::Put => write!(f, "PUT"),
+            Self::Delete => write!(f, "DELETE"),
+            Self::Patch => write!(f, "PATCH"),
         }
     }
 }
@@ -60,7 +97,7 @@ pub trait OrderNavigation: Clone + PartialEq {
     {
         let order = self.get_order();
 
-        return order[(order.iter().position(|r| r == self).unwrap() + 1) % order.len()].clone();
+        order[(order.iter().position(|r| r == self).unwrap() + 1) % order.len()].clone()
     }
     fn previous(&self) -> Self
     where
@@ -74,7 +111,7 @@ pub trait OrderNavigation: Clone + PartialEq {
             return order[order.len() - 1].clone();
         }
 
-        return order[index - 1].clone();
+        order[index - 1].clone()
     }
 
     fn get_index(&self) -> usize
@@ -97,15 +134,24 @@ pub enum AppBlock {
     Method,
     Request,
     RequestContent,
+    ResponseFilter,
     Response,
 }
 
+#[derive(Clone, PartialEq)]
+pub enum ResponseView {
+    Text,
+    Tree,
+}
+
 #[derive(Clone, PartialEq)]
 pub enum RequestTab {
     Body,
     Query,
     Headers,
+    #[allow(dead_code)]
     Auth,
+    #[allow(dead_code)]
     Cookies,
 }
 
@@ -136,30 +182,169 @@ impl OrderNavigation for AppBlock {
             Self::Endpoint,
             Self::Request,
             Self::RequestContent,
+            Self::ResponseFilter,
             Self::Response,
         ]
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Response {
     pub status_code: u16,
     pub content_type: String,
     pub text: String,
+    pub headers: Vec<(String, String)>,
+    /// Raw bytes of the body, as received
+    pub body: Vec<u8>,
+    pub url: String,
+    /// `HTTP/1.1`
+    pub http_version: String,
+    /// Shown as a hex dump, `text` is left empty
+    pub is_binary: bool,
 }
 
-#[derive(Clone)]
+impl Response {
+    /// An example shown as if it was received
+    pub fn from_example(example: &Example, url: &str) -> Self {
+        let headers: Vec<(String, String)> = example
+            .headers
+            .iter()
+            .filter(|header| header.enabled)
+            .map(|header| (header.name.clone(), header.value.clone()))
+            .collect();
+
+        let content_type = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+            .map(|(_, value)| value.to_lowercase())
+            .unwrap_or_default();
+
+        Self {
+            status_code: example.status,
+            content_type,
+            text: example.body.clone(),
+            headers,
+            body: example.body.clone().into_bytes(),
+            url: url.to_string(),
+            http_version: String::new(),
+            is_binary: false,
+        }
+    }
+
+    /// The response saved as an example, without the headers about the
+    /// exchange itself
+    pub fn to_example(&self, name: &str) -> Example {
+        Example {
+            name: name.to_string(),
+            status: self.status_code,
+            headers: self
+                .headers
+                .iter()
+                .filter(|(name, _)| !TRANSPORT_HEADERS.contains(&name.to_lowercase().as_str()))
+                .map(|(name, value)| KeyValue::new(name, value))
+                .collect(),
+            body: self.text.clone(),
+            delay: None,
+        }
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn kind(&self) -> ContentKind {
+        if self.is_binary {
+            return ContentKind::Binary;
+        }
+
+        content_type::classify(&self.content_type)
+    }
+
+    /// The body reformatted for its content type
+    pub fn pretty_text(&self) -> String {
+        let delimiter = match content_type::essence(&self.content_type).as_str() {
+            "text/tab-separated-values" => '\t',
+            _ => pretty::sniff_delimiter(&self.text),
+        };
+
+        pretty::prettify(self.text.clone(), self.kind(), delimiter)
+    }
+
+    /// File extension matching the content type, without the dot
+    pub fn extension(&self) -> &'static str {
+        match content_type::essence(&self.content_type).as_str() {
+            "application/json" => "json",
+            "application/xml" | "text/xml" => "xml",
+            "text/html" => "html",
+            "text/css" => "css",
+            "text/csv" => "csv",
+            "application/javascript" | "text/javascript" => "js",
+            "application/yaml" | "application/x-yaml" | "text/yaml" => "yaml",
+            "application/pdf" => "pdf",
+            "application/zip" => "zip",
+            "image/png" => "png",
+            "image/jpeg" => "jpg",
+            "image/gif" => "gif",
+            "image/webp" => "webp",
+            "image/svg+xml" => "svg",
+            "text/plain" => "txt",
+            _ => "bin",
+        }
+    }
+
+    /// File name to save the body as, taken from `Content-Disposition` or
+    /// the last segment of the URL
+    pub fn suggested_filename(&self) -> String {
+        let from_disposition = self.header("content-disposition").and_then(|disposition| {
+            let params: Vec<(&str, &str)> = disposition
+                .split(';')
+                .filter_map(|param| param.trim().split_once('='))
+                .collect();
+
+            let extended = params
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case("filename*"))
+                .and_then(|(_, value)| value.split("''").nth(1))
+                .map(|value| percent_decode_str(value).decode_utf8_lossy().to_string());
+
+            extended.or_else(|| {
+                params
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case("filename"))
+                    .map(|(_, value)| value.trim_matches('"').to_string())
+            })
+        });
+
+        let name = from_disposition
+            .or_else(|| {
+                url::Url::parse(&self.url)
+                    .ok()?
+                    .path_segments()?
+                    .rfind(|segment| !segment.is_empty())
+                    .map(|segment| segment.to_string())
+            })
+            // Never let the server pick a path outside the working directory
+            .map(|name| name.rsplit(['/', '\\']).next().unwrap_or("").to_string())
+            .filter(|name| !name.is_empty() && name != "." && name != "..")
+            .unwrap_or_else(|| "response".to_string());
+
+        if name.contains('.') {
+            name
+        } else {
+            format!("{name}.{}", self.extension())
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
 pub struct Coordinates {
     pub x: u16,
     pub y: u16,
 }
 
-impl Default for Coordinates {
-    fn default() -> Self {
-        Self { x: 0, y: 0 }
-    }
-}
-
 #[derive(Clone)]
 pub enum BodyType {
     Json,
@@ -167,15 +352,54 @@ pub enum BodyType {
     Xml,
 }
 
+impl BodyType {
+    pub fn kind(&self) -> ContentKind {
+        match self {
+            Self::Json => ContentKind::Json,
+            Self::Raw => ContentKind::Text,
+            Self::Xml => ContentKind::Xml,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub enum BodyContentType {
     Text(BodyType),
     Form,
 }
 
+/// What is written once replacing a file is confirmed
+#[derive(Clone, Copy)]
+pub enum SaveTarget {
+    Response,
+    /// The session history, as HAR
+    History,
+}
+
 pub enum AppPopup {
     ChangeMethod,
     FormPopup(Form),
+    /// Asks before sending a body that doesn't validate
+    ConfirmSend(String),
+    /// Asks before replacing the file the response or the history is saved
+    /// to
+    ConfirmOverwrite(SaveTarget, PathBuf),
+    Help(Help),
+    CommandPalette(Palette),
+    /// Requests or environments of the collection
+    Picker(Picker),
+    Diff(Diff),
+}
+
+/// `Basic user:password` base64-encoded, other values as they are
+fn encode_basic_auth(value: String) -> String {
+    match value.strip_prefix("Basic ") {
+        // Encoded credentials never contain a colon
+        Some(credentials) if credentials.contains(':') => {
+            format!("Basic {}", STANDARD.encode(credentials))
+        }
+        _ => value,
+    }
 }
 
 pub struct Request {
@@ -189,16 +413,148 @@ pub struct Request {
 }
 
 impl Request {
-    pub fn from_app(app: &App) -> Self {
+    /// A saved request as it is edited, its variables kept, with what
+    /// couldn't be converted. Methods that can't be sent are left as `GET`
+    pub fn from_saved(request: &SavedRequest) -> (Self, Vec<String>) {
+        let mut problems = Vec::new();
+
+        let method = RequestMethod::from_str(&request.method).unwrap_or_else(|_| {
+            problems.push(format!("{} requests can't be sent", request.method));
+            RequestMethod::Get
+        });
+
+        let enabled = |values: &[KeyValue]| -> Vec<(String, String)> {
+            values
+                .iter()
+                .filter(|value| value.enabled)
+                .map(|value| (value.name.clone(), value.value.clone()))
+                .collect()
+        };
+
+        let mut headers: HashMap<String, String> = enabled(&request.headers).into_iter().collect();
+        let mut query_params = enabled(&request.query);
+        let mut body = String::new();
+        let mut body_form = HashMap::new();
+        let mut body_content_type = BodyContentType::Text(BodyType::Json);
+
+        match &request.body {
+            Some(Body::Raw { content_type, text }) => {
+                body = text.clone();
+
+                let kind = content_type.as_deref().map(content_type::classify);
+
+                body_content_type = BodyContentType::Text(match kind {
+                    Some(ContentKind::Json) => BodyType::Json,
+                    Some(ContentKind::Xml) => BodyType::Xml,
+                    _ => BodyType::Raw,
+                });
+
+                let has_content_type = headers
+                    .keys()
+                    .any(|name| name.eq_ignore_ascii_case("content-type"));
+
+                if let (Some(content_type), false) = (content_type, has_content_type) {
+                    headers.insert("Content-Type".to_string(), content_type.clone());
+                }
+            }
+            Some(Body::Form { fields }) => {
+                body_form = enabled(fields).into_iter().collect();
+                body_content_type = BodyContentType::Form;
+            }
+            Some(Body::Multipart { fields }) => {
+                body_form = enabled(fields).into_iter().collect();
+                body_content_type = BodyContentType::Form;
+
+                problems.push("the multipart form is sent url-encoded".to_string());
+            }
+            None => {}
+        }
+
+        match &request.auth {
+            // Encoded when sent, once its variables are replaced
+            Some(Auth::Basic { username, password }) => {
+                headers.insert(
+                    "Authorization".to_string(),
+                    format!("Basic {username}:{password}"),
+                );
+            }
+            Some(Auth::Bearer { token }) => {
+                headers.insert("Authorization".to_string(), format!("Bearer {token}"));
+            }
+            Some(Auth::ApiKey {
+                name,
+                value,
+                location,
+            }) => match location {
+                ApiKeyLocation::Header => {
+                    headers.insert(name.clone(), value.clone());
+                }
+                ApiKeyLocation::Query => query_params.push((name.clone(), value.clone())),
+            },
+            None => {}
+        }
+
+        let request = Self {
+            method,
+            endpoint: request.url.clone(),
+            headers,
+            query_params,
+            body,
+            body_content_type,
+            body_form,
+        };
+
+        (request, problems)
+    }
+
+    /// The request with its variables replaced, ready to send
+    pub fn substitute(&self, variables: &HashMap<String, String>) -> Self {
+        let apply = |text: &String| substitute(text, variables);
+
         Self {
+            method: self.method.clone(),
+            endpoint: apply(&self.endpoint),
+            headers: self
+                .headers
+                .iter()
+                .map(|(name, value)| {
+                    let value = match name.eq_ignore_ascii_case("authorization") {
+                        true => encode_basic_auth(apply(value)),
+                        false => apply(value),
+                    };
+
+                    (apply(name), value)
+                })
+                .collect(),
+            body: apply(&self.body),
+            query_params: self
+                .query_params
+                .iter()
+                .map(|(name, value)| (apply(name), apply(value)))
+                .collect(),
+            body_content_type: self.body_content_type.clone(),
+            body_form: self
+                .body_form
+                .iter()
+                .map(|(name, value)| (apply(name), apply(value)))
+                .collect(),
+        }
+    }
+
+    /// The request being edited, with the variables of the collection
+    /// replaced
+    pub fn from_app(app: &App) -> Self {
+        let request = Self {
             method: app.method.clone(),
             endpoint: app.endpoint.text.clone(),
             headers: app.headers.clone(),
-            body: app.raw_body.text.clone(),
             query_params: app.query_params.clone(),
+            body: app.raw_body.text.clone(),
             body_content_type: app.body_content_type.clone(),
             body_form: app.body_form.clone(),
-        }
+        };
+
+        request.substitute(&app.variables())
     }
 }
 
@@ -217,8 +573,11 @@ pub struct App {
     pub selected_query_param: u16,
 
     pub response: Option<Response>,
+    /// Changes with every response shown, what is displayed of it is cached
+    /// by it rather than by its text
+    pub response_id: u64,
 
-    pub res_rx: Receiver<Option<Response>>,
+    pub res_rx: Receiver<Result<Exchange, String>>,
     pub req_tx: Sender<Request>,
     pub is_loading: bool,
 
@@ -228,18 +587,305 @@ pub struct App {
     pub query_params: Vec<(String, String)>,
     pub response_scroll: (u16, u16),
 
+    pub response_filter: Input,
+    /// Filters typed for each request, keyed by [`App::request_key`]
+    pub response_filters: HashMap<String, String>,
+
+    pub response_view: ResponseView,
+    /// Show the body reformatted instead of as received
+    pub response_pretty: bool,
+    pub response_tree: Option<JsonTree>,
+    pub response_search: Search,
+    pub response_hex: HexView,
+
+    /// Feedback for the last action, shown in the help bar
+    pub status_message: Option<String>,
+
+    pub keymap: KeyMap,
+    /// Keys typed so far of a multi-key binding
+    pub pending_keys: Vec<Key>,
+    pub should_quit: bool,
+
+    pub theme: Theme,
+    pub areas: Areas,
+    pub layout: PaneLayout,
+
+    /// Set after an external program took over the terminal
+    pub needs_redraw: bool,
+
     pub body_form: HashMap<String, String>,
     pub selected_form_field: u16,
 
     pub popup: Option<AppPopup>,
+
+    /// The collection opened with `--collection`
+    pub collection: Option<Collection>,
+    pub collection_path: Option<PathBuf>,
+    /// Index of the active environment of the collection
+    pub environment: Option<usize>,
+    /// Index of the request of the collection being edited
+    pub saved_request: Option<usize>,
+    /// The spec of the collection, responses are checked against it
+    pub contract: Option<Contract>,
+    /// The operation of the spec the current request was loaded from,
+    /// preferred while its method and URL still match it
+    pub operation: Option<String>,
+    /// What is wrong with the response according to the spec, if checked
+    pub violations: Option<Vec<String>>,
+
+    /// The `.http` file opened instead of a URL
+    pub http_file: Option<HttpFile>,
+    /// Index of the request of the file being edited
+    pub http_request: Option<usize>,
+
+    /// Exchanges of the session, or of the HAR file opened, oldest first
+    pub history: Vec<Exchange>,
+    /// The first response picked to compare, while the second is picked
+    pub comparing: Option<Source>,
+}
+
+/// The response shown, whether it's pretty printed and the filter applied
+pub type DisplayKey = (u64, bool, String);
+
+#[cached(size = 8, key = "DisplayKey", convert = "{ app.display_key() }")]
+fn display_response(app: &App) -> (String, Option<String>) {
+    match app.response.as_ref() {
+        Some(r) if app.response_filter.text.trim().is_empty() && app.response_pretty => {
+            (r.pretty_text(), None)
+        }
+        Some(r) if app.response_filter.text.trim().is_empty() => (r.text.clone(), None),
+        Some(r) if r.kind() == ContentKind::Json => {
+            match filter_response(r.text.clone(), app.response_filter.text.clone()) {
+                Ok(text) => (text, None),
+                Err(e) => (r.text.clone(), Some(e)),
+            }
+        }
+        Some(r) => (
+            r.text.clone(),
+            Some("filters only apply to JSON responses".to_string()),
+        ),
+        None => (String::new(), None),
+    }
+}
+
+#[cached(
+    size = 8,
+    key = "(DisplayKey, String)",
+    convert = "{ (app.display_key(), app.response_search.input.text.clone()) }"
+)]
+fn search_response(app: &App) -> Result<Vec<SearchMatch>, String> {
+    find_matches(
+        app.response_display().0,
+        app.response_search.input.text.clone(),
+    )
+}
+
+impl App {
+    /// Identifies the current request, used to remember per-request state
+    pub fn request_key(&self) -> String {
+        format!("{} {}", self.method, self.endpoint.text)
+    }
+
+    /// Remember the current response filter for the current request
+    pub fn save_response_filter(&mut self) {
+        self.response_filters
+            .insert(self.request_key(), self.response_filter.text.clone());
+    }
+
+    /// Restore the response filter previously used with the current request
+    pub fn restore_response_filter(&mut self) {
+        self.response_filter.text = self
+            .response_filters
+            .get(&self.request_key())
+            .cloned()
+            .unwrap_or_default();
+
+        self.response_filter.move_cursor_to_end_single_line();
+    }
+
+    /// What the displayed response depends on, cheaper to compare than its
+    /// text
+    pub fn display_key(&self) -> DisplayKey {
+        (
+            self.response_id,
+            self.response_pretty,
+            self.response_filter.text.clone(),
+        )
+    }
+
+    /// The response text as displayed, with the filter applied, and the
+    /// filter error if any
+    pub fn response_display(&self) -> (String, Option<String>) {
+        display_response(self)
+    }
+
+    /// Matches of the current search in the displayed response
+    pub fn response_search_matches(&self) -> Result<Vec<SearchMatch>, String> {
+        if !self.response_search.is_active() {
+            return Ok(Vec::new());
+        }
+
+        search_response(self)
+    }
+
+    /// Variables of the collection and the active environment, then those
+    /// of the `.http` file
+    pub fn variables(&self) -> HashMap<String, String> {
+        let mut variables = self
+            .collection
+            .as_ref()
+            .map(|collection| collection.variables(self.environment))
+            .unwrap_or_default();
+
+        if let Some(file) = self.http_file.as_ref() {
+            variables.extend(file.variables());
+        }
+
+        variables
+    }
+
+    /// Show a saved request in the editor, returns what couldn't be loaded
+    pub fn load_request(&mut self, request: &SavedRequest) -> Vec<String> {
+        let (loaded, problems) = Request::from_saved(request);
+
+        self.save_response_filter();
+
+        // Methods that can't be sent leave the method as it was
+        if RequestMethod::from_str(&request.method).is_ok() {
+            self.method = loaded.method;
+        }
+
+        self.endpoint.set_text(loaded.endpoint);
+        self.endpoint.move_cursor_to_end_single_line();
+
+        self.operation = request.operation.clone();
+
+        self.headers = loaded.headers;
+        self.query_params = loaded.query_params;
+        self.raw_body.set_text(loaded.body);
+        self.body_content_type = loaded.body_content_type;
+        self.body_form = loaded.body_form;
+
+        self.selected_header = 0;
+        self.selected_query_param = 0;
+        self.selected_form_field = 0;
+
+        self.response = None;
+        self.response_id += 1;
+        self.response_scroll = (0, 0);
+        self.response_tree = None;
+        self.response_search = Search::default();
+        self.response_hex = HexView::default();
+        self.restore_response_filter();
+
+        problems
+    }
+
+    /// Validation error of the text body, if any
+    pub fn body_error(&self) -> Option<BodyError> {
+        match &self.body_content_type {
+            BodyContentType::Text(body_type) => {
+                validate_body(self.raw_body.text.clone(), body_type.kind()).err()
+            }
+            BodyContentType::Form => None,
+        }
+    }
+
+    /// Reformat the text body, leaving it untouched if it doesn't parse
+    pub fn format_body(&mut self) {
+        let BodyContentType::Text(body_type) = &self.body_content_type else {
+            return;
+        };
+
+        if let Some(error) = self.body_error() {
+            self.status_message = Some(format!("Can't format an invalid body: {error}"));
+            return;
+        }
+
+        let formatted = match body_type {
+            BodyType::Json => serde_json::from_str::<serde_json::Value>(&self.raw_body.text)
+                .map(|value| format!("{:#}", value))
+                .ok(),
+            BodyType::Xml => Some(
+                pretty::prettify(self.raw_body.text.clone(), ContentKind::Xml, ',')
+                    .trim_end()
+                    .to_string(),
+            ),
+            BodyType::Raw => None,
+        };
+
+        if let Some(formatted) = formatted {
+            self.raw_body.set_text(formatted);
+        }
+    }
+
+    /// Show the response of a request sent, keeping it in the history
+    pub fn receive_response(&mut self, result: Result<Exchange, String>) {
+        self.is_loading = false;
+
+        match result {
+            Ok(exchange) => {
+                self.show_response(Some(exchange.response.clone()));
+                self.history.push(exchange);
+
+                if self.history.len() > HISTORY_LIMIT {
+                    self.history.remove(0);
+                }
+            }
+            Err(e) => {
+                self.show_response(None);
+                self.status_message = Some(format!("Request failed: {e}"));
+            }
+        }
+    }
+
+    pub fn show_response(&mut self, response: Option<Response>) {
+        // The request may have been edited since it was imported
+        let url = substitute(&self.endpoint.text, &self.variables());
+        let operation = self.contract.as_ref().and_then(|contract| {
+            contract.operation(&self.method.to_string(), &url, self.operation.as_deref())
+        });
+
+        self.violations = match (&self.contract, operation, &response) {
+            (Some(contract), Some(operation), Some(response)) => {
+                Some(contract.check(&operation, response))
+            }
+            _ => None,
+        };
+
+        self.response = response;
+        self.response_id += 1;
+        self.restore_response_filter();
+
+        self.response_tree = None;
+        self.response_hex = HexView::default();
+
+        if self.response_view == ResponseView::Tree {
+            self.build_response_tree();
+        }
+    }
+
+    /// Parse the current response into a tree, falling back to the text view
+    /// when it isn't JSON
+    pub fn build_response_tree(&mut self) {
+        self.response_tree = self
+            .response
+            .as_ref()
+            .filter(|r| r.kind() == ContentKind::Json)
+            .and_then(|r| JsonTree::parse(&r.text));
+
+        if self.response_tree.is_none() {
+            self.response_view = ResponseView::Text;
+        }
+    }
 }
 
-fn handle_requests(mut req_rx: Receiver<Request>, res_tx: Sender<Option<Response>>) {
+fn handle_requests(mut req_rx: Receiver<Request>, res_tx: Sender<Result<Exchange, String>>) {
     tokio::spawn(async move {
         while let Some(req) = req_rx.recv().await {
             let res = request::send(req).await;
 
-            res_tx.send(Some(res)).await.unwrap();
+            res_tx.send(res).await.unwrap();
         }
     });
 }
@@ -255,10 +901,7 @@ impl Default for App {
 
         Self {
             input_mode: InputMode::Normal,
-            endpoint: Input {
-                text: String::from("https://httpbin.org/get"),
-                ..Input::default()
-            },
+            endpoint: Input::new("https://httpbin.org/get"),
             selected_header: 0,
             selected_query_param: 0,
             query_params: Vec::new(),
@@ -271,8 +914,35 @@ impl Default for App {
             request_tab: RequestTab::Body,
             selected_block: AppBlock::Endpoint,
             response: None,
+            response_id: 0,
             response_scroll: (0, 0),
+            response_filter: Input::default(),
+            response_filters: HashMap::new(),
+            response_view: ResponseView::Text,
+            response_pretty: true,
+            response_tree: None,
+            response_search: Search::default(),
+            response_hex: HexView::default(),
+            status_message: None,
+            keymap: KeyMap::default(),
+            pending_keys: Vec::new(),
+            should_quit: false,
+            theme: Theme::default(),
+            areas: Areas::default(),
+            layout: PaneLayout::default(),
+            needs_redraw: false,
             popup: None,
+            collection: None,
+            collection_path: None,
+            environment: None,
+            saved_request: None,
+            contract: None,
+            operation: None,
+            violations: None,
+            http_file: None,
+            http_request: None,
+            history: Vec::new(),
+            comparing: None,
             body_form: HashMap::new(),
             selected_form_field: 0,
             body_content_type: BodyContentType::Text(BodyType::Json),
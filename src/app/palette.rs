@@ -0,0 +1,97 @@
+use crate::{config::keymap::Action, event::input::Input};
+
+#[derive(Clone)]
+pub struct PaletteEntry {
+    pub action: Action,
+    /// Keys bound to the action, may be empty
+    pub keys: String,
+}
+
+/// Fuzzy finder over the actions available where it was opened
+pub struct Palette {
+    pub input: Input,
+    pub entries: Vec<PaletteEntry>,
+    /// Index in the filtered entries
+    pub selected: usize,
+}
+
+impl Palette {
+    pub fn new(entries: Vec<PaletteEntry>) -> Self {
+        Self {
+            input: Input::default(),
+            entries,
+            selected: 0,
+        }
+    }
+
+    /// Entries matching the query, best first
+    pub fn matches(&self) -> Vec<&PaletteEntry> {
+        let query = self.input.text.trim();
+
+        let mut scored: Vec<_> = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let score = [entry.action.description(), entry.action.name()]
+                    .into_iter()
+                    .filter_map(|text| fuzzy_score(query, text))
+                    .max()?;
+
+                Some((score, entry))
+            })
+            .collect();
+
+        // Stable, ties keep the order of the entries
+        scored.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        scored.into_iter().map(|(_, entry)| entry).collect()
+    }
+
+    pub fn selected_action(&self) -> Option<Action> {
+        self.matches().get(self.selected).map(|entry| entry.action)
+    }
+
+    pub fn next(&mut self) {
+        let count = self.matches().len();
+
+        if self.selected + 1 < count {
+            self.selected += 1;
+        }
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+/// Score `text` against a fuzzy `query` whose characters must all appear
+/// in it in order. Characters next to each other or starting a word score
+/// higher, gaps lower
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for c in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let index = position + text[position..].iter().position(|t| *t == c)?;
+
+        score += 1;
+
+        if previous.is_some_and(|p| p + 1 == index) {
+            score += 5;
+        }
+
+        if index == 0 || !text[index - 1].is_alphanumeric() {
+            score += 3;
+        }
+
+        score -= i64::try_from(index - position).unwrap_or(i64::MAX).min(5);
+
+        previous = Some(index);
+        position = index + 1;
+    }
+
+    Some(score)
+}
@@ -0,0 +1,96 @@
+use crate::event::input::Input;
+
+use super::palette::fuzzy_score;
+
+/// What picking an item does
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PickerKind {
+    /// Load a request of the collection
+    Request,
+    /// Switch the environment of the collection
+    Environment,
+    /// Load a request of the `.http` file
+    FileRequest,
+    /// Load a request of the history with its response
+    History,
+    /// Show an example of the request of the collection
+    Example,
+    /// Compare the response with an example
+    DiffExample,
+    /// The first of two responses to compare
+    CompareFirst,
+    /// The response to compare the first one with
+    CompareSecond,
+}
+
+#[derive(Clone)]
+pub struct PickerItem {
+    pub label: String,
+    /// Shown muted after the label
+    pub detail: String,
+    /// Index of the item in what it was picked from
+    pub value: Option<usize>,
+}
+
+/// Fuzzy finder over a list, like the command palette
+pub struct Picker {
+    pub kind: PickerKind,
+    pub title: String,
+    pub input: Input,
+    pub items: Vec<PickerItem>,
+    /// Index in the filtered items
+    pub selected: usize,
+}
+
+impl Picker {
+    pub fn new(kind: PickerKind, title: &str, items: Vec<PickerItem>) -> Self {
+        Self {
+            kind,
+            title: title.to_string(),
+            input: Input::default(),
+            items,
+            selected: 0,
+        }
+    }
+
+    /// Items matching the query, best first
+    pub fn matches(&self) -> Vec<&PickerItem> {
+        let query = self.input.text.trim();
+
+        let mut scored: Vec<_> = self
+            .items
+            .iter()
+            .filter_map(|item| {
+                let score = [item.label.as_str(), item.detail.as_str()]
+                    .into_iter()
+                    .filter_map(|text| fuzzy_score(query, text))
+                    .max()?;
+
+                Some((score, item))
+            })
+            .collect();
+
+        // Stable, ties keep the order of the items
+        scored.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        scored.into_iter().map(|(_, item)| item).collect()
+    }
+
+    pub fn selected_item(&self) -> Option<PickerItem> {
+        self.matches()
+            .get(self.selected)
+            .map(|item| (*item).clone())
+    }
+
+    pub fn next(&mut self) {
+        let count = self.matches().len();
+
+        if self.selected + 1 < count {
+            self.selected += 1;
+        }
+    }
+
+    pub fn previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
@@ -0,0 +1,176 @@
+use regex::RegexBuilder;
+
+use crate::event::input::Input;
+
+/// Lines kept above a match when scrolling to it
+const SCROLL_CONTEXT: usize = 3;
+
+#[derive(Clone)]
+pub struct SearchMatch {
+    pub line: usize,
+    /// Byte range of the match within the line
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Clone, Default)]
+pub struct Search {
+    pub input: Input,
+    /// Index of the focused match
+    pub current: usize,
+    /// Scroll position when the search started, incremental matches are
+    /// looked up from here
+    pub origin: u16,
+}
+
+impl Search {
+    pub fn is_active(&self) -> bool {
+        !self.input.text.is_empty()
+    }
+
+    /// Focus the first match at or after the line the search started from,
+    /// returning the line to scroll to
+    pub fn focus_from_origin(&mut self, matches: &[SearchMatch]) -> Option<u16> {
+        if matches.is_empty() {
+            return None;
+        }
+
+        self.current = matches
+            .iter()
+            .position(|m| m.line >= usize::from(self.origin))
+            .unwrap_or(0);
+
+        Some(scroll_position(&matches[self.current]))
+    }
+
+    pub fn focus_next(&mut self, matches: &[SearchMatch]) -> Option<u16> {
+        if matches.is_empty() {
+            return None;
+        }
+
+        self.current = (self.current + 1) % matches.len();
+
+        Some(scroll_position(&matches[self.current]))
+    }
+
+    pub fn focus_previous(&mut self, matches: &[SearchMatch]) -> Option<u16> {
+        if matches.is_empty() {
+            return None;
+        }
+
+        self.current = if self.current == 0 || self.current >= matches.len() {
+            matches.len() - 1
+        } else {
+            self.current - 1
+        };
+
+        Some(scroll_position(&matches[self.current]))
+    }
+}
+
+fn scroll_position(m: &SearchMatch) -> u16 {
+    u16::try_from(m.line.saturating_sub(SCROLL_CONTEXT)).unwrap_or(u16::MAX)
+}
+
+/// Find every match of a regex in the text, case insensitive unless the
+/// query contains an uppercase letter
+pub fn find_matches(text: String, query: String) -> Result<Vec<SearchMatch>, String> {
+    let regex = RegexBuilder::new(&query)
+        .case_insensitive(!query.chars().any(char::is_uppercase))
+        .build()
+        .map_err(|_| "invalid regex".to_string())?;
+
+    Ok(text
+        .lines()
+        .enumerate()
+        .flat_map(|(line, content)| {
+            regex
+                .find_iter(content)
+                .filter(|m| !m.is_empty())
+                .map(move |m| SearchMatch {
+                    line,
+                    start: m.start(),
+                    end: m.end(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(text: &str, query: &str) -> Vec<(usize, usize, usize)> {
+        find_matches(text.to_string(), query.to_string())
+            .unwrap()
+            .iter()
+            .map(|m| (m.line, m.start, m.end))
+            .collect()
+    }
+
+    fn matches_on(lines: &[usize]) -> Vec<SearchMatch> {
+        lines
+            .iter()
+            .map(|line| SearchMatch {
+                line: *line,
+                start: 0,
+                end: 1,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_matches_by_line_and_byte_range() {
+        assert_eq!(
+            find("id: 1\nname: \"idée\"\nuuid: 2", "id"),
+            vec![(0, 0, 2), (1, 7, 9), (2, 2, 4)]
+        );
+        assert_eq!(find("a1b22", r"\d+"), vec![(0, 1, 2), (0, 3, 5)]);
+    }
+
+    #[test]
+    fn is_case_sensitive_with_an_uppercase_letter() {
+        assert_eq!(find("Id id", "id"), vec![(0, 0, 2), (0, 3, 5)]);
+        assert_eq!(find("Id id", "Id"), vec![(0, 0, 2)]);
+    }
+
+    #[test]
+    fn skips_empty_matches_and_reports_invalid_regexes() {
+        assert!(find("abc", "x*").is_empty());
+        assert_eq!(
+            find_matches("abc".to_string(), "(".to_string()).err(),
+            Some("invalid regex".to_string())
+        );
+    }
+
+    #[test]
+    fn focuses_from_the_origin_and_wraps_around() {
+        let matches = matches_on(&[2, 10, 40]);
+        let mut search = Search {
+            origin: 5,
+            ..Default::default()
+        };
+
+        assert_eq!(search.focus_from_origin(&matches), Some(7));
+        assert_eq!(search.current, 1);
+
+        assert_eq!(search.focus_next(&matches), Some(37));
+        assert_eq!(search.focus_next(&matches), Some(0));
+        assert_eq!(search.current, 0);
+
+        assert_eq!(search.focus_previous(&matches), Some(37));
+        assert_eq!(search.current, 2);
+    }
+
+    #[test]
+    fn focuses_the_first_match_past_the_last_one() {
+        let mut search = Search {
+            origin: 50,
+            ..Default::default()
+        };
+
+        assert_eq!(search.focus_from_origin(&matches_on(&[2, 10])), Some(0));
+        assert_eq!(search.focus_next(&[]), None);
+    }
+}
@@ -1,12 +1,118 @@
-use clap::Parser;
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
 
 use crate::app::RequestMethod;
 
 #[derive(Parser)]
-#[command(version, about, long_about=None, author)]
+#[command(version, about, long_about=None, author, args_conflicts_with_subcommands = true)]
 pub struct Cli {
+    /// URL to request, or a `.http` / `.rest` or `.har` file to open
     pub url: Option<String>,
 
     #[arg(default_value = "get", short = 'X', long, value_parser = clap::value_parser!(RequestMethod))]
     pub method: Option<RequestMethod>,
+
+    /// Collection to open, by name or path
+    #[arg(short, long)]
+    pub collection: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Convert requests from another tool into a collection
+    Import {
+        #[command(subcommand)]
+        format: ImportFormat,
+    },
+    /// Send every request of a collection in order
+    Run {
+        /// Collection to run, by name or path
+        collection: String,
+
+        /// Environment of the collection to use
+        #[arg(short, long)]
+        environment: Option<String>,
+
+        /// Write the requests and responses to a HAR file
+        #[arg(long)]
+        har: Option<PathBuf>,
+    },
+    /// Serve the example responses of a collection on a local server
+    Mock {
+        /// Collection to serve, by name or path
+        collection: String,
+
+        /// Environment of the collection to resolve the URLs with
+        #[arg(short, long)]
+        environment: Option<String>,
+
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+
+        #[arg(short, long, default_value_t = 4010)]
+        port: u16,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ImportFormat {
+    /// A Postman v2.1 collection export
+    Postman {
+        file: PathBuf,
+
+        /// A Postman environment export to add to the collection
+        #[arg(short, long = "environment")]
+        environments: Vec<PathBuf>,
+
+        #[command(flatten)]
+        output: ImportOutput,
+    },
+    /// An OpenAPI 3 or Swagger 2.0 specification, in JSON or YAML
+    Openapi {
+        file: PathBuf,
+
+        #[command(flatten)]
+        output: ImportOutput,
+    },
+    /// A HAR capture, as exported by browser devtools
+    Har {
+        file: PathBuf,
+
+        #[command(flatten)]
+        output: ImportOutput,
+    },
+    /// An Insomnia v4 export, in JSON
+    Insomnia {
+        file: PathBuf,
+
+        #[command(flatten)]
+        output: ImportOutput,
+    },
+    /// A Bruno collection folder, the one with bruno.json
+    Bruno {
+        dir: PathBuf,
+
+        #[command(flatten)]
+        output: ImportOutput,
+    },
+}
+
+#[derive(Args)]
+pub struct ImportOutput {
+    /// Where to save the collection, the collections directory by default
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Replace the output if it exists
+    #[arg(short, long)]
+    pub force: bool,
+
+    /// Show what would be imported without saving anything
+    #[arg(short = 'n', long)]
+    pub dry_run: bool,
 }
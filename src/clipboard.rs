@@ -0,0 +1,46 @@
+use std::sync::Mutex;
+
+use arboard::Clipboard;
+use once_cell::sync::Lazy;
+
+/// Kept for the whole process, on X11 and Wayland the copied text is gone
+/// once the clipboard that owns it is dropped
+static CLIPBOARD: Lazy<Mutex<Result<Clipboard, String>>> =
+    Lazy::new(|| Mutex::new(Clipboard::new().map_err(|e| e.to_string())));
+
+/// Last copied text, used when the system clipboard is not available
+static REGISTER: Mutex<String> = Mutex::new(String::new());
+
+/// Copy text to the system clipboard, or to the register without one
+pub fn copy(text: &str) -> Result<(), String> {
+    let mut clipboard = CLIPBOARD.lock().map_err(|e| e.to_string())?;
+
+    match clipboard.as_mut() {
+        Ok(clipboard) => clipboard
+            .set_text(text.to_owned())
+            .map_err(|e| e.to_string()),
+        Err(e) => {
+            if let Ok(mut register) = REGISTER.lock() {
+                *register = text.to_owned();
+            }
+
+            Err(e.clone())
+        }
+    }
+}
+
+/// Text from the system clipboard, or the last text copied in tuapi
+/// without one
+pub fn paste() -> String {
+    let Ok(mut clipboard) = CLIPBOARD.lock() else {
+        return String::new();
+    };
+
+    match clipboard.as_mut() {
+        Ok(clipboard) => clipboard.get_text().unwrap_or_default(),
+        Err(_) => REGISTER
+            .lock()
+            .map(|register| register.clone())
+            .unwrap_or_default(),
+    }
+}
@@ -0,0 +1,361 @@
+pub mod run;
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::data_dir;
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn ok() -> u16 {
+    200
+}
+
+/// A header, query parameter, form field or variable
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct KeyValue {
+    pub name: String,
+    #[serde(default)]
+    pub value: String,
+    #[serde(default = "enabled", skip_serializing_if = "is_true")]
+    pub enabled: bool,
+}
+
+impl KeyValue {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            enabled: true,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Body {
+    Raw {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content_type: Option<String>,
+        text: String,
+    },
+    /// `application/x-www-form-urlencoded`
+    Form { fields: Vec<KeyValue> },
+    /// `multipart/form-data` with text parts
+    Multipart { fields: Vec<KeyValue> },
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyLocation {
+    Header,
+    Query,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Auth {
+    Basic {
+        username: String,
+        #[serde(default)]
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+    ApiKey {
+        name: String,
+        value: String,
+        #[serde(rename = "in")]
+        location: ApiKeyLocation,
+    },
+}
+
+/// A response saved with a request, served by `tuapi mock`
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Example {
+    pub name: String,
+    #[serde(default = "ok")]
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<KeyValue>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub body: String,
+    /// Milliseconds the mock waits before responding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay: Option<u64>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct SavedRequest {
+    pub name: String,
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<KeyValue>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub query: Vec<KeyValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Body>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Auth>,
+    /// The OpenAPI operation the request was imported from, like
+    /// `GET /users/{id}`, its responses are checked against the spec
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<Example>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Folder {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub items: Vec<Item>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Item {
+    Folder(Folder),
+    Request(Box<SavedRequest>),
+}
+
+/// Variables used as `{{name}}` in requests, for a server or a stage
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Environment {
+    pub name: String,
+    #[serde(default)]
+    pub variables: Vec<KeyValue>,
+}
+
+/// Requests saved together in one file, with the environments to send them
+/// to
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct Collection {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Defaults for every environment
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<KeyValue>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub environments: Vec<Environment>,
+    /// The OpenAPI spec the collection was imported from, relative to the
+    /// collection file or absolute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spec: Option<PathBuf>,
+    /// Written as `- folder: ..` and `- request: ..`
+    #[serde(default, with = "serde_yaml::with::singleton_map_recursive")]
+    pub items: Vec<Item>,
+}
+
+/// A request of a collection with the names of the folders it's in
+pub struct RequestEntry<'a> {
+    pub folders: Vec<&'a str>,
+    pub request: &'a SavedRequest,
+}
+
+impl RequestEntry<'_> {
+    /// Folders and name, like `Users / Create user`
+    pub fn label(&self) -> String {
+        let mut parts = self.folders.clone();
+        parts.push(&self.request.name);
+
+        parts.join(" / ")
+    }
+}
+
+fn collect_requests<'a>(items: &'a [Item], folders: &[&'a str], out: &mut Vec<RequestEntry<'a>>) {
+    for item in items {
+        match item {
+            Item::Folder(folder) => {
+                let mut path = folders.to_vec();
+                path.push(&folder.name);
+
+                collect_requests(&folder.items, &path, out);
+            }
+            Item::Request(request) => out.push(RequestEntry {
+                folders: folders.to_vec(),
+                request,
+            }),
+        }
+    }
+}
+
+/// The request at `index` in the order of `collect_requests`, `index`
+/// counting down the requests passed
+fn nth_request<'a>(items: &'a mut [Item], index: &mut usize) -> Option<&'a mut SavedRequest> {
+    for item in items {
+        match item {
+            Item::Folder(folder) => {
+                if let Some(request) = nth_request(&mut folder.items, index) {
+                    return Some(request);
+                }
+            }
+            Item::Request(request) if *index == 0 => return Some(request),
+            Item::Request(_) => *index -= 1,
+        }
+    }
+
+    None
+}
+
+fn count_folders(items: &[Item]) -> usize {
+    items
+        .iter()
+        .map(|item| match item {
+            Item::Folder(folder) => 1 + count_folders(&folder.items),
+            Item::Request(_) => 0,
+        })
+        .sum()
+}
+
+impl Collection {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+
+        serde_yaml::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let text = serde_yaml::to_string(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(path, text)
+    }
+
+    /// Every request, depth first in the order of the file
+    pub fn requests(&self) -> Vec<RequestEntry<'_>> {
+        let mut requests = Vec::new();
+
+        collect_requests(&self.items, &[], &mut requests);
+
+        requests
+    }
+
+    /// Where the spec is, the collection being saved at `path`
+    pub fn spec_path(&self, path: &Path) -> Option<PathBuf> {
+        let spec = self.spec.as_ref()?;
+
+        Some(match path.parent() {
+            Some(dir) => dir.join(spec),
+            None => spec.clone(),
+        })
+    }
+
+    /// A request by its index in `requests`
+    pub fn request_mut(&mut self, index: usize) -> Option<&mut SavedRequest> {
+        let mut index = index;
+
+        nth_request(&mut self.items, &mut index)
+    }
+
+    pub fn folder_count(&self) -> usize {
+        count_folders(&self.items)
+    }
+
+    /// Index of an environment by its name, ignoring case
+    pub fn find_environment(&self, name: &str) -> Result<usize, String> {
+        self.environments
+            .iter()
+            .position(|environment| environment.name.eq_ignore_ascii_case(name))
+            .ok_or(format!("no environment named {name} in {}", self.name))
+    }
+
+    /// Values of the variables with an environment applied over the
+    /// collection's own
+    pub fn variables(&self, environment: Option<usize>) -> HashMap<String, String> {
+        let environment = environment
+            .and_then(|index| self.environments.get(index))
+            .map(|environment| environment.variables.as_slice())
+            .unwrap_or_default();
+
+        self.variables
+            .iter()
+            .chain(environment)
+            .filter(|variable| variable.enabled)
+            .map(|variable| (variable.name.clone(), variable.value.clone()))
+            .collect()
+    }
+}
+
+/// `$XDG_DATA_HOME/tuapi/collections`, or `~/.local/share/tuapi/collections`
+pub fn collections_dir() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("collections"))
+}
+
+/// A collection file given as a path, or by name in the collections
+/// directory
+pub fn find(name: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(name);
+
+    if path.is_file() {
+        return Some(path);
+    }
+
+    collections_dir()
+        .map(|dir| dir.join(format!("{}.yaml", slug(name))))
+        .filter(|path| path.is_file())
+}
+
+/// File name for a collection called `name`
+pub fn slug(name: &str) -> String {
+    let slug = name
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    match slug.is_empty() {
+        true => "collection".to_string(),
+        false => slug,
+    }
+}
+
+/// Replace `{{name}}` with the value of the variable, unknown variables
+/// are left as they are
+pub fn substitute(text: &str, variables: &HashMap<String, String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start + 2..].find("}}") else {
+            break;
+        };
+
+        let name = rest[start + 2..start + 2 + length].trim();
+
+        result.push_str(&rest[..start]);
+
+        match variables.get(name) {
+            Some(value) => result.push_str(value),
+            None => result.push_str(&rest[start..start + length + 4]),
+        }
+
+        rest = &rest[start + length + 4..];
+    }
+
+    result.push_str(rest);
+    result
+}
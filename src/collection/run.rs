@@ -0,0 +1,74 @@
+use std::{path::Path, str::FromStr};
+
+use crate::{
+    app::{Request, RequestMethod},
+    har, request,
+};
+
+use super::{find, Collection};
+
+/// Send the requests of a collection one after the other, printing their
+/// status, and optionally save the exchanges as a HAR file
+pub async fn run(
+    name: &str,
+    environment: Option<&str>,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let path = find(name).ok_or(format!("no collection named {name}"))?;
+    let collection = Collection::load(&path)?;
+
+    let environment = environment
+        .map(|environment| collection.find_environment(environment))
+        .transpose()?;
+
+    let entries = collection.requests();
+    let variables = collection.variables(environment);
+
+    let mut exchanges = Vec::new();
+    let mut failed = 0;
+
+    for entry in &entries {
+        let label = entry.label();
+
+        if RequestMethod::from_str(&entry.request.method).is_err() {
+            println!(
+                "skipped {label}: {} requests can't be sent",
+                entry.request.method
+            );
+            continue;
+        }
+
+        let (request, problems) = Request::from_saved(entry.request);
+
+        for problem in problems {
+            println!("note    {label}: {problem}");
+        }
+
+        match request::send(request.substitute(&variables)).await {
+            Ok(exchange) => {
+                println!(
+                    "{:<7} {label} ({} ms)",
+                    exchange.response.status_code,
+                    exchange.timings.total().as_millis()
+                );
+
+                exchanges.push(exchange);
+            }
+            Err(e) => {
+                println!("failed  {label}: {e}");
+                failed += 1;
+            }
+        }
+    }
+
+    if let Some(output) = output {
+        har::save(output, &exchanges).map_err(|e| format!("{}: {e}", output.display()))?;
+
+        println!("Saved to {}", output.display());
+    }
+
+    match failed {
+        0 => Ok(()),
+        failed => Err(format!("{failed} of {} requests failed", entries.len())),
+    }
+}
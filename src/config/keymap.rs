@@ -0,0 +1,797 @@
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// A key with its modifiers, shift is part of the character for printable
+/// keys
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl From<KeyEvent> for Key {
+    fn from(event: KeyEvent) -> Self {
+        let mut modifiers = event.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+
+        if !matches!(event.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers |= event.modifiers & KeyModifiers::SHIFT;
+        }
+
+        Self {
+            code: event.code,
+            modifiers,
+        }
+    }
+}
+
+const NAMED_KEYS: [(&str, KeyCode); 15] = [
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("space", KeyCode::Char(' ')),
+    ("backspace", KeyCode::Backspace),
+    ("delete", KeyCode::Delete),
+    ("insert", KeyCode::Insert),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+];
+
+impl Key {
+    /// Parse a key like `j`, `G`, `ctrl-s`, `alt-enter` or `pagedown`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut rest = text;
+        let mut modifiers = KeyModifiers::NONE;
+
+        loop {
+            let (prefix, modifier) = match rest.split_once('-') {
+                Some((prefix, tail)) if !tail.is_empty() => match prefix.to_lowercase().as_str() {
+                    "ctrl" => (prefix, KeyModifiers::CONTROL),
+                    "alt" => (prefix, KeyModifiers::ALT),
+                    "shift" => (prefix, KeyModifiers::SHIFT),
+                    _ => break,
+                },
+                _ => break,
+            };
+
+            modifiers |= modifier;
+            rest = &rest[prefix.len() + 1..];
+        }
+
+        let mut chars = rest.chars();
+
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => NAMED_KEYS
+                        .iter()
+                        .find(|(key_name, _)| *key_name == name)
+                        .map(|(_, code)| *code)
+                        .ok_or_else(|| format!("unknown key '{text}'"))?,
+                },
+            },
+        };
+
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            code => code,
+        };
+
+        Ok(Self::from(KeyEvent::new(code, modifiers)))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl-")?;
+        }
+
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt-")?;
+        }
+
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift-")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "f{n}"),
+            KeyCode::PageDown => write!(f, "pagedown"),
+            code => match NAMED_KEYS.iter().find(|(_, named)| *named == code) {
+                Some((name, _)) => write!(f, "{name}"),
+                None => write!(f, "{code:?}"),
+            },
+        }
+    }
+}
+
+/// Parse a chord, keys separated by spaces like `g g`
+pub fn parse_sequence(text: &str) -> Result<Vec<Key>, String> {
+    let keys = text
+        .split_whitespace()
+        .map(Key::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match keys.is_empty() {
+        true => Err("empty key binding".to_string()),
+        false => Ok(keys),
+    }
+}
+
+pub fn format_sequence(keys: &[Key]) -> String {
+    keys.iter()
+        .map(|key| key.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Where a binding applies
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum KeyContext {
+    /// Normal mode outside of popups
+    Normal,
+    Response,
+    Tree,
+    Hex,
+    Popup,
+}
+
+impl KeyContext {
+    pub const ALL: [Self; 5] = [
+        Self::Normal,
+        Self::Response,
+        Self::Tree,
+        Self::Hex,
+        Self::Popup,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Response => "response",
+            Self::Tree => "tree",
+            Self::Hex => "hex",
+            Self::Popup => "popup",
+        }
+    }
+
+    /// Contexts active at the same time, whose keys can clash. Normal stays
+    /// active under the response and its views
+    fn overlaps(&self, other: &Self) -> bool {
+        self == other
+            || matches!(
+                (self, other),
+                (Self::Response, Self::Tree | Self::Hex)
+                    | (Self::Tree | Self::Hex, Self::Response)
+                    | (Self::Normal, Self::Response | Self::Tree | Self::Hex)
+                    | (Self::Response | Self::Tree | Self::Hex, Self::Normal)
+            )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    Quit,
+    Insert,
+    NextBlock,
+    PreviousBlock,
+    Select,
+    Send,
+    Up,
+    Down,
+    Add,
+    Edit,
+    Delete,
+    FormatBody,
+    EditExternally,
+    ToggleContentType,
+    ToggleBodyType,
+    ToggleView,
+    SaveResponse,
+    OpenInPager,
+    TogglePretty,
+    Search,
+    NextMatch,
+    PreviousMatch,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+    GoToOffset,
+    Collapse,
+    Expand,
+    ToggleNode,
+    CollapseAll,
+    ExpandAll,
+    CopyValue,
+    CopyPath,
+    Confirm,
+    Cancel,
+    Help,
+    CommandPalette,
+    ChangeMethod,
+    ShowBody,
+    ShowQuery,
+    ShowHeaders,
+    CopyResponse,
+    GrowPane,
+    ShrinkPane,
+    CycleLayout,
+    ToggleMaximize,
+    OpenRequest,
+    SelectEnvironment,
+    SaveRequest,
+    History,
+    ExportHar,
+    SaveExample,
+    Examples,
+    DiffExample,
+    CompareResponses,
+    ToggleSideBySide,
+}
+
+impl Action {
+    pub const ALL: [Self; 57] = [
+        Self::Quit,
+        Self::Insert,
+        Self::NextBlock,
+        Self::PreviousBlock,
+        Self::Select,
+        Self::Send,
+        Self::Up,
+        Self::Down,
+        Self::Add,
+        Self::Edit,
+        Self::Delete,
+        Self::FormatBody,
+        Self::EditExternally,
+        Self::ToggleContentType,
+        Self::ToggleBodyType,
+        Self::ToggleView,
+        Self::SaveResponse,
+        Self::OpenInPager,
+        Self::TogglePretty,
+        Self::Search,
+        Self::NextMatch,
+        Self::PreviousMatch,
+        Self::PageUp,
+        Self::PageDown,
+        Self::Top,
+        Self::Bottom,
+        Self::GoToOffset,
+        Self::Collapse,
+        Self::Expand,
+        Self::ToggleNode,
+        Self::CollapseAll,
+        Self::ExpandAll,
+        Self::CopyValue,
+        Self::CopyPath,
+        Self::Confirm,
+        Self::Cancel,
+        Self::Help,
+        Self::CommandPalette,
+        Self::ChangeMethod,
+        Self::ShowBody,
+        Self::ShowQuery,
+        Self::ShowHeaders,
+        Self::CopyResponse,
+        Self::GrowPane,
+        Self::ShrinkPane,
+        Self::CycleLayout,
+        Self::ToggleMaximize,
+        Self::OpenRequest,
+        Self::SelectEnvironment,
+        Self::SaveRequest,
+        Self::History,
+        Self::ExportHar,
+        Self::SaveExample,
+        Self::Examples,
+        Self::DiffExample,
+        Self::CompareResponses,
+        Self::ToggleSideBySide,
+    ];
+
+    /// Name used in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Quit => "quit",
+            Self::Insert => "insert",
+            Self::NextBlock => "next_block",
+            Self::PreviousBlock => "previous_block",
+            Self::Select => "select",
+            Self::Send => "send",
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Add => "add",
+            Self::Edit => "edit",
+            Self::Delete => "delete",
+            Self::FormatBody => "format_body",
+            Self::EditExternally => "edit_externally",
+            Self::ToggleContentType => "toggle_content_type",
+            Self::ToggleBodyType => "toggle_body_type",
+            Self::ToggleView => "toggle_view",
+            Self::SaveResponse => "save_response",
+            Self::OpenInPager => "open_in_pager",
+            Self::TogglePretty => "toggle_pretty",
+            Self::Search => "search",
+            Self::NextMatch => "next_match",
+            Self::PreviousMatch => "previous_match",
+            Self::PageUp => "page_up",
+            Self::PageDown => "page_down",
+            Self::Top => "top",
+            Self::Bottom => "bottom",
+            Self::GoToOffset => "go_to_offset",
+            Self::Collapse => "collapse",
+            Self::Expand => "expand",
+            Self::ToggleNode => "toggle_node",
+            Self::CollapseAll => "collapse_all",
+            Self::ExpandAll => "expand_all",
+            Self::CopyValue => "copy_value",
+            Self::CopyPath => "copy_path",
+            Self::Confirm => "confirm",
+            Self::Cancel => "cancel",
+            Self::Help => "help",
+            Self::CommandPalette => "command_palette",
+            Self::ChangeMethod => "change_method",
+            Self::ShowBody => "show_body",
+            Self::ShowQuery => "show_query",
+            Self::ShowHeaders => "show_headers",
+            Self::CopyResponse => "copy_response",
+            Self::GrowPane => "grow_pane",
+            Self::ShrinkPane => "shrink_pane",
+            Self::CycleLayout => "cycle_layout",
+            Self::ToggleMaximize => "toggle_maximize",
+            Self::OpenRequest => "open_request",
+            Self::SelectEnvironment => "select_environment",
+            Self::SaveRequest => "save_request",
+            Self::History => "history",
+            Self::ExportHar => "export_har",
+            Self::SaveExample => "save_example",
+            Self::Examples => "examples",
+            Self::DiffExample => "diff_example",
+            Self::CompareResponses => "compare_responses",
+            Self::ToggleSideBySide => "toggle_side_by_side",
+        }
+    }
+
+    /// Shown in the help and the command palette
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Quit => "Quit",
+            Self::Insert => "Edit the selected input",
+            Self::NextBlock => "Select the next block",
+            Self::PreviousBlock => "Select the previous block",
+            Self::Select => "Open or send",
+            Self::Send => "Send the request",
+            Self::Up => "Move up",
+            Self::Down => "Move down",
+            Self::Add => "Add an entry",
+            Self::Edit => "Edit the selected entry",
+            Self::Delete => "Delete the selected entry",
+            Self::FormatBody => "Format the body",
+            Self::EditExternally => "Edit the body in $EDITOR",
+            Self::ToggleContentType => "Switch between text and form body",
+            Self::ToggleBodyType => "Cycle the body type",
+            Self::ToggleView => "Switch between text and tree view",
+            Self::SaveResponse => "Save the response to a file",
+            Self::OpenInPager => "Open the response in $PAGER",
+            Self::TogglePretty => "Switch between pretty and raw response",
+            Self::Search => "Search the response",
+            Self::NextMatch => "Go to the next match",
+            Self::PreviousMatch => "Go to the previous match",
+            Self::PageUp => "Page up",
+            Self::PageDown => "Page down",
+            Self::Top => "Go to the top",
+            Self::Bottom => "Go to the bottom",
+            Self::GoToOffset => "Go to an offset",
+            Self::Collapse => "Collapse the node or go to its parent",
+            Self::Expand => "Expand the node",
+            Self::ToggleNode => "Collapse or expand the node",
+            Self::CollapseAll => "Collapse all nodes",
+            Self::ExpandAll => "Expand all nodes",
+            Self::CopyValue => "Copy the value of the node",
+            Self::CopyPath => "Copy the path of the node",
+            Self::Confirm => "Confirm",
+            Self::Cancel => "Close",
+            Self::Help => "Show the keys available here",
+            Self::CommandPalette => "Open the command palette",
+            Self::ChangeMethod => "Change the request method",
+            Self::ShowBody => "Show the request body",
+            Self::ShowQuery => "Show the query parameters",
+            Self::ShowHeaders => "Show the request headers",
+            Self::CopyResponse => "Copy the response to the clipboard",
+            Self::GrowPane => "Grow the selected pane",
+            Self::ShrinkPane => "Shrink the selected pane",
+            Self::CycleLayout => "Switch between automatic, side by side and stacked panes",
+            Self::ToggleMaximize => "Maximize or restore the selected pane",
+            Self::OpenRequest => "Open a request of the collection or file",
+            Self::SelectEnvironment => "Switch the environment of the collection",
+            Self::SaveRequest => "Write the request back to its file",
+            Self::History => "Browse the requests sent and their responses",
+            Self::ExportHar => "Export the history as a HAR file",
+            Self::SaveExample => "Save the response as an example of the request",
+            Self::Examples => "Show an example response of the request",
+            Self::DiffExample => "Compare the response with an example",
+            Self::CompareResponses => "Compare two responses of the history or examples",
+            Self::ToggleSideBySide => "Switch between unified and side by side diff",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+/// Actions without keys are still available in the command palette
+const DEFAULT_BINDINGS: [(KeyContext, Action, &[&str]); 68] = [
+    (KeyContext::Normal, Action::Quit, &["q"]),
+    (KeyContext::Normal, Action::Insert, &["i"]),
+    (KeyContext::Normal, Action::NextBlock, &["tab"]),
+    (KeyContext::Normal, Action::PreviousBlock, &["backtab"]),
+    (KeyContext::Normal, Action::Select, &["enter"]),
+    (KeyContext::Normal, Action::Send, &["ctrl-s"]),
+    (KeyContext::Normal, Action::Down, &["j"]),
+    (KeyContext::Normal, Action::Up, &["k"]),
+    (KeyContext::Normal, Action::Add, &["a"]),
+    (KeyContext::Normal, Action::Edit, &["e"]),
+    (KeyContext::Normal, Action::Delete, &["d"]),
+    (KeyContext::Normal, Action::FormatBody, &["f"]),
+    (KeyContext::Normal, Action::EditExternally, &["E"]),
+    (KeyContext::Normal, Action::ToggleContentType, &["c"]),
+    (KeyContext::Normal, Action::ToggleBodyType, &["t"]),
+    (KeyContext::Normal, Action::Help, &["?"]),
+    (KeyContext::Normal, Action::CommandPalette, &["ctrl-p", ":"]),
+    (KeyContext::Normal, Action::ChangeMethod, &[]),
+    (KeyContext::Normal, Action::ShowBody, &[]),
+    (KeyContext::Normal, Action::ShowQuery, &[]),
+    (KeyContext::Normal, Action::ShowHeaders, &[]),
+    (KeyContext::Normal, Action::CopyResponse, &[]),
+    (KeyContext::Normal, Action::GrowPane, &["+"]),
+    (KeyContext::Normal, Action::ShrinkPane, &["-"]),
+    (KeyContext::Normal, Action::CycleLayout, &["|"]),
+    (KeyContext::Normal, Action::ToggleMaximize, &["z"]),
+    (KeyContext::Normal, Action::OpenRequest, &["ctrl-o"]),
+    (KeyContext::Normal, Action::SelectEnvironment, &["ctrl-e"]),
+    (KeyContext::Normal, Action::SaveRequest, &["w"]),
+    (KeyContext::Normal, Action::History, &["ctrl-r"]),
+    (KeyContext::Normal, Action::ExportHar, &[]),
+    (KeyContext::Normal, Action::Examples, &["ctrl-x"]),
+    (KeyContext::Normal, Action::CompareResponses, &["ctrl-d"]),
+    (KeyContext::Response, Action::ToggleView, &["v"]),
+    (KeyContext::Response, Action::SaveResponse, &["s"]),
+    (KeyContext::Response, Action::OpenInPager, &["o"]),
+    (KeyContext::Response, Action::TogglePretty, &["p"]),
+    (KeyContext::Response, Action::Search, &["/"]),
+    (KeyContext::Response, Action::NextMatch, &["n"]),
+    (KeyContext::Response, Action::PreviousMatch, &["N"]),
+    (KeyContext::Response, Action::SaveExample, &["x"]),
+    (KeyContext::Response, Action::DiffExample, &["D"]),
+    (KeyContext::Tree, Action::Down, &["j", "down"]),
+    (KeyContext::Tree, Action::Up, &["k", "up"]),
+    (KeyContext::Tree, Action::Collapse, &["h", "left"]),
+    (KeyContext::Tree, Action::Expand, &["l", "right"]),
+    (KeyContext::Tree, Action::ToggleNode, &["space"]),
+    (KeyContext::Tree, Action::Top, &["g"]),
+    (KeyContext::Tree, Action::Bottom, &["G"]),
+    (KeyContext::Tree, Action::CollapseAll, &["H"]),
+    (KeyContext::Tree, Action::ExpandAll, &["L"]),
+    (KeyContext::Tree, Action::CopyValue, &["y"]),
+    (KeyContext::Tree, Action::CopyPath, &["Y"]),
+    (KeyContext::Hex, Action::Down, &["j", "down"]),
+    (KeyContext::Hex, Action::Up, &["k", "up"]),
+    (KeyContext::Hex, Action::PageDown, &["J", "pagedown"]),
+    (KeyContext::Hex, Action::PageUp, &["K", "pageup"]),
+    (KeyContext::Hex, Action::Top, &["g", "home"]),
+    (KeyContext::Hex, Action::Bottom, &["G", "end"]),
+    (KeyContext::Hex, Action::GoToOffset, &[":"]),
+    (KeyContext::Popup, Action::Up, &["k"]),
+    (KeyContext::Popup, Action::Down, &["j"]),
+    (KeyContext::Popup, Action::Insert, &["i"]),
+    (KeyContext::Popup, Action::Select, &["enter"]),
+    (KeyContext::Popup, Action::Confirm, &["y"]),
+    (KeyContext::Popup, Action::ToggleSideBySide, &["s"]),
+    (KeyContext::Popup, Action::Cancel, &["esc", "q", "n"]),
+    (KeyContext::Popup, Action::Help, &["?"]),
+];
+
+#[derive(Clone, PartialEq)]
+pub struct Binding {
+    pub context: KeyContext,
+    pub action: Action,
+    pub keys: Vec<Key>,
+}
+
+pub enum Resolution {
+    Action(Action),
+    /// The keys so far start a chord
+    Pending,
+    Unbound,
+}
+
+#[derive(Clone)]
+pub struct KeyMap {
+    pub bindings: Vec<Binding>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let bindings = DEFAULT_BINDINGS
+            .iter()
+            .flat_map(|(context, action, keys)| {
+                keys.iter().map(|keys| Binding {
+                    context: *context,
+                    action: *action,
+                    keys: parse_sequence(keys).unwrap(),
+                })
+            })
+            .collect();
+
+        Self { bindings }
+    }
+}
+
+impl KeyMap {
+    /// Whether the action does anything in the context
+    pub fn is_available(context: KeyContext, action: Action) -> bool {
+        DEFAULT_BINDINGS
+            .iter()
+            .any(|(c, a, _)| *c == context && *a == action)
+    }
+
+    /// Actions that can be bound in a context, in the order of the defaults
+    pub fn actions(context: KeyContext) -> Vec<Action> {
+        let mut actions: Vec<Action> = DEFAULT_BINDINGS
+            .iter()
+            .filter(|(c, _, _)| *c == context)
+            .map(|(_, action, _)| *action)
+            .collect();
+
+        actions.dedup();
+        actions
+    }
+
+    /// Keys of an action in `context` that reach it with `contexts` active,
+    /// those also bound in an earlier context are left out
+    pub fn keys_for(
+        &self,
+        contexts: &[KeyContext],
+        context: KeyContext,
+        action: Action,
+    ) -> Vec<String> {
+        let earlier: Vec<_> = contexts.iter().take_while(|c| **c != context).collect();
+
+        self.bindings
+            .iter()
+            .filter(|binding| binding.context == context && binding.action == action)
+            .filter(|binding| {
+                !self
+                    .bindings
+                    .iter()
+                    .any(|other| earlier.contains(&&other.context) && other.keys == binding.keys)
+            })
+            .map(|binding| format_sequence(&binding.keys))
+            .collect()
+    }
+
+    /// Replace the keys of an action in a context
+    pub fn bind(&mut self, context: KeyContext, action: Action, sequences: Vec<Vec<Key>>) {
+        self.bindings
+            .retain(|binding| binding.context != context || binding.action != action);
+
+        self.bindings
+            .extend(sequences.into_iter().map(|keys| Binding {
+                context,
+                action,
+                keys,
+            }));
+    }
+
+    /// Find the action bound to the keys typed so far, trying the contexts in
+    /// order
+    pub fn resolve(&self, contexts: &[KeyContext], keys: &[Key]) -> Resolution {
+        for context in contexts {
+            let exact = self
+                .bindings
+                .iter()
+                .find(|binding| binding.context == *context && binding.keys == keys);
+
+            if let Some(binding) = exact {
+                return Resolution::Action(binding.action);
+            }
+        }
+
+        let is_prefix = self.bindings.iter().any(|binding| {
+            contexts.contains(&binding.context)
+                && binding.keys.len() > keys.len()
+                && binding.keys.starts_with(keys)
+        });
+
+        match is_prefix {
+            true => Resolution::Pending,
+            false => Resolution::Unbound,
+        }
+    }
+
+    /// Bindings that can't all work: the same keys for two actions, or a
+    /// chord starting with the keys of another binding. The defaults of a
+    /// view shadow those of Normal on purpose, like `:` in the hex view
+    pub fn conflicts(&self) -> Vec<String> {
+        let defaults = Self::default().bindings;
+        let mut conflicts = Vec::new();
+
+        for (i, a) in self.bindings.iter().enumerate() {
+            for b in &self.bindings[i + 1..] {
+                let is_same_action =
+                    a.action == b.action && (a.context == b.context || a.keys == b.keys);
+                let is_default =
+                    a.context != b.context && defaults.contains(a) && defaults.contains(b);
+
+                if !a.context.overlaps(&b.context) || is_same_action || is_default {
+                    continue;
+                }
+
+                let (short, long) = match a.keys.len() <= b.keys.len() {
+                    true => (a, b),
+                    false => (b, a),
+                };
+
+                if long.keys.starts_with(&short.keys) {
+                    conflicts.push(format!(
+                        "'{}' is bound to {}.{} and {}.{}",
+                        format_sequence(&short.keys),
+                        short.context.name(),
+                        short.action.name(),
+                        long.context.name(),
+                        long.action.name(),
+                    ));
+                }
+            }
+        }
+
+        conflicts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(text: &str) -> Key {
+        Key::parse(text).unwrap()
+    }
+
+    fn keys(text: &str) -> Vec<Key> {
+        parse_sequence(text).unwrap()
+    }
+
+    fn resolve(keymap: &KeyMap, contexts: &[KeyContext], text: &str) -> Option<Action> {
+        match keymap.resolve(contexts, &keys(text)) {
+            Resolution::Action(action) => Some(action),
+            Resolution::Pending | Resolution::Unbound => None,
+        }
+    }
+
+    #[test]
+    fn parses_keys_with_modifiers() {
+        assert_eq!(
+            key("ctrl-s"),
+            Key {
+                code: KeyCode::Char('s'),
+                modifiers: KeyModifiers::CONTROL,
+            }
+        );
+        assert_eq!(
+            key("Ctrl-Alt-Enter"),
+            Key {
+                code: KeyCode::Enter,
+                modifiers: KeyModifiers::CONTROL | KeyModifiers::ALT,
+            }
+        );
+        assert_eq!(key("shift-g"), key("G"));
+        assert_eq!(key("shift-tab"), key("backtab"));
+        assert_eq!(key("-").code, KeyCode::Char('-'));
+        assert_eq!(key("ctrl--").code, KeyCode::Char('-'));
+        assert_eq!(key("F5").code, KeyCode::F(5));
+        assert_eq!(key("space").code, KeyCode::Char(' '));
+        assert_eq!(key("pagedown").code, KeyCode::PageDown);
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert_eq!(Key::parse("f13"), Err("unknown key 'f13'".to_string()));
+        assert_eq!(
+            Key::parse("hyper-x"),
+            Err("unknown key 'hyper-x'".to_string())
+        );
+        assert_eq!(parse_sequence("  "), Err("empty key binding".to_string()));
+    }
+
+    #[test]
+    fn shift_is_part_of_printable_keys() {
+        let event = KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT);
+
+        assert_eq!(Key::from(event), key("G"));
+        assert_eq!(
+            Key::from(KeyEvent::new(KeyCode::Up, KeyModifiers::SHIFT)),
+            key("shift-up")
+        );
+    }
+
+    #[test]
+    fn formats_keys_as_parsed() {
+        for text in ["ctrl-alt-x", "shift-up", "space", "f12", "pagedown", "g g"] {
+            assert_eq!(format_sequence(&keys(text)), text);
+        }
+    }
+
+    #[test]
+    fn resolves_chords_in_context_order() {
+        let mut keymap = KeyMap::default();
+        keymap.bind(KeyContext::Tree, Action::Top, vec![keys("g g")]);
+
+        let tree = [KeyContext::Response, KeyContext::Tree, KeyContext::Normal];
+
+        assert_eq!(resolve(&keymap, &tree, "j"), Some(Action::Down));
+        assert_eq!(resolve(&keymap, &tree, "q"), Some(Action::Quit));
+        assert_eq!(resolve(&keymap, &tree, "g g"), Some(Action::Top));
+        assert!(matches!(
+            keymap.resolve(&tree, &keys("g")),
+            Resolution::Pending
+        ));
+        assert!(matches!(
+            keymap.resolve(&[KeyContext::Normal], &keys("g")),
+            Resolution::Unbound
+        ));
+        assert_eq!(
+            resolve(&keymap, &[KeyContext::Popup], "q"),
+            Some(Action::Cancel)
+        );
+    }
+
+    #[test]
+    fn the_defaults_have_no_conflicts() {
+        assert!(KeyMap::default().conflicts().is_empty());
+    }
+
+    #[test]
+    fn reports_keys_bound_twice_and_shadowed_chords() {
+        let mut keymap = KeyMap::default();
+        keymap.bind(KeyContext::Normal, Action::Send, vec![keys("e")]);
+        keymap.bind(KeyContext::Tree, Action::CopyPath, vec![keys("y p")]);
+
+        assert_eq!(
+            keymap.conflicts(),
+            vec![
+                "'e' is bound to normal.edit and normal.send",
+                "'y' is bound to tree.copy_value and tree.copy_path",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_view_keys_shadowing_normal_ones() {
+        let mut keymap = KeyMap::default();
+        keymap.bind(KeyContext::Response, Action::ToggleView, vec![keys("q")]);
+        keymap.bind(KeyContext::Hex, Action::Down, vec![keys("j"), keys("down")]);
+
+        assert_eq!(
+            keymap.conflicts(),
+            vec!["'q' is bound to normal.quit and response.toggle_view"]
+        );
+    }
+}
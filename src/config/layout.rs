@@ -0,0 +1,218 @@
+use std::{fs, io};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppBlock;
+
+use super::state_dir;
+
+/// Terminals narrower than this stack the panes in the automatic layout
+pub const DEFAULT_STACK_BELOW: u16 = 100;
+
+const MIN_SPLIT: u16 = 20;
+const MAX_SPLIT: u16 = 80;
+const SPLIT_STEP: u16 = 5;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Orientation {
+    /// Side by side, stacked when the terminal is narrow
+    #[default]
+    Auto,
+    Horizontal,
+    Vertical,
+}
+
+impl Orientation {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Auto => Self::Horizontal,
+            Self::Horizontal => Self::Vertical,
+            Self::Vertical => Self::Auto,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Auto => "automatic",
+            Self::Horizontal => "side by side",
+            Self::Vertical => "stacked",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Pane {
+    Request,
+    Response,
+}
+
+impl Pane {
+    /// The pane a block is drawn in, the method and endpoint are above both
+    pub fn of(block: &AppBlock) -> Option<Self> {
+        match block {
+            AppBlock::Request | AppBlock::RequestContent => Some(Self::Request),
+            AppBlock::ResponseFilter | AppBlock::Response => Some(Self::Response),
+            AppBlock::Method | AppBlock::Endpoint => None,
+        }
+    }
+}
+
+/// How the request and response panes share the screen, the orientation
+/// and split are kept across runs
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaneLayout {
+    pub orientation: Orientation,
+    /// Percentage of the space taken by the request pane
+    pub split: u16,
+    /// Pane taking all the space
+    #[serde(skip)]
+    pub maximized: Option<Pane>,
+    /// Width below which the automatic orientation stacks the panes
+    #[serde(skip)]
+    pub stack_below: u16,
+}
+
+impl Default for PaneLayout {
+    fn default() -> Self {
+        Self {
+            orientation: Orientation::Auto,
+            split: 50,
+            maximized: None,
+            stack_below: DEFAULT_STACK_BELOW,
+        }
+    }
+}
+
+impl PaneLayout {
+    /// Load the layout saved by the last run, if any
+    pub fn load(problems: &mut Vec<String>) -> Self {
+        let Some(path) = state_dir().map(|dir| dir.join("layout.yaml")) else {
+            return Self::default();
+        };
+
+        let Ok(text) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+
+        match Self::parse(&text) {
+            Ok(layout) => layout,
+            Err(e) => {
+                problems.push(format!("{}: {e}", path.display()));
+                Self::default()
+            }
+        }
+    }
+
+    /// A saved layout, its split kept within bounds
+    fn parse(text: &str) -> Result<Self, serde_yaml::Error> {
+        let mut layout: Self = serde_yaml::from_str(text)?;
+
+        layout.split = layout.split.clamp(MIN_SPLIT, MAX_SPLIT);
+
+        Ok(layout)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let dir = state_dir().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "no home directory to save to")
+        })?;
+
+        let text = serde_yaml::to_string(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("layout.yaml"), text)
+    }
+
+    /// Whether the panes are stacked in a terminal `width` columns wide
+    pub fn is_vertical(&self, width: u16) -> bool {
+        match self.orientation {
+            Orientation::Auto => width < self.stack_below,
+            Orientation::Horizontal => false,
+            Orientation::Vertical => true,
+        }
+    }
+
+    /// Give more space to a pane, or less when `grow` is false
+    pub fn resize(&mut self, pane: Pane, grow: bool) {
+        let step = match (pane, grow) {
+            (Pane::Request, true) | (Pane::Response, false) => SPLIT_STEP as i16,
+            _ => -(SPLIT_STEP as i16),
+        };
+
+        self.split = self
+            .split
+            .saturating_add_signed(step)
+            .clamp(MIN_SPLIT, MAX_SPLIT);
+    }
+
+    pub fn toggle_maximized(&mut self, pane: Pane) {
+        self.maximized = match self.maximized {
+            Some(_) => None,
+            None => Some(pane),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resizes_within_bounds() {
+        let mut layout = PaneLayout::default();
+
+        layout.resize(Pane::Request, true);
+        assert_eq!(layout.split, 55);
+
+        layout.resize(Pane::Response, true);
+        layout.resize(Pane::Response, true);
+        assert_eq!(layout.split, 45);
+
+        (0..20).for_each(|_| layout.resize(Pane::Request, false));
+        assert_eq!(layout.split, MIN_SPLIT);
+
+        (0..20).for_each(|_| layout.resize(Pane::Response, false));
+        assert_eq!(layout.split, MAX_SPLIT);
+    }
+
+    #[test]
+    fn stacks_narrow_terminals_in_the_automatic_layout() {
+        let mut layout = PaneLayout::default();
+
+        assert!(layout.is_vertical(DEFAULT_STACK_BELOW - 1));
+        assert!(!layout.is_vertical(DEFAULT_STACK_BELOW));
+
+        layout.orientation = layout.orientation.next();
+        assert_eq!(layout.orientation, Orientation::Horizontal);
+        assert!(!layout.is_vertical(10));
+
+        layout.orientation = layout.orientation.next();
+        assert!(layout.is_vertical(500));
+        assert_eq!(layout.orientation.next(), Orientation::Auto);
+    }
+
+    #[test]
+    fn maximizes_one_pane_at_a_time() {
+        let mut layout = PaneLayout::default();
+
+        layout.toggle_maximized(Pane::Response);
+        assert_eq!(layout.maximized, Some(Pane::Response));
+
+        layout.toggle_maximized(Pane::Request);
+        assert_eq!(layout.maximized, None);
+    }
+
+    #[test]
+    fn parses_saved_layouts() {
+        let layout = PaneLayout::parse("orientation: vertical\nsplit: 95\n").unwrap();
+
+        assert_eq!(layout.orientation, Orientation::Vertical);
+        assert_eq!(layout.split, MAX_SPLIT);
+        assert_eq!(PaneLayout::parse("split: 3").unwrap().split, MIN_SPLIT);
+        assert_eq!(PaneLayout::parse("{}").unwrap().split, 50);
+        assert!(PaneLayout::parse("orientation: diagonal").is_err());
+    }
+}
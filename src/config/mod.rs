@@ -0,0 +1,160 @@
+pub mod keymap;
+pub mod layout;
+pub mod theme;
+
+use std::{collections::HashMap, env, path::PathBuf};
+
+use serde::Deserialize;
+
+use keymap::{parse_sequence, Action, KeyContext, KeyMap};
+use layout::{PaneLayout, DEFAULT_STACK_BELOW};
+use theme::{ColorMode, Theme};
+
+/// One key binding or a list of them
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Keys {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Keys {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            Self::One(keys) => vec![keys],
+            Self::Many(keys) => keys,
+        }
+    }
+}
+
+/// The config file as written by the user
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    /// Bindings by context and action name
+    keys: HashMap<String, HashMap<String, Keys>>,
+    /// A built-in theme or one from the `themes` directory
+    theme: Option<String>,
+    /// Overrides the syntax theme of `theme`
+    syntax_theme: Option<String>,
+    color_mode: ColorMode,
+    /// Width below which the automatic layout stacks the panes
+    stack_below: Option<u16>,
+}
+
+#[derive(Clone, Default)]
+pub struct Config {
+    pub keymap: KeyMap,
+    pub theme: Theme,
+    pub layout: PaneLayout,
+}
+
+/// `$XDG_CONFIG_HOME/tuapi`, or `~/.config/tuapi`
+pub fn config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("tuapi"))
+}
+
+/// `$XDG_STATE_HOME/tuapi`, or `~/.local/state/tuapi`
+pub fn state_dir() -> Option<PathBuf> {
+    env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))
+        .map(|dir| dir.join("tuapi"))
+}
+
+/// `$XDG_DATA_HOME/tuapi`, or `~/.local/share/tuapi`
+pub fn data_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|dir| dir.join("tuapi"))
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.yaml"))
+}
+
+impl Config {
+    /// Load the config file, falling back to the defaults for anything
+    /// missing or invalid. Returns the problems found along the way
+    pub fn load() -> (Self, Vec<String>) {
+        let mut config = Self::default();
+        let mut problems = Vec::new();
+
+        let file = match config_path().map(|path| (std::fs::read_to_string(&path), path)) {
+            Some((Ok(text), path)) => match serde_yaml::from_str::<Option<ConfigFile>>(&text) {
+                Ok(file) => file.unwrap_or_default(),
+                Err(e) => {
+                    problems.push(format!("{}: {e}", path.display()));
+                    ConfigFile::default()
+                }
+            },
+            _ => ConfigFile::default(),
+        };
+
+        config.theme = Theme::load(
+            file.theme.as_deref(),
+            file.syntax_theme.as_deref(),
+            file.color_mode,
+            &mut problems,
+        );
+
+        config.layout = PaneLayout::load(&mut problems);
+        config.layout.stack_below = file.stack_below.unwrap_or(DEFAULT_STACK_BELOW);
+
+        config.apply_keys(file.keys, &mut problems);
+
+        problems.extend(config.keymap.conflicts());
+
+        (config, problems)
+    }
+
+    fn apply_keys(
+        &mut self,
+        keys: HashMap<String, HashMap<String, Keys>>,
+        problems: &mut Vec<String>,
+    ) {
+        for (context_name, actions) in keys {
+            let Some(context) = KeyContext::ALL
+                .into_iter()
+                .find(|context| context.name() == context_name)
+            else {
+                problems.push(format!("unknown key context '{context_name}'"));
+                continue;
+            };
+
+            for (action_name, sequences) in actions {
+                let action = match Action::from_name(&action_name) {
+                    Some(action) if KeyMap::is_available(context, action) => action,
+                    Some(_) => {
+                        problems.push(format!(
+                            "'{action_name}' can't be bound in the {context_name} context"
+                        ));
+                        continue;
+                    }
+                    None => {
+                        problems.push(format!("unknown action '{action_name}'"));
+                        continue;
+                    }
+                };
+
+                let sequences: Result<Vec<_>, _> = sequences
+                    .into_vec()
+                    .iter()
+                    .map(|keys| parse_sequence(keys))
+                    .collect();
+
+                match sequences {
+                    Ok(sequences) => self.keymap.bind(context, action, sequences),
+                    Err(e) => problems.push(format!("{context_name}.{action_name}: {e}")),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,610 @@
+use std::{collections::HashMap, env, path::PathBuf, sync::Arc};
+
+use once_cell::sync::Lazy;
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+use syntect::highlighting::{Theme as SyntaxTheme, ThemeSet};
+
+use super::config_dir;
+use crate::external::expand_home;
+
+/// Syntax themes bundled with syntect
+pub static SYNTAX_THEMES: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
+
+pub const BUILT_IN_THEMES: [&str; 3] = ["dark", "light", "high-contrast"];
+
+/// How far `extends` may chain user themes
+const MAX_EXTENDS_DEPTH: usize = 8;
+
+#[derive(Clone, Copy, Default, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// Colors as given by the theme
+    #[default]
+    Full,
+    /// Only the 16 colors of the terminal palette, text in the default
+    /// foreground
+    Terminal,
+    /// No colors at all, like when `NO_COLOR` is set
+    None,
+}
+
+/// A theme file from the `themes` directory of the config
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeFile {
+    /// Theme to start from, `dark` if missing
+    extends: Option<String>,
+    syntax: Option<String>,
+    colors: HashMap<String, String>,
+}
+
+#[derive(Clone)]
+pub struct Theme {
+    pub mode: ColorMode,
+
+    pub text: Color,
+    /// Secondary text like placeholders and punctuation
+    pub muted: Color,
+    /// Labels and counters that stand out, like table headers
+    pub accent: Color,
+    pub error: Color,
+
+    pub border: Color,
+    pub border_selected: Color,
+    pub border_insert: Color,
+    /// Selected tab, table row or option
+    pub highlight: Color,
+
+    pub cursor: Color,
+    pub cursor_normal: Color,
+    /// Text drawn over the cursor and search matches
+    pub background: Color,
+    pub selection: Color,
+    pub search_match: Color,
+    pub search_current: Color,
+
+    pub method_get: Color,
+    pub method_post: Color,
+    pub method_put: Color,
+    pub method_delete: Color,
+    pub method_patch: Color,
+
+    pub status_success: Color,
+    pub status_redirect: Color,
+    pub status_client_error: Color,
+    pub status_server_error: Color,
+
+    pub json_key: Color,
+    pub json_string: Color,
+    pub json_number: Color,
+    pub json_bool: Color,
+
+    pub hex_null: Color,
+    pub hex_printable: Color,
+    pub hex_whitespace: Color,
+    pub hex_other: Color,
+
+    /// Name or path of the syntax theme, `None` without highlighting
+    pub syntax_name: Option<String>,
+    pub syntax: Option<Arc<SyntaxTheme>>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// Names of the colors a theme file can set
+    pub const COLORS: [&'static str; 31] = [
+        "text",
+        "muted",
+        "accent",
+        "error",
+        "border",
+        "border_selected",
+        "border_insert",
+        "highlight",
+        "cursor",
+        "cursor_normal",
+        "background",
+        "selection",
+        "search_match",
+        "search_current",
+        "method_get",
+        "method_post",
+        "method_put",
+        "method_delete",
+        "method_patch",
+        "status_success",
+        "status_redirect",
+        "status_client_error",
+        "status_server_error",
+        "json_key",
+        "json_string",
+        "json_number",
+        "json_bool",
+        "hex_null",
+        "hex_printable",
+        "hex_whitespace",
+        "hex_other",
+    ];
+
+    fn color_mut(&mut self, name: &str) -> Option<&mut Color> {
+        Some(match name {
+            "text" => &mut self.text,
+            "muted" => &mut self.muted,
+            "accent" => &mut self.accent,
+            "error" => &mut self.error,
+            "border" => &mut self.border,
+            "border_selected" => &mut self.border_selected,
+            "border_insert" => &mut self.border_insert,
+            "highlight" => &mut self.highlight,
+            "cursor" => &mut self.cursor,
+            "cursor_normal" => &mut self.cursor_normal,
+            "background" => &mut self.background,
+            "selection" => &mut self.selection,
+            "search_match" => &mut self.search_match,
+            "search_current" => &mut self.search_current,
+            "method_get" => &mut self.method_get,
+            "method_post" => &mut self.method_post,
+            "method_put" => &mut self.method_put,
+            "method_delete" => &mut self.method_delete,
+            "method_patch" => &mut self.method_patch,
+            "status_success" => &mut self.status_success,
+            "status_redirect" => &mut self.status_redirect,
+            "status_client_error" => &mut self.status_client_error,
+            "status_server_error" => &mut self.status_server_error,
+            "json_key" => &mut self.json_key,
+            "json_string" => &mut self.json_string,
+            "json_number" => &mut self.json_number,
+            "json_bool" => &mut self.json_bool,
+            "hex_null" => &mut self.hex_null,
+            "hex_printable" => &mut self.hex_printable,
+            "hex_whitespace" => &mut self.hex_whitespace,
+            "hex_other" => &mut self.hex_other,
+            _ => return None,
+        })
+    }
+
+    pub fn dark() -> Self {
+        Self {
+            mode: ColorMode::Full,
+            text: Color::White,
+            muted: Color::DarkGray,
+            accent: Color::Yellow,
+            error: Color::Red,
+            border: Color::White,
+            border_selected: Color::Blue,
+            border_insert: Color::Green,
+            highlight: Color::Green,
+            cursor: Color::Green,
+            cursor_normal: Color::Blue,
+            background: Color::Black,
+            selection: Color::DarkGray,
+            search_match: Color::Yellow,
+            search_current: Color::LightRed,
+            method_get: Color::Green,
+            method_post: Color::Blue,
+            method_put: Color::Yellow,
+            method_delete: Color::Red,
+            method_patch: Color::Magenta,
+            status_success: Color::Green,
+            status_redirect: Color::Blue,
+            status_client_error: Color::Yellow,
+            status_server_error: Color::Red,
+            json_key: Color::Blue,
+            json_string: Color::Green,
+            json_number: Color::Yellow,
+            json_bool: Color::Magenta,
+            hex_null: Color::DarkGray,
+            hex_printable: Color::Cyan,
+            hex_whitespace: Color::Yellow,
+            hex_other: Color::Magenta,
+            syntax_name: Some("base16-ocean.dark".to_string()),
+            syntax: SYNTAX_THEMES
+                .themes
+                .get("base16-ocean.dark")
+                .cloned()
+                .map(Arc::new),
+        }
+    }
+
+    pub fn light() -> Self {
+        let amber = Color::Rgb(0xb5, 0x89, 0x00);
+
+        Self {
+            text: Color::Black,
+            muted: Color::DarkGray,
+            accent: amber,
+            border: Color::DarkGray,
+            border_selected: Color::Blue,
+            border_insert: Color::Green,
+            highlight: Color::Blue,
+            background: Color::White,
+            selection: Color::Gray,
+            search_match: Color::LightYellow,
+            search_current: Color::LightRed,
+            method_put: amber,
+            status_client_error: amber,
+            json_number: amber,
+            hex_printable: Color::Blue,
+            hex_whitespace: amber,
+            ..Self::dark()
+        }
+        .with_syntax("InspiredGitHub")
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            text: Color::White,
+            muted: Color::Gray,
+            accent: Color::LightYellow,
+            error: Color::LightRed,
+            border: Color::White,
+            border_selected: Color::LightCyan,
+            border_insert: Color::LightGreen,
+            highlight: Color::LightGreen,
+            cursor: Color::LightGreen,
+            cursor_normal: Color::LightCyan,
+            background: Color::Black,
+            selection: Color::Blue,
+            search_match: Color::LightYellow,
+            search_current: Color::LightMagenta,
+            method_get: Color::LightGreen,
+            method_post: Color::LightCyan,
+            method_put: Color::LightYellow,
+            method_delete: Color::LightRed,
+            method_patch: Color::LightMagenta,
+            status_success: Color::LightGreen,
+            status_redirect: Color::LightCyan,
+            status_client_error: Color::LightYellow,
+            status_server_error: Color::LightRed,
+            json_key: Color::LightCyan,
+            json_string: Color::LightGreen,
+            json_number: Color::LightYellow,
+            json_bool: Color::LightMagenta,
+            hex_null: Color::Gray,
+            hex_printable: Color::LightCyan,
+            hex_whitespace: Color::LightYellow,
+            hex_other: Color::LightMagenta,
+            ..Self::dark()
+        }
+        .with_syntax("base16-eighties.dark")
+    }
+
+    fn with_syntax(mut self, name: &str) -> Self {
+        self.syntax = SYNTAX_THEMES.themes.get(name).cloned().map(Arc::new);
+        self.syntax_name = Some(name.to_string());
+        self
+    }
+
+    /// Build the theme called `name`, a built-in one or a file in the
+    /// `themes` directory of the config, then apply the syntax theme and
+    /// color mode over it. Problems are pushed to `problems` and the
+    /// defaults used instead
+    pub fn load(
+        name: Option<&str>,
+        syntax: Option<&str>,
+        mode: ColorMode,
+        problems: &mut Vec<String>,
+    ) -> Self {
+        let mut theme = match name {
+            Some(name) => Self::named(name, 0, problems),
+            None => Self::default(),
+        };
+
+        if let Some(syntax) = syntax {
+            match load_syntax_theme(syntax) {
+                Ok(syntax_theme) => {
+                    theme.syntax = Some(Arc::new(syntax_theme));
+                    theme.syntax_name = Some(syntax.to_string());
+                }
+                Err(e) => problems.push(e),
+            }
+        }
+
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+        theme.with_mode(if no_color { ColorMode::None } else { mode })
+    }
+
+    fn named(name: &str, depth: usize, problems: &mut Vec<String>) -> Self {
+        match name {
+            "dark" => return Self::dark(),
+            "light" => return Self::light(),
+            "high-contrast" => return Self::high_contrast(),
+            _ => {}
+        }
+
+        if depth >= MAX_EXTENDS_DEPTH {
+            problems.push(format!("theme '{name}' extends too many themes"));
+            return Self::default();
+        }
+
+        let unknown = || {
+            format!(
+                "unknown theme '{name}', expected a file in the themes directory or one of: {}",
+                BUILT_IN_THEMES.join(", ")
+            )
+        };
+
+        let Some(path) = theme_path(name) else {
+            problems.push(unknown());
+            return Self::default();
+        };
+
+        let file = match std::fs::read_to_string(&path) {
+            Ok(text) => match serde_yaml::from_str::<Option<ThemeFile>>(&text) {
+                Ok(file) => file.unwrap_or_default(),
+                Err(e) => {
+                    problems.push(format!("{}: {e}", path.display()));
+                    return Self::default();
+                }
+            },
+            Err(_) => {
+                problems.push(unknown());
+                return Self::default();
+            }
+        };
+
+        let mut theme = match file.extends.as_deref() {
+            Some(parent) => Self::named(parent, depth + 1, problems),
+            None => Self::default(),
+        };
+
+        for (color_name, value) in file.colors {
+            match (theme.color_mut(&color_name), parse_color(&value)) {
+                (Some(color), Some(value)) => *color = value,
+                (None, _) => problems.push(format!("{name}: unknown color '{color_name}'")),
+                (_, None) => problems.push(format!("{name}.{color_name}: invalid color '{value}'")),
+            }
+        }
+
+        if let Some(syntax) = file.syntax {
+            match load_syntax_theme(&syntax) {
+                Ok(syntax_theme) => {
+                    theme.syntax = Some(Arc::new(syntax_theme));
+                    theme.syntax_name = Some(syntax);
+                }
+                Err(e) => problems.push(format!("{name}: {e}")),
+            }
+        }
+
+        theme
+    }
+
+    fn with_mode(mut self, mode: ColorMode) -> Self {
+        self.mode = mode;
+
+        match mode {
+            ColorMode::Full => {}
+            ColorMode::Terminal => {
+                for name in Self::COLORS {
+                    if let Some(color) = self.color_mut(name) {
+                        *color = to_terminal_color(*color);
+                    }
+                }
+
+                self.text = Color::Reset;
+                self.border = Color::Reset;
+            }
+            ColorMode::None => {
+                for name in Self::COLORS {
+                    if let Some(color) = self.color_mut(name) {
+                        *color = Color::Reset;
+                    }
+                }
+
+                self.syntax = None;
+                self.syntax_name = None;
+            }
+        }
+
+        self
+    }
+
+    /// Color of a syntax highlighted token, `None` keeps the text color
+    pub fn syntax_color(&self, color: syntect::highlighting::Color) -> Option<Color> {
+        match color {
+            syntect::highlighting::Color { a: 0, .. } => None,
+            syntect::highlighting::Color { r, g, b, .. } => match self.mode {
+                ColorMode::Full => Some(Color::Rgb(r, g, b)),
+                ColorMode::Terminal => Some(nearest_ansi((r, g, b))),
+                ColorMode::None => None,
+            },
+        }
+    }
+
+    fn is_colorless(&self) -> bool {
+        self.mode == ColorMode::None
+    }
+
+    /// `color` as foreground, or bold text when colors are off
+    pub fn emphasis(&self, color: Color) -> Style {
+        match self.is_colorless() {
+            true => Style::default().add_modifier(Modifier::BOLD),
+            false => Style::default().fg(color),
+        }
+    }
+
+    pub fn border(&self, is_selected: bool, is_editing: bool) -> Style {
+        match (is_selected, is_editing) {
+            (true, true) => self.emphasis(self.border_insert),
+            (true, false) => self.emphasis(self.border_selected),
+            _ => Style::default().fg(self.border),
+        }
+    }
+
+    pub fn cursor(&self, normal_mode: bool) -> Style {
+        if self.is_colorless() {
+            return Style::default().add_modifier(Modifier::REVERSED);
+        }
+
+        Style::default()
+            .bg(match normal_mode {
+                true => self.cursor_normal,
+                false => self.cursor,
+            })
+            .fg(self.background)
+    }
+
+    pub fn selection(&self) -> Style {
+        match self.is_colorless() {
+            true => Style::default().add_modifier(Modifier::UNDERLINED),
+            false => Style::default().bg(self.selection),
+        }
+    }
+
+    pub fn search_match(&self, is_current: bool) -> Style {
+        match (self.is_colorless(), is_current) {
+            (true, true) => Style::default().add_modifier(Modifier::REVERSED),
+            (true, false) => Style::default().add_modifier(Modifier::UNDERLINED),
+            (false, true) => Style::default().bg(self.search_current).fg(self.background),
+            (false, false) => Style::default().bg(self.search_match).fg(self.background),
+        }
+    }
+
+    /// The selected row of a list
+    pub fn selected_row(&self) -> Style {
+        match self.is_colorless() {
+            true => Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            false => Style::default()
+                .bg(self.selection)
+                .add_modifier(Modifier::BOLD),
+        }
+    }
+}
+
+/// `<config dir>/themes/<name>.yaml`, or `name` itself when it's a path
+fn theme_path(name: &str) -> Option<PathBuf> {
+    if name.ends_with(".yaml") || name.ends_with(".yml") {
+        return Some(expand_home(name));
+    }
+
+    config_dir().map(|dir| dir.join("themes").join(format!("{name}.yaml")))
+}
+
+/// A syntax theme bundled with syntect by name, or a `.tmTheme` file
+fn load_syntax_theme(name: &str) -> Result<SyntaxTheme, String> {
+    if let Some(theme) = SYNTAX_THEMES.themes.get(name) {
+        return Ok(theme.clone());
+    }
+
+    if !name.ends_with(".tmTheme") {
+        let mut names: Vec<_> = SYNTAX_THEMES.themes.keys().map(String::as_str).collect();
+        names.sort_unstable();
+
+        return Err(format!(
+            "unknown syntax theme '{name}', expected a .tmTheme file or one of: {}",
+            names.join(", ")
+        ));
+    }
+
+    let mut path = expand_home(name);
+
+    if path.is_relative() {
+        if let Some(dir) = config_dir() {
+            path = dir.join(path);
+        }
+    }
+
+    ThemeSet::get_theme(&path).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// A color name like `light-blue`, `#rrggbb` or a 256 color palette index
+pub fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim().to_lowercase().replace(['_', ' '], "-");
+
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+
+        return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+    }
+
+    if let Ok(index) = value.parse::<u8>() {
+        return Some(Color::Indexed(index));
+    }
+
+    Some(match value.as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "dark-gray" | "dark-grey" | "darkgray" | "darkgrey" => Color::DarkGray,
+        "light-red" => Color::LightRed,
+        "light-green" => Color::LightGreen,
+        "light-yellow" => Color::LightYellow,
+        "light-blue" => Color::LightBlue,
+        "light-magenta" => Color::LightMagenta,
+        "light-cyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return None,
+    })
+}
+
+/// The 16 terminal colors with their usual xterm values
+const ANSI_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+fn nearest_ansi((r, g, b): (u8, u8, u8)) -> Color {
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        [(r, r2), (g, g2), (b, b2)]
+            .into_iter()
+            .map(|(a, b)| (i32::from(a) - i32::from(b)).pow(2))
+            .sum::<i32>()
+    };
+
+    ANSI_COLORS
+        .into_iter()
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map(|(color, _)| color)
+        .unwrap_or(Color::Reset)
+}
+
+/// Map a color to the closest one of the terminal palette
+fn to_terminal_color(color: Color) -> Color {
+    match color {
+        Color::Rgb(r, g, b) => nearest_ansi((r, g, b)),
+        Color::Indexed(index) if index < 16 => ANSI_COLORS[usize::from(index)].0,
+        Color::Indexed(index) if index < 232 => {
+            let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+            let index = index - 16;
+
+            nearest_ansi((level(index / 36), level(index / 6 % 6), level(index % 6)))
+        }
+        Color::Indexed(index) => {
+            let gray = 8 + (index - 232) * 10;
+
+            nearest_ansi((gray, gray, gray))
+        }
+        color => color,
+    }
+}
@@ -0,0 +1,85 @@
+/// The formats tuapi knows how to highlight and pretty print
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ContentKind {
+    Json,
+    Xml,
+    Html,
+    Yaml,
+    Csv,
+    Form,
+    Javascript,
+    Css,
+    Text,
+    Binary,
+}
+
+/// The `type/subtype` part of a content type, lowercased and without
+/// parameters like `charset`
+pub fn essence(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_lowercase()
+}
+
+pub fn classify(content_type: &str) -> ContentKind {
+    let essence = essence(content_type);
+    let (kind, subtype) = essence.split_once('/').unwrap_or((essence.as_str(), ""));
+
+    // Structured syntax suffixes, e.g. `application/problem+json`
+    if subtype.ends_with("+json") {
+        return ContentKind::Json;
+    }
+
+    if subtype.ends_with("+xml") {
+        return ContentKind::Xml;
+    }
+
+    match (kind, subtype) {
+        (_, "json") | (_, "x-ndjson") | (_, "json-seq") => ContentKind::Json,
+        (_, "xml") => ContentKind::Xml,
+        ("text", "html") => ContentKind::Html,
+        (_, "yaml") | (_, "x-yaml") => ContentKind::Yaml,
+        ("text", "csv") | ("text", "tab-separated-values") => ContentKind::Csv,
+        ("application", "x-www-form-urlencoded") => ContentKind::Form,
+        (_, "javascript") | (_, "ecmascript") => ContentKind::Javascript,
+        ("text", "css") => ContentKind::Css,
+        ("image", _) | ("audio", _) | ("video", _) | ("font", _) => ContentKind::Binary,
+        (
+            "application",
+            "octet-stream"
+            | "pdf"
+            | "zip"
+            | "gzip"
+            | "x-gzip"
+            | "x-tar"
+            | "x-7z-compressed"
+            | "wasm"
+            | "protobuf"
+            | "x-protobuf"
+            | "vnd.google.protobuf"
+            | "grpc"
+            | "msgpack"
+            | "x-msgpack"
+            | "cbor",
+        ) => ContentKind::Binary,
+        _ => ContentKind::Text,
+    }
+}
+
+impl ContentKind {
+    /// Extension used to find the syntect syntax
+    pub fn syntax_extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Xml => "xml",
+            Self::Html => "html",
+            Self::Yaml => "yaml",
+            Self::Javascript => "js",
+            Self::Css => "css",
+            Self::Csv | Self::Form | Self::Text | Self::Binary => "txt",
+        }
+    }
+}
@@ -0,0 +1,594 @@
+use std::{fs, path::Path};
+
+use percent_encoding::percent_decode_str;
+use regex::Regex;
+use serde_json::Value;
+use url::Url;
+
+use crate::{app::Response, content_type};
+
+/// Deepest nesting of schemas followed, recursive schemas stop there
+const MAX_DEPTH: usize = 32;
+
+static NULL: Value = Value::Null;
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_i64() || number.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn has_type(value: &Value, kind: &str) -> bool {
+    match kind {
+        "integer" => value.as_f64().is_some_and(|number| number.fract() == 0.0),
+        "number" => value.is_number(),
+        kind => type_name(value) == kind,
+    }
+}
+
+/// The non-empty segments of the path of a URL, absolute or not
+fn path_segments(url: &str) -> Vec<String> {
+    let path = match Url::parse(url) {
+        Ok(url) => url.path().to_string(),
+        Err(_) => url.split(['?', '#']).next().unwrap_or_default().to_string(),
+    };
+
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| percent_decode_str(segment).decode_utf8_lossy().to_string())
+        .collect()
+}
+
+/// How many literal segments of a path like `/users/{id}` match the end of
+/// `segments`, if all of them do
+fn path_score(template: &str, segments: &[String]) -> Option<usize> {
+    let template = path_segments(template);
+
+    let start = segments.len().checked_sub(template.len())?;
+    let mut score = 0;
+
+    for (part, segment) in template.iter().zip(&segments[start..]) {
+        match part.starts_with('{') && part.ends_with('}') {
+            true => {}
+            false if part == segment => score += 1,
+            false => return None,
+        }
+    }
+
+    Some(score)
+}
+
+/// An OpenAPI 3 or Swagger 2.0 spec responses are checked against
+pub struct Contract {
+    root: Value,
+    is_swagger: bool,
+}
+
+impl Contract {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let source = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+
+        // JSON is read as YAML too
+        let root: Value =
+            serde_yaml::from_str(&source).map_err(|e| format!("{}: {e}", path.display()))?;
+
+        let is_swagger = root.get("swagger").is_some();
+
+        Ok(Self { root, is_swagger })
+    }
+
+    /// Follow local `$ref`s
+    fn resolve<'a>(&'a self, value: &'a Value) -> &'a Value {
+        let mut value = value;
+
+        for _ in 0..MAX_DEPTH {
+            let Some(reference) = value.get("$ref").and_then(Value::as_str) else {
+                return value;
+            };
+
+            let pointer = reference
+                .strip_prefix('#')
+                .map(|pointer| percent_decode_str(pointer).decode_utf8_lossy().to_string());
+
+            match pointer.and_then(|pointer| self.root.pointer(&pointer)) {
+                Some(target) => value = target,
+                None => return &NULL,
+            }
+        }
+
+        value
+    }
+
+    /// Paths the operations are under, from the servers or `basePath`
+    fn base_paths(&self) -> Vec<Vec<String>> {
+        let mut bases = vec![Vec::new()];
+
+        match self.is_swagger {
+            true => bases.extend(
+                self.root
+                    .get("basePath")
+                    .and_then(Value::as_str)
+                    .map(path_segments),
+            ),
+            false => bases.extend(
+                self.root
+                    .get("servers")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|server| server.get("url")?.as_str())
+                    .map(path_segments),
+            ),
+        }
+
+        bases
+    }
+
+    /// The operation a request is for, like `GET /users/{id}`: the one it
+    /// was imported from while its method and URL still match it, else the
+    /// path of the spec with the most literal segments matching the URL
+    pub fn operation(&self, method: &str, url: &str, imported: Option<&str>) -> Option<String> {
+        let segments = path_segments(url);
+        let bases = self.base_paths();
+
+        let score = |path: &str| {
+            let score = path_score(path, &segments)?;
+            let prefix = &segments[..segments.len() - path_segments(path).len()];
+
+            bases
+                .iter()
+                .any(|base| base.as_slice() == prefix)
+                .then_some(score)
+        };
+
+        let declares = |path: &str| {
+            self.root
+                .get("paths")
+                .and_then(|paths| paths.get(path))
+                .map(|item| self.resolve(item))
+                .is_some_and(|item| item.get(method.to_lowercase()).is_some())
+        };
+
+        if let Some((imported_method, path)) =
+            imported.and_then(|operation| operation.split_once(' '))
+        {
+            if imported_method.eq_ignore_ascii_case(method) && score(path).is_some() {
+                return imported.map(str::to_string);
+            }
+        }
+
+        let paths = self.root.get("paths").and_then(Value::as_object)?;
+
+        paths
+            .keys()
+            .filter(|path| declares(path))
+            .filter_map(|path| score(path).map(|score| (path, score)))
+            // The first of the best, as `max_by_key` keeps the last
+            .rev()
+            .max_by_key(|(_, score)| *score)
+            .map(|(path, _)| format!("{} {path}", method.to_uppercase()))
+    }
+
+    /// What is wrong with a response to an `operation`, like
+    /// `GET /users/{id}`
+    pub fn check(&self, operation: &str, response: &Response) -> Vec<String> {
+        let (method, path) = operation.split_once(' ').unwrap_or(("GET", operation));
+
+        let Some(declared) = self
+            .root
+            .get("paths")
+            .and_then(|paths| paths.get(path))
+            .map(|item| self.resolve(item))
+            .and_then(|item| item.get(method.to_lowercase()))
+        else {
+            return vec![format!("{operation} is not in the spec")];
+        };
+
+        let responses = declared.get("responses").and_then(Value::as_object);
+
+        let status = response.status_code.to_string();
+        let range = format!("{}XX", &status[..1]);
+
+        let declared_response = responses.and_then(|responses| {
+            responses.get(&status).or_else(|| {
+                responses
+                    .iter()
+                    .find(|(code, _)| code.eq_ignore_ascii_case(&range))
+                    .map(|(_, response)| response)
+                    .or_else(|| responses.get("default"))
+            })
+        });
+
+        let Some(declared_response) = declared_response.map(|response| self.resolve(response))
+        else {
+            return vec![format!("undeclared status code {status}")];
+        };
+
+        let essence = content_type::essence(&response.content_type);
+
+        let schema = match self.is_swagger {
+            true => declared_response.get("schema"),
+            false => {
+                let Some(content) = declared_response
+                    .get("content")
+                    .and_then(Value::as_object)
+                    .filter(|content| !content.is_empty())
+                else {
+                    return Vec::new();
+                };
+
+                let wildcard = format!("{}/*", essence.split('/').next().unwrap_or_default());
+
+                let media = content
+                    .get(&essence)
+                    .or_else(|| content.get(&wildcard))
+                    .or_else(|| content.get("*/*"));
+
+                let Some(media) = media else {
+                    let declared: Vec<&str> = content.keys().map(String::as_str).collect();
+
+                    return vec![format!(
+                        "undeclared content type {essence}, expected {}",
+                        declared.join(" or ")
+                    )];
+                };
+
+                media.get("schema")
+            }
+        };
+
+        let Some(schema) = schema else {
+            return Vec::new();
+        };
+
+        if !essence.contains("json") {
+            return Vec::new();
+        }
+
+        let body: Value = match serde_json::from_str(&response.text) {
+            Ok(body) => body,
+            Err(e) => return vec![format!("the body is not valid JSON: {e}")],
+        };
+
+        let mut violations = Vec::new();
+
+        self.validate(schema, &body, "$", &mut violations, 0);
+
+        violations
+    }
+
+    /// Whether a value matches a schema, without collecting why not
+    fn matches(&self, schema: &Value, value: &Value, depth: usize) -> bool {
+        let mut violations = Vec::new();
+
+        self.validate(schema, value, "$", &mut violations, depth);
+
+        violations.is_empty()
+    }
+
+    fn validate(
+        &self,
+        schema: &Value,
+        value: &Value,
+        path: &str,
+        violations: &mut Vec<String>,
+        depth: usize,
+    ) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+
+        let schema = self.resolve(schema);
+
+        if schema == &Value::Bool(false) {
+            violations.push(format!("{path}: not allowed"));
+            return;
+        }
+
+        let nullable = schema.get("nullable").and_then(Value::as_bool) == Some(true)
+            || schema.get("x-nullable").and_then(Value::as_bool) == Some(true);
+
+        if value.is_null() && nullable {
+            return;
+        }
+
+        for part in schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            self.validate(part, value, path, violations, depth + 1);
+        }
+
+        for key in ["oneOf", "anyOf"] {
+            let Some(choices) = schema.get(key).and_then(Value::as_array) else {
+                continue;
+            };
+
+            if !choices
+                .iter()
+                .any(|choice| self.matches(choice, value, depth + 1))
+            {
+                violations.push(format!("{path}: matches none of the schemas of {key}"));
+            }
+        }
+
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            if !values.contains(value) {
+                violations.push(format!("{path}: {value} is not one of the allowed values"));
+            }
+        }
+
+        if let Some(constant) = schema.get("const") {
+            if constant != value {
+                violations.push(format!("{path}: expected {constant}, got {value}"));
+            }
+        }
+
+        let kinds: Vec<&str> = match schema.get("type") {
+            Some(Value::String(kind)) => vec![kind.as_str()],
+            // OpenAPI 3.1 lists the types, possibly with null
+            Some(Value::Array(kinds)) => kinds.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+
+        if !kinds.is_empty() && !kinds.iter().any(|kind| has_type(value, kind)) {
+            violations.push(format!(
+                "{path}: expected {}, got {}",
+                kinds.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+
+        match value {
+            Value::Object(object) => {
+                for required in schema
+                    .get("required")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                {
+                    if !object.contains_key(required) {
+                        violations.push(format!("{path}: missing required field '{required}'"));
+                    }
+                }
+
+                let properties = schema.get("properties").and_then(Value::as_object);
+                let additional = schema.get("additionalProperties");
+
+                for (key, field) in object {
+                    match properties.and_then(|properties| properties.get(key)) {
+                        Some(property) => self.validate(
+                            property,
+                            field,
+                            &format!("{path}.{key}"),
+                            violations,
+                            depth + 1,
+                        ),
+                        None => match additional {
+                            Some(Value::Bool(false)) => {
+                                violations.push(format!("{path}: unexpected field '{key}'"))
+                            }
+                            Some(additional) if additional.is_object() => self.validate(
+                                additional,
+                                field,
+                                &format!("{path}.{key}"),
+                                violations,
+                                depth + 1,
+                            ),
+                            _ => {}
+                        },
+                    }
+                }
+            }
+            Value::Array(items) => {
+                let count = items.len() as u64;
+
+                if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                    if count < min {
+                        violations.push(format!("{path}: {count} items, at least {min} expected"));
+                    }
+                }
+
+                if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                    if count > max {
+                        violations.push(format!("{path}: {count} items, at most {max} expected"));
+                    }
+                }
+
+                if let Some(item_schema) = schema.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        let path = format!("{path}[{index}]");
+
+                        self.validate(item_schema, item, &path, violations, depth + 1);
+                    }
+                }
+            }
+            Value::String(text) => {
+                let length = text.chars().count() as u64;
+
+                if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                    if length < min {
+                        violations.push(format!("{path}: shorter than {min} characters"));
+                    }
+                }
+
+                if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                    if length > max {
+                        violations.push(format!("{path}: longer than {max} characters"));
+                    }
+                }
+
+                let pattern = schema
+                    .get("pattern")
+                    .and_then(Value::as_str)
+                    .and_then(|pattern| Regex::new(pattern).ok());
+
+                if let Some(pattern) = pattern {
+                    if !pattern.is_match(text) {
+                        violations.push(format!("{path}: doesn't match {pattern}"));
+                    }
+                }
+            }
+            Value::Number(number) => {
+                let number = number.as_f64().unwrap_or_default();
+
+                // A boolean in OpenAPI 3.0, the bound itself in 3.1
+                let exclusive = |key: &str| schema.get(key).and_then(Value::as_bool) == Some(true);
+
+                if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                    if number < min || (exclusive("exclusiveMinimum") && number == min) {
+                        violations.push(format!("{path}: {number} is below the minimum {min}"));
+                    }
+                }
+
+                if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                    if number > max || (exclusive("exclusiveMaximum") && number == max) {
+                        violations.push(format!("{path}: {number} is above the maximum {max}"));
+                    }
+                }
+
+                if let Some(min) = schema.get("exclusiveMinimum").and_then(Value::as_f64) {
+                    if number <= min {
+                        violations.push(format!("{path}: {number} is not above {min}"));
+                    }
+                }
+
+                if let Some(max) = schema.get("exclusiveMaximum").and_then(Value::as_f64) {
+                    if number >= max {
+                        violations.push(format!("{path}: {number} is not below {max}"));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract(source: &str) -> Contract {
+        let root: Value = serde_yaml::from_str(source).unwrap();
+
+        Contract {
+            is_swagger: root.get("swagger").is_some(),
+            root,
+        }
+    }
+
+    const SPEC: &str = "
+openapi: 3.0.0
+servers:
+  - url: https://api.example.com/v1
+paths:
+  /users/{id}:
+    get: {responses: {'200': {description: ok}}}
+    delete: {responses: {'204': {description: gone}}}
+  /users/me:
+    get: {responses: {'200': {description: ok}}}
+  /:
+    get: {responses: {'200': {description: ok}}}
+";
+
+    #[test]
+    fn resolves_the_most_specific_path() {
+        let contract = contract(SPEC);
+
+        let operation = |method, url| contract.operation(method, url, None);
+
+        assert_eq!(
+            operation("GET", "https://api.example.com/v1/users/42?x=1"),
+            Some("GET /users/{id}".to_string())
+        );
+        assert_eq!(
+            operation("GET", "http://localhost:4010/v1/users/me"),
+            Some("GET /users/me".to_string())
+        );
+        assert_eq!(
+            operation("GET", "http://localhost:4010/users/me"),
+            Some("GET /users/me".to_string())
+        );
+        assert_eq!(
+            operation("GET", "https://api.example.com/v1/"),
+            Some("GET /".to_string())
+        );
+        assert_eq!(
+            operation("PUT", "https://api.example.com/v1/users/42"),
+            None
+        );
+        assert_eq!(
+            operation("GET", "https://api.example.com/v1/orders/42"),
+            None
+        );
+        assert_eq!(
+            operation("GET", "https://api.example.com/v2/users/42"),
+            None
+        );
+    }
+
+    #[test]
+    fn keeps_the_imported_operation_while_it_matches() {
+        let contract = contract(SPEC);
+
+        // `/users/me` matches `/users/{id}` too
+        assert_eq!(
+            contract.operation(
+                "GET",
+                "https://api.example.com/v1/users/me",
+                Some("GET /users/{id}")
+            ),
+            Some("GET /users/{id}".to_string())
+        );
+
+        // Edited to another method or path
+        assert_eq!(
+            contract.operation(
+                "DELETE",
+                "https://api.example.com/v1/users/1",
+                Some("GET /users/{id}")
+            ),
+            Some("DELETE /users/{id}".to_string())
+        );
+        assert_eq!(
+            contract.operation(
+                "GET",
+                "https://api.example.com/v1/users",
+                Some("GET /users/{id}")
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn uses_the_swagger_base_path() {
+        let contract = contract(
+            "
+swagger: '2.0'
+basePath: /api
+paths:
+  /pets:
+    get: {responses: {'200': {description: ok}}}
+",
+        );
+
+        assert_eq!(
+            contract.operation("GET", "http://localhost/api/pets", None),
+            Some("GET /pets".to_string())
+        );
+        assert_eq!(
+            contract.operation("GET", "http://localhost/other/pets", None),
+            None
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use serde_json::{Map, Value};
+
+/// Most lines compared one to one, as `rows * columns`, longer texts are
+/// shown as entirely replaced
+const MAX_CELLS: usize = 1_000_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+impl Change {
+    pub fn is_same(&self) -> bool {
+        matches!(self, Self::Same(_))
+    }
+}
+
+/// The lines of `old` and `new`, in order, marked as kept, removed or added
+pub fn lines(old: &str, new: &str) -> Vec<Change> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let middle_old = &old[prefix..old.len() - suffix];
+    let middle_new = &new[prefix..new.len() - suffix];
+
+    let mut changes: Vec<Change> = old[..prefix]
+        .iter()
+        .map(|line| Change::Same(line.to_string()))
+        .collect();
+
+    changes.extend(middle(middle_old, middle_new));
+
+    changes.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| Change::Same(line.to_string())),
+    );
+
+    changes
+}
+
+/// The longest common subsequence of lines, kept, with the others removed
+/// or added around it
+fn middle(old: &[&str], new: &[&str]) -> Vec<Change> {
+    let removed = || old.iter().map(|line| Change::Removed(line.to_string()));
+    let added = || new.iter().map(|line| Change::Added(line.to_string()));
+
+    if old.is_empty() || new.is_empty() || old.len() * new.len() > MAX_CELLS {
+        return removed().chain(added()).collect();
+    }
+
+    let columns = new.len() + 1;
+
+    // Length of the common subsequence of `old[i..]` and `new[j..]`
+    let mut lengths = vec![0u32; (old.len() + 1) * columns];
+
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i * columns + j] = match old[i] == new[j] {
+                true => lengths[(i + 1) * columns + j + 1] + 1,
+                false => lengths[(i + 1) * columns + j].max(lengths[i * columns + j + 1]),
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            changes.push(Change::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * columns + j] >= lengths[i * columns + j + 1] {
+            changes.push(Change::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            changes.push(Change::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+
+    changes.extend(
+        old[i..]
+            .iter()
+            .map(|line| Change::Removed(line.to_string())),
+    );
+    changes.extend(new[j..].iter().map(|line| Change::Added(line.to_string())));
+
+    changes
+}
+
+/// A path of two JSON documents whose value differs, like `$.items[0].id`
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathChange {
+    Added(String, Value),
+    Removed(String, Value),
+    Changed(String, Value, Value),
+}
+
+/// The same value with the keys of its objects sorted, so that their order
+/// doesn't show as a difference
+pub fn canonical(value: &Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut keys: Vec<&String> = object.keys().collect();
+            keys.sort();
+
+            Value::Object(
+                keys.into_iter()
+                    .map(|key| (key.clone(), canonical(&object[key])))
+                    .collect::<Map<String, Value>>(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonical).collect()),
+        value => value.clone(),
+    }
+}
+
+/// The paths whose values differ between `old` and `new`, objects compared
+/// by key and arrays by index
+pub fn json(old: &Value, new: &Value) -> Vec<PathChange> {
+    let mut changes = Vec::new();
+
+    compare(old, new, "$", &mut changes);
+
+    changes
+}
+
+fn compare(old: &Value, new: &Value, path: &str, changes: &mut Vec<PathChange>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                let path = format!("{path}.{key}");
+
+                match (old.get(key), new.get(key)) {
+                    (Some(old), Some(new)) => compare(old, new, &path, changes),
+                    (Some(old), None) => changes.push(PathChange::Removed(path, old.clone())),
+                    (None, Some(new)) => changes.push(PathChange::Added(path, new.clone())),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for index in 0..old.len().max(new.len()) {
+                let path = format!("{path}[{index}]");
+
+                match (old.get(index), new.get(index)) {
+                    (Some(old), Some(new)) => compare(old, new, &path, changes),
+                    (Some(old), None) => changes.push(PathChange::Removed(path, old.clone())),
+                    (None, Some(new)) => changes.push(PathChange::Added(path, new.clone())),
+                    (None, None) => {}
+                }
+            }
+        }
+        (old, new) if old != new => changes.push(PathChange::Changed(
+            path.to_string(),
+            old.clone(),
+            new.clone(),
+        )),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn same(line: &str) -> Change {
+        Change::Same(line.to_string())
+    }
+
+    fn removed(line: &str) -> Change {
+        Change::Removed(line.to_string())
+    }
+
+    fn added(line: &str) -> Change {
+        Change::Added(line.to_string())
+    }
+
+    #[test]
+    fn diffs_lines_around_their_common_subsequence() {
+        assert_eq!(
+            lines("a\nb\nc\nd\ne", "a\nc\nx\nd\ne\nf"),
+            vec![
+                same("a"),
+                removed("b"),
+                same("c"),
+                added("x"),
+                same("d"),
+                same("e"),
+                added("f"),
+            ]
+        );
+    }
+
+    #[test]
+    fn diffs_identical_and_empty_texts() {
+        assert_eq!(lines("a\nb", "a\nb"), vec![same("a"), same("b")]);
+        assert_eq!(lines("", "a"), vec![added("a")]);
+        assert_eq!(lines("a", ""), vec![removed("a")]);
+        assert!(lines("", "").is_empty());
+    }
+
+    #[test]
+    fn sorts_object_keys() {
+        let value = json!({"b": 1, "a": [{"d": 2, "c": 3}]});
+
+        assert_eq!(
+            canonical(&value).to_string(),
+            r#"{"a":[{"c":3,"d":2}],"b":1}"#
+        );
+    }
+
+    #[test]
+    fn diffs_json_by_path() {
+        let old = json!({"id": 1, "tags": ["a", "b"], "user": {"name": "x"}});
+        let new = json!({"id": 2, "tags": ["a"], "user": {"name": "x", "age": 3}});
+
+        assert_eq!(
+            json(&old, &new),
+            vec![
+                PathChange::Changed("$.id".to_string(), json!(1), json!(2)),
+                PathChange::Removed("$.tags[1]".to_string(), json!("b")),
+                PathChange::Added("$.user.age".to_string(), json!(3)),
+            ]
+        );
+        assert!(json(&old, &old).is_empty());
+    }
+}
@@ -0,0 +1,575 @@
+use std::str::FromStr;
+
+use reqwest::StatusCode;
+
+use crate::{
+    app::{
+        diff::{Diff, Source},
+        form::{Form, FormField, FormKind},
+        picker::{Picker, PickerItem, PickerKind},
+        App, AppPopup, BodyContentType, InputMode, RequestMethod, Response,
+    },
+    collection::Example,
+    http_file::encode_pairs,
+    plural::count,
+};
+
+fn open_picker(app: &mut App, picker: Picker) {
+    app.popup = Some(AppPopup::Picker(picker));
+    app.input_mode = InputMode::Insert;
+}
+
+/// List the requests of the `.http` file, or else of the collection, to
+/// load one
+pub fn open_requests(app: &mut App) {
+    if let Some(file) = app.http_file.as_ref() {
+        let items = file
+            .requests
+            .iter()
+            .enumerate()
+            .map(|(index, request)| PickerItem {
+                label: request.name.clone(),
+                detail: format!("{} {}", request.method, request.url),
+                value: Some(index),
+            })
+            .collect();
+
+        let title = file
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        open_picker(app, Picker::new(PickerKind::FileRequest, &title, items));
+        return;
+    }
+
+    let Some(collection) = app.collection.as_ref() else {
+        app.status_message = Some("No collection open, start with --collection".to_string());
+        return;
+    };
+
+    let items = collection
+        .requests()
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| PickerItem {
+            label: entry.label(),
+            detail: format!("{} {}", entry.request.method, entry.request.url),
+            value: Some(index),
+        })
+        .collect();
+
+    let title = collection.name.clone();
+
+    open_picker(app, Picker::new(PickerKind::Request, &title, items));
+}
+
+/// List the environments of the collection to switch to one
+pub fn open_environments(app: &mut App) {
+    let Some(collection) = app.collection.as_ref() else {
+        app.status_message = Some("No collection open, start with --collection".to_string());
+        return;
+    };
+
+    let items = std::iter::once(PickerItem {
+        label: "No environment".to_string(),
+        detail: String::new(),
+        value: None,
+    })
+    .chain(
+        collection
+            .environments
+            .iter()
+            .enumerate()
+            .map(|(index, environment)| PickerItem {
+                label: environment.name.clone(),
+                detail: format!("{} variables", environment.variables.len()),
+                value: Some(index),
+            }),
+    )
+    .collect();
+
+    open_picker(
+        app,
+        Picker::new(PickerKind::Environment, "Environments", items),
+    );
+}
+
+/// List the exchanges of the history, latest first, to load one with its
+/// response
+pub fn open_history(app: &mut App) {
+    if app.history.is_empty() {
+        app.status_message = Some("No requests sent yet".to_string());
+        return;
+    }
+
+    let items = app
+        .history
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(index, exchange)| PickerItem {
+            label: exchange.label(),
+            detail: format!(
+                "{} · {} ms",
+                exchange.started.format("%H:%M:%S"),
+                exchange.timings.total().as_millis()
+            ),
+            value: Some(index),
+        })
+        .collect();
+
+    open_picker(app, Picker::new(PickerKind::History, "History", items));
+}
+
+/// Ask where to write the history as a HAR file
+pub fn export_har(app: &mut App) {
+    if app.history.is_empty() {
+        app.status_message = Some("No requests sent yet".to_string());
+        return;
+    }
+
+    let path_input = FormField::new("Path", "path").value("tuapi.har");
+
+    let form = Form::new(FormKind::ExportHar, vec![path_input]).title("Export HAR");
+
+    app.popup = Some(AppPopup::FormPopup(form));
+}
+
+/// The examples of the request of the collection being edited
+fn examples(app: &App) -> Vec<Example> {
+    app.collection
+        .as_ref()
+        .zip(app.saved_request)
+        .and_then(|(collection, index)| {
+            collection
+                .requests()
+                .get(index)
+                .map(|entry| entry.request.examples.clone())
+        })
+        .unwrap_or_default()
+}
+
+fn open_examples_picker(app: &mut App, kind: PickerKind, title: &str) {
+    let examples = examples(app);
+
+    if examples.is_empty() {
+        app.status_message = Some("No examples saved with the request".to_string());
+        return;
+    }
+
+    let items = examples
+        .iter()
+        .enumerate()
+        .map(|(index, example)| PickerItem {
+            label: example.name.clone(),
+            detail: format!("{} · {}", example.status, count(example.body.len(), "byte")),
+            value: Some(index),
+        })
+        .collect();
+
+    open_picker(app, Picker::new(kind, title, items));
+}
+
+/// List the examples of the request to show one in place of the response
+pub fn open_examples(app: &mut App) {
+    open_examples_picker(app, PickerKind::Example, "Examples");
+}
+
+/// List the examples of the request to compare the response with one
+pub fn diff_example(app: &mut App) {
+    if app.response.is_none() {
+        app.status_message = Some("No response to compare".to_string());
+        return;
+    }
+
+    open_examples_picker(app, PickerKind::DiffExample, "Compare with");
+}
+
+/// The responses that can be compared: the one shown, the history latest
+/// first, and the examples of the request
+pub fn compare_sources(app: &App) -> Vec<Source> {
+    let response = app.response.as_ref().map(|_| Source::Response);
+    let history = (0..app.history.len()).rev().map(Source::History);
+    let examples = (0..examples(app).len()).map(Source::Example);
+
+    response
+        .into_iter()
+        .chain(history)
+        .chain(examples)
+        .collect()
+}
+
+/// The response of a source, named for the diff title, with a label and
+/// detail for the picker
+fn source_response(app: &App, source: Source) -> Option<(PickerItem, String, Response)> {
+    match source {
+        Source::Response => {
+            let response = app.response.clone()?;
+
+            let item = PickerItem {
+                label: "Current response".to_string(),
+                detail: format!(
+                    "{} · {}",
+                    response.status_code,
+                    count(response.body.len(), "byte")
+                ),
+                value: None,
+            };
+
+            Some((item, "the response".to_string(), response))
+        }
+        Source::History(index) => {
+            let exchange = app.history.get(index)?;
+            let time = exchange.started.format("%H:%M:%S");
+
+            let item = PickerItem {
+                label: exchange.label(),
+                detail: format!("{time} · {} ms", exchange.timings.total().as_millis()),
+                value: None,
+            };
+
+            Some((
+                item,
+                format!("the response of {time}"),
+                exchange.response.clone(),
+            ))
+        }
+        Source::Example(index) => {
+            let example = examples(app).get(index)?.clone();
+
+            let item = PickerItem {
+                label: format!("Example {}", example.name),
+                detail: format!("{} · {}", example.status, count(example.body.len(), "byte")),
+                value: None,
+            };
+            let response = Response::from_example(&example, &app.endpoint.text);
+
+            Some((item, format!("example {}", example.name), response))
+        }
+    }
+}
+
+/// List the responses to compare, leaving out the one picked first
+fn open_compare_picker(app: &mut App, kind: PickerKind, title: &str) {
+    let items = compare_sources(app)
+        .into_iter()
+        .enumerate()
+        .filter(|(_, source)| Some(*source) != app.comparing)
+        .filter_map(|(index, source)| {
+            source_response(app, source).map(|(item, _, _)| PickerItem {
+                value: Some(index),
+                ..item
+            })
+        })
+        .collect();
+
+    open_picker(app, Picker::new(kind, title, items));
+}
+
+/// Pick two responses of the history, the examples or the one shown to
+/// compare them
+pub fn compare_responses(app: &mut App) {
+    if compare_sources(app).len() < 2 {
+        app.status_message = Some("Send requests or save examples to compare them".to_string());
+        return;
+    }
+
+    app.comparing = None;
+
+    open_compare_picker(app, PickerKind::CompareFirst, "Compare");
+}
+
+/// Ask for the name to save the response under as an example of the
+/// request
+pub fn save_example(app: &mut App) {
+    if app.saved_request.is_none() {
+        app.status_message = Some("No request of a collection to save to".to_string());
+        return;
+    }
+
+    let Some(response) = app.response.as_ref() else {
+        app.status_message = Some("No response to save".to_string());
+        return;
+    };
+
+    let name = match StatusCode::from_u16(response.status_code)
+        .ok()
+        .and_then(|status| status.canonical_reason())
+    {
+        Some(reason) => format!("{} {reason}", response.status_code),
+        None => response.status_code.to_string(),
+    };
+
+    let name_input = FormField::new("Name", "name").value(&name);
+
+    let form = Form::new(FormKind::SaveExample, vec![name_input]).title("Save example");
+
+    app.popup = Some(AppPopup::FormPopup(form));
+}
+
+/// Pin the response as an example of the request, replacing the one with
+/// the same name, and save the collection
+pub fn add_example(app: &mut App, name: &str) {
+    let name = name.trim();
+
+    if name.is_empty() {
+        app.status_message = Some("An example needs a name".to_string());
+        return;
+    }
+
+    let Some(response) = app.response.as_ref() else {
+        return;
+    };
+
+    if response.is_binary {
+        app.status_message = Some("Binary responses can't be saved as examples".to_string());
+        return;
+    }
+
+    let mut example = response.to_example(name);
+
+    let (Some(collection), Some(index), Some(path)) = (
+        app.collection.as_mut(),
+        app.saved_request,
+        app.collection_path.as_ref(),
+    ) else {
+        return;
+    };
+
+    let Some(request) = collection.request_mut(index) else {
+        return;
+    };
+
+    let existing = request
+        .examples
+        .iter_mut()
+        .find(|existing| existing.name == name);
+
+    let message = match existing {
+        Some(existing) => {
+            example.delay = existing.delay;
+            *existing = example;
+
+            format!("Updated the example {name}")
+        }
+        None => {
+            request.examples.push(example);
+
+            format!("Saved the example {name} with {}", request.name)
+        }
+    };
+
+    app.status_message = Some(match collection.save(path) {
+        Ok(_) => message,
+        Err(e) => format!("Could not save the collection: {e}"),
+    });
+}
+
+/// Act on the item picked in a picker
+pub fn pick(app: &mut App, kind: PickerKind, item: PickerItem) {
+    match kind {
+        PickerKind::Request => {
+            let request =
+                app.collection
+                    .as_ref()
+                    .zip(item.value)
+                    .and_then(|(collection, index)| {
+                        collection
+                            .requests()
+                            .get(index)
+                            .map(|entry| entry.request.clone())
+                    });
+
+            let Some(request) = request else {
+                return;
+            };
+
+            let problems = app.load_request(&request);
+
+            app.http_request = None;
+            app.saved_request = item.value;
+            app.status_message = Some(match problems.is_empty() {
+                true => format!("Loaded {}", item.label),
+                false => format!("Loaded {}, but {}", item.label, problems.join("; ")),
+            });
+        }
+        PickerKind::FileRequest => {
+            let request = app
+                .http_file
+                .as_ref()
+                .zip(item.value)
+                .and_then(|(file, index)| file.requests.get(index))
+                .map(|request| request.to_saved());
+
+            let Some(request) = request else {
+                return;
+            };
+
+            let problems = app.load_request(&request);
+
+            app.http_request = item.value;
+            app.saved_request = None;
+            app.status_message = Some(match problems.is_empty() {
+                true => format!("Loaded {}", item.label),
+                false => format!("Loaded {}, but {}", item.label, problems.join("; ")),
+            });
+        }
+        PickerKind::History => {
+            let Some(exchange) = item.value.and_then(|index| app.history.get(index).cloned())
+            else {
+                return;
+            };
+
+            let problems = app.load_request(&exchange.request);
+
+            app.http_request = None;
+            app.saved_request = None;
+            app.show_response(Some(exchange.response));
+            app.status_message = Some(match problems.is_empty() {
+                true => format!("Loaded {}", item.label),
+                false => format!("Loaded {}, but {}", item.label, problems.join("; ")),
+            });
+        }
+        PickerKind::Example | PickerKind::DiffExample => {
+            let Some(example) = item
+                .value
+                .and_then(|index| examples(app).get(index).cloned())
+            else {
+                return;
+            };
+
+            let example_response = Response::from_example(&example, &app.endpoint.text);
+
+            match kind {
+                PickerKind::DiffExample => {
+                    let Some(response) = app.response.as_ref() else {
+                        return;
+                    };
+
+                    let diff = Diff::new(
+                        (&format!("example {}", example.name), &example_response),
+                        ("the response", response),
+                    );
+
+                    app.popup = Some(AppPopup::Diff(diff));
+                }
+                _ => {
+                    app.show_response(Some(example_response));
+                    app.status_message = Some(format!("Showing the example {}", example.name));
+                }
+            }
+        }
+        PickerKind::CompareFirst | PickerKind::CompareSecond => {
+            let Some(source) = item
+                .value
+                .and_then(|index| compare_sources(app).get(index).copied())
+            else {
+                return;
+            };
+
+            if kind == PickerKind::CompareFirst {
+                app.comparing = Some(source);
+                open_compare_picker(app, PickerKind::CompareSecond, "Compare with");
+                return;
+            }
+
+            let Some(first) = app.comparing.take() else {
+                return;
+            };
+
+            let (Some((_, old_name, old)), Some((_, new_name, new))) =
+                (source_response(app, first), source_response(app, source))
+            else {
+                return;
+            };
+
+            let diff = Diff::new((&old_name, &old), (&new_name, &new));
+
+            app.popup = Some(AppPopup::Diff(diff));
+        }
+        PickerKind::Environment => {
+            app.environment = item.value;
+            app.status_message = Some(match item.value {
+                Some(_) => format!("Using the {} environment", item.label),
+                None => "Using no environment".to_string(),
+            });
+        }
+    }
+}
+
+/// Write the request being edited back into the `.http` file it was loaded
+/// from
+pub fn save_request(app: &mut App) {
+    let Some(index) = app.http_request else {
+        app.status_message = Some("No request of a file to save".to_string());
+        return;
+    };
+
+    let Some(mut edited) = app
+        .http_file
+        .as_ref()
+        .and_then(|file| file.requests.get(index).cloned())
+    else {
+        return;
+    };
+
+    // Methods that can't be sent aren't changed in the editor
+    if RequestMethod::from_str(&edited.method).is_ok() {
+        edited.method = app.method.to_string();
+    }
+
+    edited.url = app.endpoint.text.clone();
+
+    if !app.query_params.is_empty() {
+        let separator = match edited.url.contains('?') {
+            true => '&',
+            false => '?',
+        };
+        let query = encode_pairs(app.query_params.iter().map(|(name, value)| (name, value)));
+
+        edited.url.push_str(&format!("{separator}{query}"));
+    }
+
+    // Headers keep their order in the file, new ones are sorted at the end
+    let mut headers: Vec<(String, String)> = edited
+        .headers
+        .iter()
+        .filter_map(|(name, _)| {
+            app.headers
+                .get(name)
+                .map(|value| (name.clone(), value.clone()))
+        })
+        .collect();
+
+    let mut added: Vec<(String, String)> = app
+        .headers
+        .iter()
+        .filter(|(name, _)| !edited.headers.iter().any(|(header, _)| header == *name))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+
+    added.sort();
+    headers.extend(added);
+    edited.headers = headers;
+
+    edited.body = match app.body_content_type {
+        BodyContentType::Text(_) => app.raw_body.text.clone(),
+        BodyContentType::Form => {
+            let mut fields: Vec<(&String, &String)> = app.body_form.iter().collect();
+
+            fields.sort();
+            encode_pairs(fields)
+        }
+    };
+
+    let Some(file) = app.http_file.as_mut() else {
+        return;
+    };
+
+    app.status_message = Some(match file.update(index, &edited) {
+        Ok(()) => format!("Saved {} to {}", edited.name, file.path.display()),
+        Err(e) => format!("Can't save to {}: {e}", file.path.display()),
+    });
+}
@@ -0,0 +1,193 @@
+use crate::{
+    app::{
+        help::{Help, HelpSection},
+        palette::{Palette, PaletteEntry},
+        App, AppBlock, AppPopup, BodyContentType, InputMode, RequestTab,
+    },
+    config::keymap::{Action, KeyContext, KeyMap},
+};
+
+use super::{collection, key_contexts};
+
+/// Whether an action does something where it would run now
+pub fn is_applicable(app: &App, action: Action) -> bool {
+    match app.popup.as_ref() {
+        Some(AppPopup::ChangeMethod) => {
+            return matches!(
+                action,
+                Action::Up | Action::Down | Action::Select | Action::Cancel | Action::Help
+            )
+        }
+        Some(AppPopup::FormPopup(_)) => {
+            return matches!(
+                action,
+                Action::Insert
+                    | Action::Up
+                    | Action::Down
+                    | Action::Select
+                    | Action::Cancel
+                    | Action::Help
+            )
+        }
+        Some(AppPopup::ConfirmSend(_) | AppPopup::ConfirmOverwrite(..)) => {
+            return matches!(
+                action,
+                Action::Confirm | Action::Select | Action::Cancel | Action::Help
+            )
+        }
+        Some(AppPopup::Help(_) | AppPopup::CommandPalette(_) | AppPopup::Picker(_)) => {
+            return matches!(
+                action,
+                Action::Up | Action::Down | Action::Cancel | Action::Help
+            )
+        }
+        Some(AppPopup::Diff(_)) => {
+            return matches!(
+                action,
+                Action::Up
+                    | Action::Down
+                    | Action::ToggleSideBySide
+                    | Action::Select
+                    | Action::Cancel
+                    | Action::Help
+            )
+        }
+        None => {}
+    }
+
+    let in_content = app.selected_block == AppBlock::RequestContent;
+    let is_text_body = app.request_tab == RequestTab::Body
+        && matches!(app.body_content_type, BodyContentType::Text(_));
+    let has_table = match app.request_tab {
+        RequestTab::Headers | RequestTab::Query => true,
+        RequestTab::Body => matches!(app.body_content_type, BodyContentType::Form),
+        _ => false,
+    };
+
+    match action {
+        Action::Insert => match app.selected_block {
+            AppBlock::Endpoint | AppBlock::ResponseFilter => true,
+            AppBlock::RequestContent => is_text_body,
+            _ => false,
+        },
+        Action::Up | Action::Down => match app.selected_block {
+            AppBlock::Method | AppBlock::Request | AppBlock::Response => true,
+            AppBlock::RequestContent => has_table,
+            _ => false,
+        },
+        Action::Add | Action::Edit | Action::Delete => in_content && has_table,
+        Action::FormatBody | Action::EditExternally | Action::ToggleBodyType => {
+            in_content && is_text_body
+        }
+        Action::ToggleContentType => in_content && app.request_tab == RequestTab::Body,
+        Action::CopyResponse => app.response.as_ref().is_some_and(|r| !r.is_binary),
+        Action::OpenRequest => app.collection.is_some() || app.http_file.is_some(),
+        Action::SelectEnvironment => app.collection.is_some(),
+        Action::SaveRequest => app.http_request.is_some(),
+        Action::History | Action::ExportHar => !app.history.is_empty(),
+        Action::SaveExample => app.saved_request.is_some() && app.response.is_some(),
+        Action::Examples => has_examples(app),
+        Action::DiffExample => has_examples(app) && app.response.is_some(),
+        Action::CompareResponses => collection::compare_sources(app).len() >= 2,
+        Action::ToggleSideBySide => false,
+        _ => true,
+    }
+}
+
+/// Whether the request of the collection being edited has examples
+fn has_examples(app: &App) -> bool {
+    app.collection
+        .as_ref()
+        .zip(app.saved_request)
+        .and_then(|(collection, index)| {
+            collection
+                .requests()
+                .get(index)
+                .map(|entry| !entry.request.examples.is_empty())
+        })
+        .unwrap_or(false)
+}
+
+fn section_title(app: &App, context: KeyContext) -> &'static str {
+    match context {
+        KeyContext::Normal => match app.selected_block {
+            AppBlock::Method => "Method",
+            AppBlock::Endpoint => "Endpoint",
+            AppBlock::Request => "Request tabs",
+            AppBlock::RequestContent => match app.request_tab {
+                RequestTab::Query => "Query parameters",
+                RequestTab::Headers => "Headers",
+                _ => "Body",
+            },
+            AppBlock::ResponseFilter => "Filter",
+            AppBlock::Response => "General",
+        },
+        KeyContext::Response => "Response",
+        KeyContext::Tree => "Tree view",
+        KeyContext::Hex => "Hex view",
+        KeyContext::Popup => match app.popup {
+            Some(AppPopup::ChangeMethod) => "Method",
+            Some(AppPopup::FormPopup(_)) => "Form",
+            Some(AppPopup::ConfirmSend(_) | AppPopup::ConfirmOverwrite(..)) => "Confirm",
+            Some(AppPopup::Diff(_)) => "Diff",
+            _ => "Help",
+        },
+    }
+}
+
+/// Show the keys bound in the active contexts, over the current popup if
+/// any
+pub fn open_help(app: &mut App) {
+    let contexts = key_contexts(app);
+
+    let sections = contexts
+        .iter()
+        .map(|context| HelpSection {
+            title: section_title(app, *context).to_string(),
+            entries: KeyMap::actions(*context)
+                .into_iter()
+                .filter(|action| is_applicable(app, *action))
+                .filter_map(|action| {
+                    let keys = app.keymap.keys_for(&contexts, *context, action);
+
+                    (!keys.is_empty()).then(|| (keys.join(", "), action.description().to_string()))
+                })
+                .collect(),
+        })
+        .filter(|section| !section.entries.is_empty())
+        .collect();
+
+    let previous = app.popup.take().map(Box::new);
+
+    app.popup = Some(AppPopup::Help(Help {
+        sections,
+        scroll: 0,
+        height: 0,
+        previous,
+    }));
+}
+
+/// Fuzzy find any action available in the active contexts, bound to keys
+/// or not
+pub fn open_palette(app: &mut App) {
+    let contexts = key_contexts(app);
+    let mut entries: Vec<PaletteEntry> = Vec::new();
+
+    for context in &contexts {
+        for action in KeyMap::actions(*context) {
+            let is_listed = entries.iter().any(|entry| entry.action == action);
+
+            if action == Action::CommandPalette || is_listed || !is_applicable(app, action) {
+                continue;
+            }
+
+            entries.push(PaletteEntry {
+                action,
+                keys: app.keymap.keys_for(&contexts, *context, action).join(", "),
+            });
+        }
+    }
+
+    app.popup = Some(AppPopup::CommandPalette(Palette::new(entries)));
+    app.input_mode = InputMode::Insert;
+}
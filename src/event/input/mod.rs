@@ -0,0 +1,923 @@
+mod vim;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::{app::Coordinates, clipboard};
+
+use vim::Vim;
+
+/// Undo steps kept per input
+const HISTORY_LIMIT: usize = 100;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SelectionKind {
+    Char,
+    Line,
+}
+
+#[derive(Clone)]
+pub struct Selection {
+    /// Where the selection started, the cursor is the other end
+    pub anchor: Coordinates,
+    pub kind: SelectionKind,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum EditKind {
+    Insert,
+    Delete,
+    Other,
+}
+
+#[derive(Clone)]
+struct Snapshot {
+    text: String,
+    cursor_position: Coordinates,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CharClass {
+    Whitespace,
+    Word,
+    Punctuation,
+}
+
+/// Class of a grapheme cluster, from its base character
+fn char_class(grapheme: &str) -> CharClass {
+    let c = grapheme.chars().next().unwrap_or(' ');
+
+    if c.is_whitespace() {
+        CharClass::Whitespace
+    } else if c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+fn to_u16(n: usize) -> u16 {
+    u16::try_from(n).unwrap_or(u16::MAX)
+}
+
+#[derive(Clone, Default)]
+pub struct Input {
+    pub text: String,
+    pub cursor_position: Coordinates,
+    pub selection: Option<Selection>,
+    undo_stack: Vec<Snapshot>,
+    redo_stack: Vec<Snapshot>,
+    /// Consecutive edits of the same kind are undone together
+    last_edit: Option<EditKind>,
+    /// Set during a vim insert session, all its edits are undone together
+    group_edits: bool,
+    pub vim: Vim,
+    /// First line shown by a multiline input
+    pub scroll: usize,
+}
+
+impl Input {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            ..Self::default()
+        }
+    }
+
+    fn line(&self, y: usize) -> &str {
+        self.text.split('\n').nth(y).unwrap_or("")
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.text.split('\n').count()
+    }
+
+    /// Length of a line in cursor positions, one per grapheme cluster
+    pub fn line_len(&self, y: usize) -> usize {
+        self.line(y).graphemes(true).count()
+    }
+
+    /// Display width of the line up to the cursor
+    pub fn cursor_column(&self) -> usize {
+        let line_start = self.offset_of(&Coordinates {
+            x: 0,
+            y: self.cursor_position.y,
+        });
+
+        self.text[line_start..self.cursor_offset()].width()
+    }
+
+    /// Byte offset in `text` of a position
+    fn offset_of(&self, position: &Coordinates) -> usize {
+        let mut offset = 0;
+
+        for (index, line) in self.text.split('\n').enumerate() {
+            if index == usize::from(position.y) {
+                return offset
+                    + line
+                        .grapheme_indices(true)
+                        .nth(position.x.into())
+                        .map(|(i, _)| i)
+                        .unwrap_or(line.len());
+            }
+
+            offset += line.len() + 1;
+        }
+
+        self.text.len()
+    }
+
+    fn position_of(&self, offset: usize) -> Coordinates {
+        let before = &self.text[..offset.min(self.text.len())];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+        Coordinates {
+            x: to_u16(before[line_start..].graphemes(true).count()),
+            y: to_u16(before.matches('\n').count()),
+        }
+    }
+
+    pub fn grapheme_at_cursor(&self) -> Option<&str> {
+        self.text[self.cursor_offset()..]
+            .graphemes(true)
+            .next()
+            .filter(|g| *g != "\n")
+    }
+
+    fn cursor_offset(&self) -> usize {
+        self.offset_of(&self.cursor_position)
+    }
+
+    fn set_cursor_offset(&mut self, offset: usize) {
+        self.cursor_position = self.position_of(offset);
+    }
+
+    /// Save the current state before an edit, unless it continues the
+    /// previous edit
+    fn checkpoint(&mut self, kind: EditKind) {
+        let continues_group = self.group_edits && self.last_edit.is_some();
+
+        if continues_group || (kind != EditKind::Other && self.last_edit == Some(kind)) {
+            return;
+        }
+
+        self.undo_stack.push(Snapshot {
+            text: self.text.clone(),
+            cursor_position: self.cursor_position,
+        });
+
+        if self.undo_stack.len() > HISTORY_LIMIT {
+            self.undo_stack.remove(0);
+        }
+
+        self.redo_stack.clear();
+        self.last_edit = Some(kind);
+    }
+
+    /// Start a new undo step on the next edit, unless a vim insert session
+    /// is grouping them
+    fn break_undo_group(&mut self) {
+        if !self.group_edits {
+            self.last_edit = None;
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) -> Snapshot {
+        let previous = Snapshot {
+            text: std::mem::replace(&mut self.text, snapshot.text),
+            cursor_position: self.cursor_position,
+        };
+
+        self.cursor_position = snapshot.cursor_position;
+        self.selection = None;
+        self.break_undo_group();
+
+        previous
+    }
+
+    pub fn undo(&mut self) {
+        if let Some(snapshot) = self.undo_stack.pop() {
+            let current = self.restore(snapshot);
+
+            self.redo_stack.push(current);
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Some(snapshot) = self.redo_stack.pop() {
+            let current = self.restore(snapshot);
+
+            self.undo_stack.push(current);
+        }
+    }
+
+    /// Replace the whole text as a single undoable edit
+    pub fn set_text(&mut self, text: String) {
+        self.checkpoint(EditKind::Other);
+        self.text = text;
+        self.selection = None;
+        self.cursor_position = Coordinates::default();
+        self.break_undo_group();
+    }
+
+    fn replace_range(&mut self, start: usize, end: usize, replacement: &str) {
+        self.text.replace_range(start..end, replacement);
+        self.set_cursor_offset(start + replacement.len());
+        self.selection = None;
+    }
+
+    pub fn move_cursor_left(&mut self) {
+        self.break_undo_group();
+        self.cursor_position.x = self.cursor_position.x.saturating_sub(1);
+    }
+
+    pub fn move_cursor_right(&mut self) {
+        self.break_undo_group();
+
+        let len = to_u16(self.line_len(self.cursor_position.y.into()));
+
+        self.cursor_position.x = (self.cursor_position.x + 1).min(len);
+    }
+
+    pub fn move_cursor_up(&mut self) {
+        self.break_undo_group();
+
+        if self.cursor_position.y > 0 {
+            self.cursor_position.y -= 1;
+            self.clamp_cursor_x();
+        }
+    }
+
+    pub fn move_cursor_down(&mut self) {
+        self.break_undo_group();
+
+        if usize::from(self.cursor_position.y) + 1 < self.line_count() {
+            self.cursor_position.y += 1;
+            self.clamp_cursor_x();
+        }
+    }
+
+    fn clamp_cursor_x(&mut self) {
+        let len = to_u16(self.line_len(self.cursor_position.y.into()));
+
+        self.cursor_position.x = self.cursor_position.x.min(len);
+    }
+
+    pub fn move_cursor_to_start_of_line(&mut self) {
+        self.break_undo_group();
+        self.cursor_position.x = 0;
+    }
+
+    pub fn move_cursor_to_end_of_line(&mut self) {
+        self.break_undo_group();
+        self.cursor_position.x = to_u16(self.line_len(self.cursor_position.y.into()));
+    }
+
+    /// Move the cursor to the end of the text
+    pub fn move_cursor_to_end_single_line(&mut self) {
+        self.break_undo_group();
+        self.set_cursor_offset(self.text.len());
+    }
+
+    /// Move the cursor to the grapheme cluster drawn at display `column` of
+    /// line `y`, like when clicked
+    pub fn move_cursor_to_column(&mut self, y: usize, column: usize) {
+        self.break_undo_group();
+        self.selection = None;
+
+        let y = y.min(self.line_count() - 1);
+        let mut width = 0;
+
+        let x = self
+            .line(y)
+            .graphemes(true)
+            .take_while(|g| {
+                width += g.width();
+                width <= column
+            })
+            .count();
+
+        self.cursor_position = Coordinates {
+            x: to_u16(x),
+            y: to_u16(y),
+        };
+        self.clamp_normal_cursor();
+    }
+
+    /// First column shown by a single line input `width` columns wide, so
+    /// the cursor stays visible
+    pub fn horizontal_scroll(&self, width: usize) -> usize {
+        let cursor_width = self
+            .grapheme_at_cursor()
+            .map(|g| g.width().max(1))
+            .unwrap_or(1);
+
+        (self.cursor_column() + cursor_width).saturating_sub(width)
+    }
+
+    /// Keep the cursor line inside a viewport of `height` lines
+    pub fn scroll_to_cursor(&mut self, height: usize) {
+        if height == 0 {
+            return;
+        }
+
+        let y = usize::from(self.cursor_position.y);
+
+        self.scroll = self.scroll.min(self.line_count() - 1);
+
+        if y < self.scroll {
+            self.scroll = y;
+        } else if y >= self.scroll + height {
+            self.scroll = y + 1 - height;
+        }
+    }
+
+    /// Scroll a viewport of `height` lines, the cursor moves along when it
+    /// would leave it
+    pub fn scroll_by(&mut self, lines: isize, height: usize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(lines)
+            .min(self.line_count() - 1);
+
+        let y =
+            usize::from(self.cursor_position.y).clamp(self.scroll, self.scroll + height.max(1) - 1);
+
+        if y != usize::from(self.cursor_position.y) {
+            self.break_undo_group();
+            self.cursor_position.y = to_u16(y);
+            self.clamp_cursor_x();
+            self.clamp_normal_cursor();
+        }
+    }
+
+    /// Offset of the start of the next word, crossing lines
+    fn next_word_offset(&self) -> usize {
+        let offset = self.cursor_offset();
+        let mut graphemes = self.text[offset..].grapheme_indices(true).peekable();
+
+        let Some((_, first)) = graphemes.peek().copied() else {
+            return offset;
+        };
+
+        let class = char_class(first);
+
+        if class != CharClass::Whitespace {
+            while graphemes.next_if(|(_, g)| char_class(g) == class).is_some() {}
+        }
+
+        while graphemes
+            .next_if(|(_, g)| char_class(g) == CharClass::Whitespace)
+            .is_some()
+        {}
+
+        graphemes
+            .peek()
+            .map(|(i, _)| offset + i)
+            .unwrap_or(self.text.len())
+    }
+
+    /// Offset of the start of the current or previous word
+    fn previous_word_offset(&self) -> usize {
+        let offset = self.cursor_offset();
+        let mut graphemes = self.text[..offset].grapheme_indices(true).rev().peekable();
+
+        while graphemes
+            .next_if(|(_, g)| char_class(g) == CharClass::Whitespace)
+            .is_some()
+        {}
+
+        let Some((mut start, first)) = graphemes.next() else {
+            return 0;
+        };
+
+        let class = char_class(first);
+
+        while let Some((i, _)) = graphemes.next_if(|(_, g)| char_class(g) == class) {
+            start = i;
+        }
+
+        start
+    }
+
+    pub fn move_word_forward(&mut self) {
+        self.break_undo_group();
+        self.set_cursor_offset(self.next_word_offset());
+    }
+
+    pub fn move_word_backward(&mut self) {
+        self.break_undo_group();
+        self.set_cursor_offset(self.previous_word_offset());
+    }
+
+    pub fn add_char_at_cursor(&mut self, c: char) {
+        // Every word typed is its own undo step
+        if c.is_whitespace() {
+            self.break_undo_group();
+        }
+
+        self.insert_str(&c.to_string());
+    }
+
+    pub fn add_newline_at_cursor(&mut self) {
+        self.add_char_at_cursor('\n');
+    }
+
+    /// Insert text at the cursor, replacing the selection if there is one
+    pub fn insert_str(&mut self, text: &str) {
+        self.checkpoint(EditKind::Insert);
+
+        let cursor = self.cursor_offset();
+        let (start, end) = self.selected_range().unwrap_or((cursor, cursor));
+
+        self.replace_range(start, end, text);
+    }
+
+    pub fn remove_char_before_cursor(&mut self) {
+        if self.selection.is_some() {
+            self.delete_selection();
+            return;
+        }
+
+        let offset = self.cursor_offset();
+
+        let Some(len) = self.text[..offset]
+            .graphemes(true)
+            .next_back()
+            .map(str::len)
+        else {
+            return;
+        };
+
+        self.checkpoint(EditKind::Delete);
+        self.replace_range(offset - len, offset, "");
+    }
+
+    pub fn remove_char_at_cursor(&mut self) {
+        if self.selection.is_some() {
+            self.delete_selection();
+            return;
+        }
+
+        let offset = self.cursor_offset();
+
+        let Some(len) = self.text[offset..].graphemes(true).next().map(str::len) else {
+            return;
+        };
+
+        self.checkpoint(EditKind::Delete);
+        self.replace_range(offset, offset + len, "");
+    }
+
+    pub fn remove_word_before_cursor(&mut self) {
+        let (start, end) = (self.previous_word_offset(), self.cursor_offset());
+
+        if start < end {
+            self.checkpoint(EditKind::Other);
+            self.replace_range(start, end, "");
+        }
+    }
+
+    pub fn remove_word_after_cursor(&mut self) {
+        let (start, end) = (self.cursor_offset(), self.next_word_offset());
+
+        if start < end {
+            self.checkpoint(EditKind::Other);
+            self.replace_range(start, end, "");
+        }
+    }
+
+    /// Start selecting from the cursor, keeping the current selection if it
+    /// is of the same kind
+    pub fn start_selection(&mut self, kind: SelectionKind) {
+        if self.selection.as_ref().map(|s| s.kind) != Some(kind) {
+            self.selection = Some(Selection {
+                anchor: self.cursor_position,
+                kind,
+            });
+        }
+    }
+
+    pub fn select_all(&mut self) {
+        self.selection = Some(Selection {
+            anchor: Coordinates::default(),
+            kind: SelectionKind::Char,
+        });
+
+        self.set_cursor_offset(self.text.len());
+    }
+
+    /// Select the current line, or extend the line selection downwards
+    pub fn select_line(&mut self) {
+        if self.selection.as_ref().map(|s| s.kind) == Some(SelectionKind::Line) {
+            self.move_cursor_down();
+        } else {
+            self.start_selection(SelectionKind::Line);
+        }
+    }
+
+    /// Ends of the selection, in order
+    fn selection_bounds(&self) -> Option<(Coordinates, Coordinates)> {
+        let anchor = self.selection.as_ref()?.anchor;
+        let cursor = self.cursor_position;
+
+        if (anchor.y, anchor.x) <= (cursor.y, cursor.x) {
+            Some((anchor, cursor))
+        } else {
+            Some((cursor, anchor))
+        }
+    }
+
+    /// Byte range of the selection, line selections include a line break
+    pub fn selected_range(&self) -> Option<(usize, usize)> {
+        let (start, end) = self.selection_bounds()?;
+
+        match self.selection.as_ref()?.kind {
+            SelectionKind::Char => Some((self.offset_of(&start), self.offset_of(&end))),
+            SelectionKind::Line => Some(self.line_range(start.y.into(), end.y.into())),
+        }
+    }
+
+    /// Byte range of the lines `first..=last` with one of their line breaks
+    fn line_range(&self, first: usize, last: usize) -> (usize, usize) {
+        let start = self.offset_of(&Coordinates {
+            x: 0,
+            y: to_u16(first),
+        });
+        let end = self.offset_of(&Coordinates {
+            x: to_u16(self.line_len(last)),
+            y: to_u16(last),
+        });
+
+        if end < self.text.len() {
+            (start, end + 1)
+        } else {
+            (start.saturating_sub(1), end)
+        }
+    }
+
+    /// Columns of line `y` covered by the selection
+    pub fn selection_columns(&self, y: usize) -> Option<(usize, usize)> {
+        let (start, end) = self.selection_bounds()?;
+
+        if y < usize::from(start.y) || y > usize::from(end.y) {
+            return None;
+        }
+
+        if self.selection.as_ref()?.kind == SelectionKind::Line {
+            return Some((0, self.line_len(y)));
+        }
+
+        let from = if y == usize::from(start.y) {
+            start.x.into()
+        } else {
+            0
+        };
+
+        let to = if y == usize::from(end.y) {
+            end.x.into()
+        } else {
+            self.line_len(y)
+        };
+
+        Some((from, to))
+    }
+
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selected_range()?;
+
+        Some(self.text[start..end].to_string())
+    }
+
+    pub fn delete_selection(&mut self) {
+        if let Some((start, end)) = self.selected_range() {
+            self.checkpoint(EditKind::Other);
+            self.replace_range(start, end, "");
+        }
+    }
+
+    pub fn copy_selection(&mut self) {
+        if let Some(text) = self.selected_text() {
+            // Still pasteable inside tuapi when there is no system clipboard
+            let _ = clipboard::copy(&text);
+
+            self.selection = None;
+        }
+    }
+
+    pub fn cut_selection(&mut self) {
+        if let Some(text) = self.selected_text() {
+            let _ = clipboard::copy(&text);
+
+            self.delete_selection();
+        }
+    }
+
+    /// Insert pasted text as a single undo step, line breaks are dropped in
+    /// single line inputs
+    pub fn paste(&mut self, text: &str, multiline: bool) {
+        let text = if multiline {
+            text.replace("\r\n", "\n").replace('\r', "\n")
+        } else {
+            text.replace(['\r', '\n'], "")
+        };
+
+        self.break_undo_group();
+        self.insert_str(&text);
+        self.break_undo_group();
+    }
+
+    /// Move the cursor, extending the selection when `extend` is set and
+    /// dropping it otherwise
+    fn move_selecting(&mut self, extend: bool, movement: fn(&mut Self)) {
+        if extend {
+            self.start_selection(SelectionKind::Char);
+        } else {
+            self.selection = None;
+        }
+
+        movement(self);
+    }
+
+    /// Handle the editing keys shared by every input, returns `false` for
+    /// the keys left to the caller like Enter, Tab or Esc
+    pub fn handle_key(&mut self, key: KeyEvent, multiline: bool) -> bool {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+
+        match key.code {
+            KeyCode::Char('z') if control => self.undo(),
+            KeyCode::Char('y') | KeyCode::Char('Z') if control => self.redo(),
+            KeyCode::Char('c') if control => self.copy_selection(),
+            KeyCode::Char('x') if control => self.cut_selection(),
+            KeyCode::Char('v') if control => self.paste(&clipboard::paste(), multiline),
+            KeyCode::Char('a') if control => self.select_all(),
+            KeyCode::Char('l') if control && multiline => self.select_line(),
+            KeyCode::Char('w') if control => self.remove_word_before_cursor(),
+            KeyCode::Char('b') if alt => self.move_selecting(false, Self::move_word_backward),
+            KeyCode::Char('f') if alt => self.move_selecting(false, Self::move_word_forward),
+            KeyCode::Char('d') if alt => self.remove_word_after_cursor(),
+            KeyCode::Char(_) if control || alt => return false,
+            KeyCode::Char(c) => self.add_char_at_cursor(c),
+            KeyCode::Left if control => self.move_selecting(shift, Self::move_word_backward),
+            KeyCode::Right if control => self.move_selecting(shift, Self::move_word_forward),
+            KeyCode::Left => self.move_selecting(shift, Self::move_cursor_left),
+            KeyCode::Right => self.move_selecting(shift, Self::move_cursor_right),
+            KeyCode::Up if multiline => self.move_selecting(shift, Self::move_cursor_up),
+            KeyCode::Down if multiline => self.move_selecting(shift, Self::move_cursor_down),
+            KeyCode::Home => self.move_selecting(shift, Self::move_cursor_to_start_of_line),
+            KeyCode::End => self.move_selecting(shift, Self::move_cursor_to_end_of_line),
+            KeyCode::Backspace if control || alt => self.remove_word_before_cursor(),
+            KeyCode::Backspace => self.remove_char_before_cursor(),
+            KeyCode::Delete if control => self.remove_word_after_cursor(),
+            KeyCode::Delete => self.remove_char_at_cursor(),
+            KeyCode::Enter if multiline => self.add_newline_at_cursor(),
+            KeyCode::Tab if multiline => self.insert_str("  "),
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor(input: &Input) -> (u16, u16) {
+        (input.cursor_position.x, input.cursor_position.y)
+    }
+
+    fn at(text: &str, x: u16, y: u16) -> Input {
+        let mut input = Input::new(text);
+        input.cursor_position = Coordinates { x, y };
+        input
+    }
+
+    fn type_text(input: &mut Input, text: &str) {
+        text.chars().for_each(|c| input.add_char_at_cursor(c));
+    }
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn undoes_a_word_at_a_time() {
+        let mut input = Input::default();
+
+        type_text(&mut input, "hello world");
+        assert_eq!(input.text, "hello world");
+
+        input.undo();
+        assert_eq!(input.text, "hello");
+        assert_eq!(cursor(&input), (5, 0));
+
+        input.undo();
+        assert_eq!(input.text, "");
+
+        input.redo();
+        input.redo();
+        assert_eq!(input.text, "hello world");
+        assert_eq!(cursor(&input), (11, 0));
+    }
+
+    #[test]
+    fn moving_starts_a_new_undo_step() {
+        let mut input = Input::default();
+
+        type_text(&mut input, "ab");
+        input.move_cursor_left();
+        type_text(&mut input, "c");
+        input.remove_char_before_cursor();
+        input.remove_char_before_cursor();
+
+        assert_eq!(input.text, "b");
+
+        input.undo();
+        assert_eq!(input.text, "acb");
+
+        input.undo();
+        assert_eq!(input.text, "ab");
+
+        // A new edit drops what could be redone
+        type_text(&mut input, "x");
+        input.redo();
+        assert_eq!(input.text, "axb");
+    }
+
+    #[test]
+    fn selects_characters_across_lines() {
+        let mut input = at("one\ntwo\nthree", 1, 0);
+
+        input.start_selection(SelectionKind::Char);
+        input.move_cursor_down();
+        input.move_cursor_down();
+
+        assert_eq!(input.selected_text().as_deref(), Some("ne\ntwo\nt"));
+        assert_eq!(input.selection_columns(0), Some((1, 3)));
+        assert_eq!(input.selection_columns(1), Some((0, 3)));
+        assert_eq!(input.selection_columns(2), Some((0, 1)));
+
+        input.delete_selection();
+        assert_eq!(input.text, "ohree");
+        assert_eq!(cursor(&input), (1, 0));
+    }
+
+    #[test]
+    fn selects_whole_lines() {
+        let mut input = at("one\ntwo\nthree", 2, 1);
+
+        input.select_line();
+        assert_eq!(input.selected_text().as_deref(), Some("two\n"));
+
+        // The last line takes the line break before it instead
+        input.select_line();
+        assert_eq!(input.selected_text().as_deref(), Some("\ntwo\nthree"));
+
+        input.delete_selection();
+        assert_eq!(input.text, "one");
+    }
+
+    #[test]
+    fn typing_replaces_the_selection() {
+        let mut input = at("hello world", 0, 0);
+
+        input.select_all();
+        type_text(&mut input, "hi");
+
+        assert_eq!(input.text, "hi");
+        assert!(input.selection.is_none());
+
+        input.undo();
+        assert_eq!(input.text, "hello world");
+    }
+
+    #[test]
+    fn moves_and_deletes_by_word() {
+        let mut input = at("let x = foo.bar(1);", 0, 0);
+
+        input.move_word_forward();
+        assert_eq!(cursor(&input), (4, 0));
+
+        input.move_word_forward();
+        input.move_word_forward();
+        input.move_word_forward();
+        assert_eq!(cursor(&input), (11, 0));
+
+        input.move_word_backward();
+        assert_eq!(cursor(&input), (8, 0));
+
+        input.remove_word_after_cursor();
+        assert_eq!(input.text, "let x = .bar(1);");
+
+        input.move_cursor_to_end_of_line();
+        input.remove_word_before_cursor();
+        assert_eq!(input.text, "let x = .bar(1");
+    }
+
+    #[test]
+    fn pastes_as_one_step() {
+        let mut single = Input::default();
+        single.paste("a\r\nb", false);
+        assert_eq!(single.text, "ab");
+
+        let mut multi = Input::default();
+        type_text(&mut multi, "x");
+        multi.paste("a\r\nb\rc", true);
+        assert_eq!(multi.text, "xa\nb\nc");
+        assert_eq!(cursor(&multi), (1, 2));
+
+        multi.undo();
+        assert_eq!(multi.text, "x");
+    }
+
+    #[test]
+    fn handles_editing_keys() {
+        let mut input = at("abc", 3, 0);
+
+        assert!(input.handle_key(key(KeyCode::Left, KeyModifiers::SHIFT), false));
+        assert!(input.handle_key(key(KeyCode::Left, KeyModifiers::SHIFT), false));
+        assert_eq!(input.selected_text().as_deref(), Some("bc"));
+
+        assert!(input.handle_key(key(KeyCode::Backspace, KeyModifiers::NONE), false));
+        assert_eq!(input.text, "a");
+
+        assert!(input.handle_key(key(KeyCode::Char('z'), KeyModifiers::CONTROL), false));
+        assert_eq!(input.text, "abc");
+
+        // Left to the caller
+        assert!(!input.handle_key(key(KeyCode::Enter, KeyModifiers::NONE), false));
+        assert!(!input.handle_key(key(KeyCode::Up, KeyModifiers::NONE), false));
+        assert!(!input.handle_key(key(KeyCode::Char('s'), KeyModifiers::CONTROL), false));
+    }
+
+    #[test]
+    fn moves_by_grapheme_cluster() {
+        // `e` and a combining accent, then a family emoji joined by ZWJs
+        let mut input = Input::new("e\u{301}👨‍👩‍👧x");
+
+        assert_eq!(input.line_len(0), 3);
+
+        input.move_cursor_right();
+        assert_eq!(input.offset_of(&input.cursor_position), 3);
+        assert_eq!(input.grapheme_at_cursor(), Some("👨‍👩‍👧"));
+
+        input.move_cursor_right();
+        assert_eq!(input.grapheme_at_cursor(), Some("x"));
+
+        input.remove_char_before_cursor();
+        assert_eq!(input.text, "e\u{301}x");
+        assert_eq!(cursor(&input), (1, 0));
+
+        input.move_cursor_left();
+        input.remove_char_at_cursor();
+        assert_eq!(input.text, "x");
+    }
+
+    #[test]
+    fn counts_cursor_columns_in_display_width() {
+        let mut input = at("日本語 ok\né", 2, 0);
+
+        assert_eq!(input.cursor_column(), 4);
+
+        input.move_cursor_to_end_of_line();
+        assert_eq!(input.cursor_column(), 9);
+
+        input.move_cursor_down();
+        assert_eq!(cursor(&input), (1, 1));
+        assert_eq!(input.cursor_column(), 1);
+        assert_eq!(input.grapheme_at_cursor(), None);
+    }
+
+    #[test]
+    fn converts_offsets_and_positions() {
+        let input = Input::new("aé\n日x");
+
+        assert_eq!(input.offset_of(&Coordinates { x: 2, y: 0 }), 3);
+        assert_eq!(input.offset_of(&Coordinates { x: 1, y: 1 }), 7);
+        // Past the end of a line or of the text
+        assert_eq!(input.offset_of(&Coordinates { x: 9, y: 0 }), 3);
+        assert_eq!(input.offset_of(&Coordinates { x: 0, y: 5 }), 8);
+
+        let position = input.position_of(7);
+        assert_eq!((position.x, position.y), (1, 1));
+    }
+
+    #[test]
+    fn moves_by_word_over_wide_characters() {
+        let mut input = Input::new("日本 語,x");
+
+        input.move_word_forward();
+        assert_eq!(cursor(&input), (3, 0));
+
+        input.move_word_forward();
+        assert_eq!(cursor(&input), (4, 0));
+
+        input.move_cursor_to_end_of_line();
+        input.move_word_backward();
+        assert_eq!(cursor(&input), (5, 0));
+    }
+}
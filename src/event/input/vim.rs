@@ -0,0 +1,497 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{char_class, to_u16, CharClass, EditKind, Input};
+use crate::{app::Coordinates, clipboard};
+
+/// Stands for Ctrl-r among the normal mode commands
+const REDO: char = '\u{12}';
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum VimMode {
+    #[default]
+    Insert,
+    Normal,
+}
+
+/// State of the vim-style modes of an input
+#[derive(Clone, Default)]
+pub struct Vim {
+    pub mode: VimMode,
+    count: Option<usize>,
+    /// First key of a two key command like `dd` or `gg`
+    pending: Option<char>,
+    /// Keys of the command being typed
+    keys: Vec<KeyEvent>,
+    /// Keys of a change still being typed in insert mode
+    recording: Option<Vec<KeyEvent>>,
+    /// Keys of the last change, replayed by `.`
+    last_change: Vec<KeyEvent>,
+}
+
+impl Input {
+    pub fn enter_insert_mode(&mut self) {
+        self.vim = Vim {
+            last_change: std::mem::take(&mut self.vim.last_change),
+            ..Vim::default()
+        };
+    }
+
+    pub fn is_normal_mode(&self) -> bool {
+        self.vim.mode == VimMode::Normal
+    }
+
+    /// Handle a key in an input with vim-style modes, Esc in insert mode
+    /// goes to normal mode. Returns `false` for the keys left to the caller,
+    /// like Esc in normal mode
+    pub fn handle_modal_key(&mut self, key: KeyEvent, multiline: bool) -> bool {
+        match self.vim.mode {
+            VimMode::Insert => self.handle_insert_key(key, multiline),
+            VimMode::Normal => self.handle_normal_key(key, multiline),
+        }
+    }
+
+    fn handle_insert_key(&mut self, key: KeyEvent, multiline: bool) -> bool {
+        if key.code == KeyCode::Esc {
+            if let Some(mut keys) = self.vim.recording.take() {
+                keys.push(key);
+                self.vim.last_change = keys;
+            }
+
+            self.vim.mode = VimMode::Normal;
+            self.group_edits = false;
+            self.selection = None;
+            self.move_cursor_left();
+
+            return true;
+        }
+
+        let handled = self.handle_key(key, multiline);
+
+        if let Some(keys) = self.vim.recording.as_mut().filter(|_| handled) {
+            keys.push(key);
+        }
+
+        handled
+    }
+
+    fn reset_command(&mut self) {
+        self.vim.count = None;
+        self.vim.pending = None;
+        self.vim.keys.clear();
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent, multiline: bool) -> bool {
+        let command = match key.code {
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => REDO,
+            KeyCode::Char(_) if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char(c) => c,
+            KeyCode::Left => 'h',
+            KeyCode::Right => 'l',
+            KeyCode::Up => 'k',
+            KeyCode::Down => 'j',
+            KeyCode::Home => '0',
+            KeyCode::End => '$',
+            KeyCode::Esc if self.vim.count.is_some() || self.vim.pending.is_some() => {
+                self.reset_command();
+                return true;
+            }
+            _ => return false,
+        };
+
+        self.vim.keys.push(key);
+
+        if let Some(digit) = command.to_digit(10) {
+            if self.vim.pending.is_none() && (digit != 0 || self.vim.count.is_some()) {
+                self.vim.count = Some(self.vim.count.unwrap_or(0) * 10 + digit as usize);
+                return true;
+            }
+        }
+
+        let count = self.vim.count;
+        let times = count.unwrap_or(1);
+
+        match (self.vim.pending.take(), command) {
+            (None, 'g' | 'd' | 'y') => {
+                self.vim.pending = Some(command);
+                return true;
+            }
+            (None, 'h') => (0..times).for_each(|_| self.move_cursor_left()),
+            (None, 'l') => (0..times).for_each(|_| self.move_cursor_right()),
+            (None, 'j') if multiline => (0..times).for_each(|_| self.move_cursor_down()),
+            (None, 'k') if multiline => (0..times).for_each(|_| self.move_cursor_up()),
+            (None, 'w') => (0..times).for_each(|_| self.move_word_forward()),
+            (None, 'b') => (0..times).for_each(|_| self.move_word_backward()),
+            (None, 'e') => (0..times).for_each(|_| self.move_word_end()),
+            (None, '0') => self.move_cursor_to_start_of_line(),
+            (None, '$') => self.move_cursor_to_end_of_line(),
+            (Some('g'), 'g') => self.go_to_line(count.unwrap_or(1) - 1),
+            (None, 'G') => self.go_to_line(count.map(|n| n - 1).unwrap_or(usize::MAX)),
+            (Some('y'), 'y') => self.yank_lines(times),
+            (None, 'u') => (0..times).for_each(|_| self.undo()),
+            (None, REDO) => (0..times).for_each(|_| self.redo()),
+            (None, '.') => {
+                self.reset_command();
+                self.repeat_last_change(times, multiline);
+            }
+            (None, 'x') => {
+                self.remove_chars(times);
+                self.finish_change();
+            }
+            (Some('d'), 'd') => {
+                self.delete_lines(times);
+                self.finish_change();
+            }
+            (None, 'p') => {
+                self.put(times, true, multiline);
+                self.finish_change();
+            }
+            (None, 'P') => {
+                self.put(times, false, multiline);
+                self.finish_change();
+            }
+            (None, 'i' | 'a' | 'I' | 'A' | 'o' | 'O') => {
+                self.start_insert_change(command, multiline);
+                return true;
+            }
+            _ => {}
+        }
+
+        self.reset_command();
+        self.clamp_normal_cursor();
+
+        true
+    }
+
+    /// In normal mode the cursor stays on a character
+    pub(super) fn clamp_normal_cursor(&mut self) {
+        if self.vim.mode != VimMode::Normal {
+            return;
+        }
+
+        let len = to_u16(self.line_len(self.cursor_position.y.into()));
+
+        self.cursor_position.x = self.cursor_position.x.min(len.saturating_sub(1));
+    }
+
+    /// Remember the command just run for `.`
+    fn finish_change(&mut self) {
+        self.vim.last_change = std::mem::take(&mut self.vim.keys);
+    }
+
+    fn repeat_last_change(&mut self, times: usize, multiline: bool) {
+        let keys = self.vim.last_change.clone();
+
+        for _ in 0..times {
+            for key in &keys {
+                self.handle_modal_key(*key, multiline);
+            }
+        }
+    }
+
+    fn start_insert_change(&mut self, command: char, multiline: bool) {
+        self.vim.recording = Some(std::mem::take(&mut self.vim.keys));
+        self.vim.count = None;
+        self.vim.mode = VimMode::Insert;
+        self.last_edit = None;
+        self.group_edits = true;
+
+        match command {
+            'a' => self.move_cursor_right(),
+            'I' => {
+                let indent = self
+                    .line(self.cursor_position.y.into())
+                    .graphemes(true)
+                    .take_while(|g| g.trim().is_empty())
+                    .count();
+
+                self.cursor_position.x = to_u16(indent);
+            }
+            'A' => self.move_cursor_to_end_of_line(),
+            'o' if multiline => {
+                self.move_cursor_to_end_of_line();
+                self.insert_str("\n");
+            }
+            'O' if multiline => {
+                self.move_cursor_to_start_of_line();
+                self.insert_str("\n");
+                self.cursor_position.y -= 1;
+            }
+            _ => {}
+        }
+    }
+
+    fn go_to_line(&mut self, y: usize) {
+        self.cursor_position = Coordinates {
+            x: 0,
+            y: to_u16(y.min(self.line_count() - 1)),
+        };
+    }
+
+    /// Offset of the last character of the current or next word
+    fn word_end_offset(&self) -> usize {
+        let offset = self.cursor_offset();
+        let mut graphemes = self.text[offset..]
+            .grapheme_indices(true)
+            .skip(1)
+            .peekable();
+
+        while graphemes
+            .next_if(|(_, g)| char_class(g) == CharClass::Whitespace)
+            .is_some()
+        {}
+
+        let Some((mut end, first)) = graphemes.next() else {
+            return offset;
+        };
+
+        let class = char_class(first);
+
+        while let Some((i, _)) = graphemes.next_if(|(_, g)| char_class(g) == class) {
+            end = i;
+        }
+
+        offset + end
+    }
+
+    pub fn move_word_end(&mut self) {
+        self.break_undo_group();
+        self.set_cursor_offset(self.word_end_offset());
+    }
+
+    fn remove_chars(&mut self, count: usize) {
+        let start = self.cursor_offset();
+        let end = self.offset_of(&Coordinates {
+            x: self.cursor_position.x.saturating_add(to_u16(count)),
+            y: self.cursor_position.y,
+        });
+
+        if start < end {
+            let _ = clipboard::copy(&self.text[start..end]);
+
+            self.checkpoint(EditKind::Other);
+            self.replace_range(start, end, "");
+        }
+    }
+
+    /// The `count` lines from the cursor, ending with a line break
+    fn lines_from_cursor(&self, count: usize) -> (usize, usize, String) {
+        let first = usize::from(self.cursor_position.y);
+        let last = (first + count).min(self.line_count()) - 1;
+
+        let mut lines = self
+            .text
+            .split('\n')
+            .skip(first)
+            .take(last - first + 1)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        lines.push('\n');
+
+        (first, last, lines)
+    }
+
+    fn yank_lines(&mut self, count: usize) {
+        let (_, _, lines) = self.lines_from_cursor(count);
+
+        let _ = clipboard::copy(&lines);
+    }
+
+    fn delete_lines(&mut self, count: usize) {
+        let (first, last, lines) = self.lines_from_cursor(count);
+        let (start, end) = self.line_range(first, last);
+
+        let _ = clipboard::copy(&lines);
+
+        self.checkpoint(EditKind::Other);
+        self.replace_range(start, end, "");
+        self.go_to_line(first);
+    }
+
+    /// Paste `count` times after (`p`) or before (`P`) the cursor, text
+    /// copied as whole lines goes on its own lines
+    fn put(&mut self, count: usize, after: bool, multiline: bool) {
+        let text = clipboard::paste();
+
+        if text.is_empty() {
+            return;
+        }
+
+        self.checkpoint(EditKind::Other);
+
+        if multiline && text.ends_with('\n') {
+            let lines = text.repeat(count);
+            let y = usize::from(self.cursor_position.y) + usize::from(after);
+
+            if y < self.line_count() {
+                let offset = self.offset_of(&Coordinates { x: 0, y: to_u16(y) });
+
+                self.text.insert_str(offset, &lines);
+            } else {
+                self.text.push('\n');
+                self.text.push_str(lines.trim_end_matches('\n'));
+            }
+
+            self.go_to_line(y);
+            return;
+        }
+
+        let text = if multiline {
+            text.repeat(count)
+        } else {
+            text.replace(['\r', '\n'], "").repeat(count)
+        };
+
+        if after && self.line_len(self.cursor_position.y.into()) > 0 {
+            self.cursor_position.x += 1;
+        }
+
+        let offset = self.cursor_offset();
+
+        self.text.insert_str(offset, &text);
+        self.set_cursor_offset(offset + text.len());
+        self.cursor_position.x = self.cursor_position.x.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Type vim keys, `<esc>` for Esc
+    fn keys(input: &mut Input, keys: &str, multiline: bool) {
+        let mut rest = keys;
+
+        while let Some(c) = rest.chars().next() {
+            let code = match rest.strip_prefix("<esc>") {
+                Some(after) => {
+                    rest = after;
+                    KeyCode::Esc
+                }
+                None => {
+                    rest = &rest[c.len_utf8()..];
+                    KeyCode::Char(c)
+                }
+            };
+
+            input.handle_modal_key(KeyEvent::new(code, KeyModifiers::NONE), multiline);
+        }
+    }
+
+    fn normal(text: &str) -> Input {
+        let mut input = Input::new(text);
+        keys(&mut input, "<esc>", true);
+        input
+    }
+
+    fn cursor(input: &Input) -> (u16, u16) {
+        (input.cursor_position.x, input.cursor_position.y)
+    }
+
+    #[test]
+    fn esc_goes_to_normal_mode_on_a_character() {
+        let mut input = Input::new("ab");
+        input.move_cursor_to_end_single_line();
+
+        keys(&mut input, "<esc>", true);
+
+        assert!(input.is_normal_mode());
+        assert_eq!(cursor(&input), (1, 0));
+    }
+
+    #[test]
+    fn moves_with_counts() {
+        let mut input = normal("one two three four\nfive\nsix\nseven");
+
+        keys(&mut input, "2w", true);
+        assert_eq!(cursor(&input), (8, 0));
+
+        keys(&mut input, "e", true);
+        assert_eq!(cursor(&input), (12, 0));
+
+        keys(&mut input, "$", true);
+        assert_eq!(cursor(&input), (17, 0));
+
+        // Clamped to the shorter line, on its last character
+        keys(&mut input, "j", true);
+        assert_eq!(cursor(&input), (3, 1));
+
+        keys(&mut input, "G", true);
+        assert_eq!(cursor(&input), (0, 3));
+
+        keys(&mut input, "2gg", true);
+        assert_eq!(cursor(&input), (0, 1));
+
+        keys(&mut input, "10l", true);
+        assert_eq!(cursor(&input), (3, 1));
+    }
+
+    #[test]
+    fn counts_and_pending_commands_are_cancelled_by_esc() {
+        let mut input = normal("abcdef");
+
+        keys(&mut input, "3<esc>l", true);
+        assert_eq!(cursor(&input), (1, 0));
+
+        keys(&mut input, "d<esc>l", true);
+        assert_eq!(input.text, "abcdef");
+        assert_eq!(cursor(&input), (2, 0));
+    }
+
+    #[test]
+    fn inserts_and_repeats_with_dot() {
+        let mut input = normal("a\nb");
+
+        keys(&mut input, "Ax<esc>", true);
+        assert_eq!(input.text, "ax\nb");
+
+        keys(&mut input, "j.", true);
+        assert_eq!(input.text, "ax\nbx");
+
+        keys(&mut input, "oc<esc>", true);
+        assert_eq!(input.text, "ax\nbx\nc");
+        assert_eq!(cursor(&input), (0, 2));
+
+        // The whole insert is undone at once
+        keys(&mut input, "u", true);
+        assert_eq!(input.text, "ax\nbx");
+    }
+
+    /// The only test using the clipboard, shared by the tests running in
+    /// parallel
+    #[test]
+    fn deletes_yanks_and_puts_lines() {
+        let mut input = normal("1\n2\n3\n4");
+
+        keys(&mut input, "j2dd", true);
+        assert_eq!(input.text, "1\n4");
+        assert_eq!(cursor(&input), (0, 1));
+
+        keys(&mut input, "P", true);
+        assert_eq!(input.text, "1\n2\n3\n4");
+        assert_eq!(cursor(&input), (0, 1));
+
+        keys(&mut input, "Gp", true);
+        assert_eq!(input.text, "1\n2\n3\n4\n2\n3");
+
+        keys(&mut input, "u", true);
+        assert_eq!(input.text, "1\n2\n3\n4");
+
+        keys(&mut input, "gg3x.", true);
+        assert_eq!(input.text, "\n2\n3\n4");
+    }
+
+    #[test]
+    fn single_line_inputs_ignore_line_commands() {
+        let mut input = Input::new("abc");
+        keys(&mut input, "<esc>", false);
+
+        keys(&mut input, "ljk", false);
+        assert_eq!(cursor(&input), (1, 0));
+
+        // Inserts in place instead of opening a line
+        keys(&mut input, "o<esc>", false);
+        assert_eq!(input.text, "abc");
+    }
+}
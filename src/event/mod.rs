@@ -1,472 +1,686 @@
+pub mod collection;
+mod help;
 pub mod input;
+pub mod mouse;
 mod navigation;
 mod popup;
+mod response;
 
 use crossterm::event::{KeyCode, KeyEvent};
 
-use crate::app::{
-    form::{Form, FormField, FormKind},
-    App, AppBlock, AppPopup, BodyContentType, BodyType, InputMode, OrderNavigation, Request,
-    RequestTab,
+use crate::{
+    app::{
+        form::{Form, FormField, FormKind},
+        App, AppBlock, AppPopup, BodyContentType, BodyType, InputMode, OrderNavigation, Request,
+        RequestTab, ResponseView,
+    },
+    clipboard,
+    config::{
+        keymap::{Action, Key, KeyContext, Resolution},
+        layout::Pane,
+    },
+    external,
 };
 
+/// Send the current request, asking first if the JSON body is invalid
+async fn send_request(app: &mut App) {
+    if let BodyContentType::Text(BodyType::Json) = app.body_content_type {
+        if let Some(error) = app.body_error() {
+            app.popup = Some(AppPopup::ConfirmSend(format!(
+                "The body is not valid JSON: {error}"
+            )));
+            return;
+        }
+    }
+
+    dispatch_request(app).await;
+}
+
+/// Open the body in `$VISUAL` or `$EDITOR` and load it back when it exits
+fn edit_body_externally(app: &mut App) {
+    let BodyContentType::Text(body_type) = &app.body_content_type else {
+        return;
+    };
+
+    let path = external::temp_path("body", body_type.kind().syntax_extension());
+
+    if let Err(e) = std::fs::write(&path, &app.raw_body.text) {
+        app.status_message = Some(format!("Could not write {}: {e}", path.display()));
+        return;
+    }
+
+    let command = external::command_from_env(&["VISUAL", "EDITOR"], "vi");
+
+    match external::run_suspended(&command, &path).and_then(|_| std::fs::read_to_string(&path)) {
+        Ok(text) => {
+            // Editors usually add a final newline
+            let text = match app.raw_body.text.ends_with('\n') {
+                true => text,
+                false => text.strip_suffix('\n').map(String::from).unwrap_or(text),
+            };
+
+            if text != app.raw_body.text {
+                app.raw_body.set_text(text);
+            }
+        }
+        Err(e) => app.status_message = Some(format!("Could not edit body with {command}: {e}")),
+    }
+
+    let _ = std::fs::remove_file(&path);
+
+    app.needs_redraw = true;
+}
+
+fn show_request_tab(app: &mut App, tab: RequestTab) {
+    app.request_tab = tab;
+    app.selected_block = AppBlock::RequestContent;
+}
+
+/// The pane of the selected block, the response for the blocks above both
+fn selected_pane(app: &App) -> Pane {
+    Pane::of(&app.selected_block).unwrap_or(Pane::Response)
+}
+
+fn resize_pane(app: &mut App, grow: bool) {
+    app.layout.maximized = None;
+    app.layout.resize(selected_pane(app), grow);
+
+    save_layout(app);
+}
+
+fn save_layout(app: &mut App) {
+    if let Err(e) = app.layout.save() {
+        app.status_message = Some(format!("Could not save the layout: {e}"));
+    }
+}
+
+/// Copy the response as shown, pretty printed and filtered
+fn copy_response(app: &mut App) {
+    if app.response.as_ref().is_none_or(|r| r.is_binary) {
+        app.status_message = Some("No text response to copy".to_string());
+        return;
+    }
+
+    let (text, _) = app.response_display();
+
+    app.status_message = Some(match clipboard::copy(&text) {
+        Ok(_) => "Copied response to clipboard".to_string(),
+        Err(e) => format!("Could not copy response: {e}"),
+    });
+}
+
+pub async fn dispatch_request(app: &mut App) {
+    app.is_loading = true;
+    app.req_tx.send(Request::from_app(app)).await.unwrap();
+}
+
+/// The key contexts active in normal mode, most specific first
+fn key_contexts(app: &App) -> Vec<KeyContext> {
+    if app.popup.is_some() {
+        return vec![KeyContext::Popup];
+    }
+
+    if app.selected_block != AppBlock::Response {
+        return vec![KeyContext::Normal];
+    }
+
+    if app.response.as_ref().is_some_and(|r| r.is_binary) {
+        vec![KeyContext::Response, KeyContext::Hex, KeyContext::Normal]
+    } else if app.response_view == ResponseView::Tree && app.response_tree.is_some() {
+        vec![KeyContext::Response, KeyContext::Tree, KeyContext::Normal]
+    } else {
+        vec![KeyContext::Response, KeyContext::Normal]
+    }
+}
+
+/// Add a key to the chord being typed, returns its action once complete
+fn resolve_action(app: &mut App, key: KeyEvent) -> Option<Action> {
+    app.pending_keys.push(Key::from(key));
+
+    match app.keymap.resolve(&key_contexts(app), &app.pending_keys) {
+        Resolution::Action(action) => {
+            app.pending_keys.clear();
+            Some(action)
+        }
+        Resolution::Pending => None,
+        Resolution::Unbound => {
+            let was_chord = app.pending_keys.len() > 1;
+
+            app.pending_keys.clear();
+
+            // The key may start a binding of its own
+            match was_chord {
+                true => resolve_action(app, key),
+                false => None,
+            }
+        }
+    }
+}
+
 pub async fn handle_input(app: &mut App, key: KeyEvent) {
-    if let Some(_) = &mut app.popup {
-        popup::handle_popup_events(app, key);
+    if app.input_mode == InputMode::Insert {
+        handle_insert(app, key).await;
         return;
     }
 
-    match app.input_mode {
-        InputMode::Normal => match key.code {
-            KeyCode::Char('i') => match app.selected_block {
-                AppBlock::Endpoint => {
-                    app.input_mode = InputMode::Insert;
-                    app.endpoint.move_cursor_to_end_single_line();
+    if let Some(action) = resolve_action(app, key) {
+        run_action(app, action).await;
+    }
+}
+
+/// Run an action typed in normal mode or picked in the command palette
+async fn run_action(app: &mut App, action: Action) {
+    if action == Action::Help && !matches!(app.popup, Some(AppPopup::Help(_))) {
+        help::open_help(app);
+        return;
+    }
+
+    if app.popup.is_some() {
+        popup::handle_popup_action(app, action).await;
+        return;
+    }
+
+    app.status_message = None;
+
+    if app.selected_block == AppBlock::Response && response::handle_response_action(app, action) {
+        return;
+    }
+
+    let in_content = app.selected_block == AppBlock::RequestContent;
+    let in_body = in_content && app.request_tab == RequestTab::Body;
+
+    match action {
+        Action::Quit => app.should_quit = true,
+        Action::Send => send_request(app).await,
+        Action::CommandPalette => help::open_palette(app),
+        Action::OpenRequest => collection::open_requests(app),
+        Action::SelectEnvironment => collection::open_environments(app),
+        Action::SaveRequest => collection::save_request(app),
+        Action::History => collection::open_history(app),
+        Action::ExportHar => collection::export_har(app),
+        Action::SaveExample => collection::save_example(app),
+        Action::Examples => collection::open_examples(app),
+        Action::DiffExample => collection::diff_example(app),
+        Action::CompareResponses => collection::compare_responses(app),
+        Action::ChangeMethod => app.popup = Some(AppPopup::ChangeMethod),
+        Action::ShowBody => show_request_tab(app, RequestTab::Body),
+        Action::ShowQuery => show_request_tab(app, RequestTab::Query),
+        Action::ShowHeaders => show_request_tab(app, RequestTab::Headers),
+        Action::CopyResponse => copy_response(app),
+        Action::GrowPane => resize_pane(app, true),
+        Action::ShrinkPane => resize_pane(app, false),
+        Action::CycleLayout => {
+            app.layout.orientation = app.layout.orientation.next();
+            app.status_message = Some(format!("Layout: {}", app.layout.orientation.description()));
+            save_layout(app);
+        }
+        Action::ToggleMaximize => {
+            app.layout.toggle_maximized(selected_pane(app));
+            app.status_message = Some(
+                match app.layout.maximized {
+                    Some(Pane::Request) => "Maximized the request",
+                    Some(Pane::Response) => "Maximized the response",
+                    None => "Restored the panes",
                 }
-                AppBlock::RequestContent => {
-                    if let RequestTab::Body = app.request_tab {
-                        if let BodyContentType::Text(_) = app.body_content_type {
-                            app.input_mode = InputMode::Insert;
-                        }
+                .to_string(),
+            );
+        }
+        Action::Insert => match app.selected_block {
+            AppBlock::Endpoint => {
+                app.input_mode = InputMode::Insert;
+                app.endpoint.move_cursor_to_end_single_line();
+            }
+            AppBlock::ResponseFilter => {
+                app.input_mode = InputMode::Insert;
+                app.response_filter.move_cursor_to_end_single_line();
+            }
+            AppBlock::RequestContent => {
+                if let RequestTab::Body = app.request_tab {
+                    if let BodyContentType::Text(_) = app.body_content_type {
+                        app.input_mode = InputMode::Insert;
+                        app.raw_body.enter_insert_mode();
                     }
                 }
-                _ => {}
-            },
-            KeyCode::Tab => {
-                app.selected_block = app.selected_block.next();
             }
-            KeyCode::BackTab => {
-                app.selected_block = app.selected_block.previous();
+            _ => {}
+        },
+        Action::NextBlock => {
+            app.selected_block = app.selected_block.next();
+        }
+        Action::PreviousBlock => {
+            app.selected_block = app.selected_block.previous();
+        }
+        Action::Select => match app.selected_block {
+            AppBlock::Request => {
+                app.selected_block = AppBlock::RequestContent;
             }
-            KeyCode::Enter => match app.selected_block {
-                AppBlock::Request => {
-                    app.selected_block = AppBlock::RequestContent;
-                }
-                AppBlock::Method => {
-                    app.popup = Some(AppPopup::ChangeMethod);
-                }
-                _ => {
-                    app.is_loading = true;
-                    app.req_tx.send(Request::from_app(&app)).await.unwrap();
-                }
-            },
-            KeyCode::Char('j') => match app.selected_block {
-                AppBlock::Response => {
-                    navigation::scroll_down_response(app);
-                }
-                AppBlock::Request => {
-                    app.request_tab = app.request_tab.previous();
+            AppBlock::Method => {
+                app.popup = Some(AppPopup::ChangeMethod);
+            }
+            _ => {
+                send_request(app).await;
+            }
+        },
+        Action::Down => match app.selected_block {
+            AppBlock::Response => {
+                navigation::scroll_down_response(app);
+            }
+            AppBlock::Request => {
+                app.request_tab = app.request_tab.previous();
+            }
+            AppBlock::RequestContent => match app.request_tab {
+                RequestTab::Headers => {
+                    let quantity = app.headers.len() as u16;
+
+                    if quantity == 0 {
+                        app.selected_query_param = 0;
+                        return;
+                    }
+
+                    if app.selected_header < quantity - 1 {
+                        app.selected_header += 1;
+                    } else {
+                        app.selected_header = 0;
+                    }
                 }
-                AppBlock::RequestContent => match app.request_tab {
-                    RequestTab::Headers => {
-                        let quantity = app.headers.len() as u16;
 
-                        if quantity == 0 {
-                            app.selected_query_param = 0;
-                            return;
-                        }
+                RequestTab::Query => {
+                    let quantity = app.query_params.len() as u16;
 
-                        if app.selected_header < quantity - 1 {
-                            app.selected_header += 1;
-                        } else {
-                            app.selected_header = 0;
-                        }
+                    if quantity == 0 {
+                        app.selected_query_param = 0;
+                        return;
+                    }
+
+                    if app.selected_query_param < quantity - 1 {
+                        app.selected_query_param += 1;
+                    } else {
+                        app.selected_query_param = 0;
                     }
+                }
 
-                    RequestTab::Query => {
-                        let quantity = app.query_params.len() as u16;
+                RequestTab::Body => {
+                    if let BodyContentType::Form = app.body_content_type {
+                        let quantity = app.body_form.len() as u16;
 
                         if quantity == 0 {
-                            app.selected_query_param = 0;
+                            app.selected_form_field = 0;
                             return;
                         }
 
-                        if app.selected_query_param < quantity - 1 {
-                            app.selected_query_param += 1;
+                        if app.selected_form_field < quantity - 1 {
+                            app.selected_form_field += 1;
                         } else {
-                            app.selected_query_param = 0;
-                        }
-                    }
-
-                    RequestTab::Body => {
-                        if let BodyContentType::Form = app.body_content_type {
-                            let quantity = app.body_form.len() as u16;
-
-                            if quantity == 0 {
-                                app.selected_form_field = 0;
-                                return;
-                            }
-
-                            if app.selected_form_field < quantity - 1 {
-                                app.selected_form_field += 1;
-                            } else {
-                                app.selected_form_field = 0;
-                            }
+                            app.selected_form_field = 0;
                         }
                     }
-                    _ => {}
-                },
-                AppBlock::Method => app.method = app.method.previous(),
+                }
                 _ => {}
             },
-            KeyCode::Char('k') => match app.selected_block {
-                AppBlock::Response => {
-                    navigation::scroll_up_response(app);
+            AppBlock::Method => app.method = app.method.previous(),
+            _ => {}
+        },
+        Action::Up => match app.selected_block {
+            AppBlock::Response => {
+                navigation::scroll_up_response(app);
+            }
+            AppBlock::Request => app.request_tab = app.request_tab.next(),
+            AppBlock::RequestContent => match app.request_tab {
+                RequestTab::Headers => {
+                    let quantity = app.headers.len() as u16;
+
+                    if quantity == 0 {
+                        app.selected_header = 0;
+                        return;
+                    }
+
+                    if app.selected_header > 0 {
+                        app.selected_header -= 1;
+                    } else {
+                        app.selected_header = quantity - 1;
+                    }
                 }
-                AppBlock::Request => app.request_tab = app.request_tab.next(),
-                AppBlock::RequestContent => match app.request_tab {
-                    RequestTab::Headers => {
-                        let quantity = app.headers.len() as u16;
+                RequestTab::Query => {
+                    let quantity = app.query_params.len() as u16;
 
-                        if quantity == 0 {
-                            app.selected_header = 0;
-                            return;
-                        }
+                    if quantity == 0 {
+                        app.selected_query_param = 0;
+                        return;
+                    }
 
-                        if app.selected_header > 0 {
-                            app.selected_header -= 1;
-                        } else {
-                            app.selected_header = quantity - 1;
-                        }
+                    if app.selected_query_param > 0 {
+                        app.selected_query_param -= 1;
+                    } else {
+                        app.selected_query_param = quantity - 1;
                     }
-                    RequestTab::Query => {
-                        let quantity = app.query_params.len() as u16;
+                }
+
+                RequestTab::Body => {
+                    if let BodyContentType::Form = app.body_content_type {
+                        let quantity = app.body_form.len() as u16;
 
                         if quantity == 0 {
-                            app.selected_query_param = 0;
+                            app.selected_form_field = 0;
                             return;
                         }
 
-                        if app.selected_query_param > 0 {
-                            app.selected_query_param -= 1;
+                        if app.selected_form_field > 0 {
+                            app.selected_form_field -= 1;
                         } else {
-                            app.selected_query_param = quantity - 1;
+                            app.selected_form_field = quantity - 1;
                         }
                     }
-
-                    RequestTab::Body => {
-                        if let BodyContentType::Form = app.body_content_type {
-                            let quantity = app.body_form.len() as u16;
-
-                            if quantity == 0 {
-                                app.selected_form_field = 0;
-                                return;
-                            }
-
-                            if app.selected_form_field > 0 {
-                                app.selected_form_field -= 1;
-                            } else {
-                                app.selected_form_field = quantity - 1;
-                            }
-                        }
-                    }
-                    _ => {}
-                },
-                AppBlock::Method => app.method = app.method.next(),
+                }
                 _ => {}
             },
-            KeyCode::Char('c') => match app.selected_block {
-                AppBlock::RequestContent => match app.request_tab {
-                    RequestTab::Body => {
-                        app.body_content_type = match app.body_content_type {
-                            BodyContentType::Text(_) => BodyContentType::Form,
-                            BodyContentType::Form => BodyContentType::Text(BodyType::Raw),
-                        };
+            AppBlock::Method => app.method = app.method.next(),
+            _ => {}
+        },
+        Action::FormatBody if in_body => app.format_body(),
+        Action::EditExternally if in_body => edit_body_externally(app),
+        Action::ToggleContentType if in_body => {
+            app.body_content_type = match app.body_content_type {
+                BodyContentType::Text(_) => BodyContentType::Form,
+                BodyContentType::Form => BodyContentType::Text(BodyType::Raw),
+            };
+        }
+        Action::ToggleBodyType if in_body => {
+            if let BodyContentType::Text(body_type) = app.body_content_type.clone() {
+                let new_body_type = match body_type {
+                    BodyType::Raw => BodyType::Json,
+                    BodyType::Json => BodyType::Xml,
+                    BodyType::Xml => BodyType::Raw,
+                };
+
+                match new_body_type {
+                    BodyType::Json => {
+                        app.headers
+                            .insert("Content-Type".to_owned(), "application/json".to_owned());
                     }
-                    _ => {}
-                },
-                _ => {}
-            },
-            KeyCode::Char('t') => match app.selected_block {
-                AppBlock::RequestContent => match app.request_tab {
-                    RequestTab::Body => {
-                        if let BodyContentType::Text(body_type) = app.body_content_type.clone() {
-                            let new_body_type = match body_type {
-                                BodyType::Raw => BodyType::Json,
-                                BodyType::Json => BodyType::Xml,
-                                BodyType::Xml => BodyType::Raw,
-                            };
-
-                            match new_body_type {
-                                BodyType::Json => {
-                                    app.headers.insert(
-                                        "Content-Type".to_owned(),
-                                        "application/json".to_owned(),
-                                    );
-                                }
-                                BodyType::Raw => {
-                                    app.headers
-                                        .insert("Content-Type".to_owned(), "text/plain".to_owned());
-                                }
-                                BodyType::Xml => {
-                                    app.headers.insert(
-                                        "Content-Type".to_owned(),
-                                        "application/xml".to_owned(),
-                                    );
-                                }
-                            }
-
-                            app.body_content_type = BodyContentType::Text(new_body_type);
-                        }
+                    BodyType::Raw => {
+                        app.headers
+                            .insert("Content-Type".to_owned(), "text/plain".to_owned());
                     }
-                    _ => {}
-                },
-                _ => {}
-            },
-            KeyCode::Char('a') => match app.selected_block {
-                AppBlock::RequestContent => match app.request_tab {
-                    RequestTab::Headers => {
-                        let key_input = FormField::new("Key", "key");
+                    BodyType::Xml => {
+                        app.headers
+                            .insert("Content-Type".to_owned(), "application/xml".to_owned());
+                    }
+                }
 
-                        let value_input = FormField::new("Value", "value");
+                app.body_content_type = BodyContentType::Text(new_body_type);
+            }
+        }
+        Action::Add if in_content => match app.request_tab {
+            RequestTab::Headers => {
+                let key_input = FormField::new("Key", "key");
 
-                        let form = Form::new(FormKind::AddHeader, vec![key_input, value_input])
-                            .title("Add Header");
+                let value_input = FormField::new("Value", "value");
 
-                        app.popup = Some(AppPopup::FormPopup(form));
-                    }
-                    RequestTab::Query => {
-                        let key_input = FormField::new("Key", "key");
+                let form = Form::new(FormKind::AddHeader, vec![key_input, value_input])
+                    .title("Add Header");
 
-                        let value_input = FormField::new("Value", "value");
+                app.popup = Some(AppPopup::FormPopup(form));
+            }
+            RequestTab::Query => {
+                let key_input = FormField::new("Key", "key");
 
-                        let form = Form::new(FormKind::AddQueryParam, vec![key_input, value_input])
-                            .title("Add Query Param");
+                let value_input = FormField::new("Value", "value");
 
-                        app.popup = Some(AppPopup::FormPopup(form));
-                    }
-                    RequestTab::Body => {
-                        if let BodyContentType::Form = app.body_content_type {
-                            let key_input = FormField::new("Key", "key");
+                let form = Form::new(FormKind::AddQueryParam, vec![key_input, value_input])
+                    .title("Add Query Param");
 
-                            let value_input = FormField::new("Value", "value");
+                app.popup = Some(AppPopup::FormPopup(form));
+            }
+            RequestTab::Body => {
+                if let BodyContentType::Form = app.body_content_type {
+                    let key_input = FormField::new("Key", "key");
 
-                            let form =
-                                Form::new(FormKind::AddBodyFormField, vec![key_input, value_input])
-                                    .title("Add Form Field");
+                    let value_input = FormField::new("Value", "value");
 
-                            app.popup = Some(AppPopup::FormPopup(form));
-                        }
-                    }
-                    _ => {}
-                },
-                _ => {}
-            },
-            KeyCode::Char('e') => match app.selected_block {
-                AppBlock::RequestContent => match app.request_tab {
-                    RequestTab::Headers => {
-                        let key = app
-                            .headers
-                            .clone()
-                            .keys()
-                            .nth(app.selected_header as usize)
-                            .unwrap()
-                            .to_owned();
-
-                        let value = app.headers.get(&key).unwrap().to_owned();
-
-                        let key_input = FormField::new("Key", "key").value(&key);
-
-                        let current_key = FormField::new("Current Key", "current_key")
-                            .value(&key)
-                            .hidden();
-
-                        let value_input = FormField::new("Value", "value").value(&value);
-
-                        let form = Form::new(
-                            FormKind::EditHeader,
-                            vec![key_input, value_input, current_key],
-                        )
-                        .title("Edit Header");
-
-                        app.popup = Some(AppPopup::FormPopup(form));
-                    }
-                    RequestTab::Query => {
-                        let (key, value) =
-                            app.query_params[app.selected_query_param as usize].clone();
+                    let form = Form::new(FormKind::AddBodyFormField, vec![key_input, value_input])
+                        .title("Add Form Field");
 
-                        let key_input = FormField::new("Key", "key").value(&key);
+                    app.popup = Some(AppPopup::FormPopup(form));
+                }
+            }
+            _ => {}
+        },
+        Action::Edit if in_content => match app.request_tab {
+            RequestTab::Headers => {
+                let key = app
+                    .headers
+                    .clone()
+                    .keys()
+                    .nth(app.selected_header as usize)
+                    .unwrap()
+                    .to_owned();
 
-                        let value_input = FormField::new("Value", "value").value(&value);
+                let value = app.headers.get(&key).unwrap().to_owned();
 
-                        let index_input = FormField::new("Index", "index")
-                            .value(&app.selected_query_param.to_string())
-                            .hidden();
+                let key_input = FormField::new("Key", "key").value(&key);
 
-                        let form = Form::new(
-                            FormKind::EditQueryParam,
-                            vec![key_input, value_input, index_input],
-                        )
-                        .title("Edit Query Param");
+                let current_key = FormField::new("Current Key", "current_key")
+                    .value(&key)
+                    .hidden();
 
-                        app.popup = Some(AppPopup::FormPopup(form));
-                    }
-                    RequestTab::Body => {
-                        if let BodyContentType::Form = app.body_content_type {
-                            let (key, value) = app
-                                .body_form
-                                .iter()
-                                .nth(app.selected_form_field as usize)
-                                .unwrap();
+                let value_input = FormField::new("Value", "value").value(&value);
 
-                            let key_input = FormField::new("Key", "key").value(key);
+                let form = Form::new(
+                    FormKind::EditHeader,
+                    vec![key_input, value_input, current_key],
+                )
+                .title("Edit Header");
 
-                            let value_input = FormField::new("Value", "value").value(value);
+                app.popup = Some(AppPopup::FormPopup(form));
+            }
+            RequestTab::Query => {
+                let (key, value) = app.query_params[app.selected_query_param as usize].clone();
 
-                            let current_key = FormField::new("Current Key", "current_key")
-                                .value(&key)
-                                .hidden();
+                let key_input = FormField::new("Key", "key").value(&key);
 
-                            let form = Form::new(
-                                FormKind::EditBodyFormField,
-                                vec![key_input, value_input, current_key],
-                            )
-                            .title("Edit Form Field");
+                let value_input = FormField::new("Value", "value").value(&value);
 
-                            app.popup = Some(AppPopup::FormPopup(form));
-                        }
-                    }
-                    _ => {}
-                },
-                _ => {}
-            },
-            KeyCode::Char('d') => match app.selected_block {
-                AppBlock::RequestContent => match app.request_tab {
-                    RequestTab::Headers => {
-                        if app.headers.len() == 0 {
-                            return;
-                        }
+                let index_input = FormField::new("Index", "index")
+                    .value(&app.selected_query_param.to_string())
+                    .hidden();
 
-                        let key = app
-                            .headers
-                            .clone()
-                            .keys()
-                            .nth(app.selected_header as usize)
-                            .unwrap()
-                            .to_owned();
+                let form = Form::new(
+                    FormKind::EditQueryParam,
+                    vec![key_input, value_input, index_input],
+                )
+                .title("Edit Query Param");
 
-                        app.headers.remove(&key);
+                app.popup = Some(AppPopup::FormPopup(form));
+            }
+            RequestTab::Body => {
+                if let BodyContentType::Form = app.body_content_type {
+                    let (key, value) = app
+                        .body_form
+                        .iter()
+                        .nth(app.selected_form_field as usize)
+                        .unwrap();
 
-                        if app.selected_header as usize == app.headers.len()
-                            && app.headers.len() != 0
-                        {
-                            app.selected_header -= 1;
-                        }
-                    }
-                    RequestTab::Query => {
-                        if app.query_params.len() == 0 {
-                            return;
-                        }
+                    let key_input = FormField::new("Key", "key").value(key);
 
-                        app.query_params.remove(app.selected_query_param as usize);
+                    let value_input = FormField::new("Value", "value").value(value);
 
-                        if app.selected_query_param as usize == app.query_params.len()
-                            && app.query_params.len() != 0
-                        {
-                            app.selected_query_param -= 1;
-                        }
-                    }
-                    RequestTab::Body => {
-                        if let BodyContentType::Form = app.body_content_type {
-                            if app.body_form.len() == 0 {
-                                return;
-                            }
-
-                            let key = app
-                                .body_form
-                                .clone()
-                                .keys()
-                                .nth(app.selected_form_field as usize)
-                                .unwrap()
-                                .to_owned();
-
-                            app.body_form.remove(&key);
-
-                            if app.selected_header as usize == app.body_form.len()
-                                && app.body_form.len() != 0
-                            {
-                                app.selected_form_field -= 1;
-                            }
-                        }
-                    }
-                    _ => {}
-                },
-                _ => {}
-            },
+                    let current_key = FormField::new("Current Key", "current_key")
+                        .value(key)
+                        .hidden();
+
+                    let form = Form::new(
+                        FormKind::EditBodyFormField,
+                        vec![key_input, value_input, current_key],
+                    )
+                    .title("Edit Form Field");
+
+                    app.popup = Some(AppPopup::FormPopup(form));
+                }
+            }
             _ => {}
         },
-        InputMode::Insert => match key.code {
-            KeyCode::Char(c) => match app.selected_block {
-                AppBlock::Endpoint => {
-                    app.endpoint.add_char_at_cursor(c);
-                }
-                AppBlock::RequestContent => {
-                    if let RequestTab::Body = app.request_tab {
-                        app.raw_body.add_char_at_cursor(c);
-                    }
-                }
-                _ => {}
-            },
-            KeyCode::Up => match app.selected_block {
-                AppBlock::RequestContent => {
-                    app.raw_body.move_cursor_up();
+        Action::Delete if in_content => match app.request_tab {
+            RequestTab::Headers => {
+                if app.headers.is_empty() {
+                    return;
                 }
-                _ => {}
-            },
-            KeyCode::Down => match app.selected_block {
-                AppBlock::RequestContent => {
-                    app.raw_body.move_cursor_down();
+
+                let key = app
+                    .headers
+                    .clone()
+                    .keys()
+                    .nth(app.selected_header as usize)
+                    .unwrap()
+                    .to_owned();
+
+                app.headers.remove(&key);
+
+                if app.selected_header as usize == app.headers.len() && !app.headers.is_empty() {
+                    app.selected_header -= 1;
                 }
-                _ => {}
-            },
-            KeyCode::Right => match app.selected_block {
-                AppBlock::Endpoint => {
-                    app.endpoint.move_cursor_right();
+            }
+            RequestTab::Query => {
+                if app.query_params.is_empty() {
+                    return;
                 }
-                AppBlock::RequestContent => {
-                    app.raw_body.move_cursor_right();
+
+                app.query_params.remove(app.selected_query_param as usize);
+
+                if app.selected_query_param as usize == app.query_params.len()
+                    && !app.query_params.is_empty()
+                {
+                    app.selected_query_param -= 1;
                 }
-                _ => (),
-            },
-            KeyCode::Left => match app.selected_block {
-                AppBlock::Endpoint => {
-                    app.endpoint.move_cursor_left();
+            }
+            RequestTab::Body => {
+                if let BodyContentType::Form = app.body_content_type {
+                    if app.body_form.is_empty() {
+                        return;
+                    }
+
+                    let key = app
+                        .body_form
+                        .clone()
+                        .keys()
+                        .nth(app.selected_form_field as usize)
+                        .unwrap()
+                        .to_owned();
+
+                    app.body_form.remove(&key);
+
+                    if app.selected_header as usize == app.body_form.len()
+                        && !app.body_form.is_empty()
+                    {
+                        app.selected_form_field -= 1;
+                    }
                 }
-                AppBlock::RequestContent => {
-                    app.raw_body.move_cursor_left();
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+async fn handle_insert(app: &mut App, key: KeyEvent) {
+    if let Some(AppPopup::CommandPalette(_)) = app.popup {
+        popup::handle_palette_input(app, key).await;
+        return;
+    }
+
+    if let Some(AppPopup::Picker(_)) = app.popup {
+        popup::handle_picker_input(app, key);
+        return;
+    }
+
+    if app.popup.is_some() {
+        popup::handle_form_input(app, key);
+        return;
+    }
+
+    app.status_message = None;
+
+    if app.selected_block == AppBlock::Response {
+        response::handle_search_input(app, key);
+        return;
+    }
+
+    match app.selected_block {
+        AppBlock::RequestContent => {
+            if let RequestTab::Body = app.request_tab {
+                if !app.raw_body.handle_modal_key(key, true) && key.code == KeyCode::Esc {
+                    app.input_mode = InputMode::Normal;
                 }
-                _ => (),
-            },
+            }
+        }
+        _ => match key.code {
             KeyCode::Enter => match app.selected_block {
-                AppBlock::RequestContent => {
-                    app.raw_body.add_newline_at_cursor();
-                }
                 AppBlock::Endpoint => {
-                    app.is_loading = true;
-
-                    app.req_tx.send(Request::from_app(&app)).await.unwrap();
+                    app.input_mode = InputMode::Normal;
 
+                    send_request(app).await;
+                }
+                AppBlock::ResponseFilter => {
                     app.input_mode = InputMode::Normal;
                 }
                 _ => {}
             },
-            KeyCode::Tab => {
-                if let AppBlock::RequestContent = app.selected_block {
-                    app.raw_body.add_char_at_cursor(' ');
-                    app.raw_body.add_char_at_cursor(' ');
-                }
-            }
-            KeyCode::Backspace => match app.selected_block {
+            KeyCode::Esc => app.input_mode = InputMode::Normal,
+            _ => match app.selected_block {
                 AppBlock::Endpoint => {
-                    app.endpoint.remove_char_before_cursor_single_line();
+                    app.endpoint.handle_key(key, false);
                 }
-                AppBlock::RequestContent => {
-                    app.raw_body.remove_char_before_cursor();
+                AppBlock::ResponseFilter if app.response_filter.handle_key(key, false) => {
+                    app.save_response_filter();
+                    app.response_scroll = (0, 0);
                 }
                 _ => {}
             },
-            KeyCode::Esc => app.input_mode = InputMode::Normal,
-            _ => {}
         },
     }
 }
+
+/// Insert text pasted in the terminal into the input being edited
+pub fn handle_paste(app: &mut App, text: &str) {
+    if app.input_mode != InputMode::Insert {
+        return;
+    }
+
+    match app.popup.as_mut() {
+        Some(AppPopup::FormPopup(form)) => {
+            if let Some(field) = form.fields.get_mut(form.selected_field) {
+                field.input.paste(text, false);
+            }
+            return;
+        }
+        Some(AppPopup::CommandPalette(palette)) => {
+            palette.input.paste(text, false);
+            palette.selected = 0;
+            return;
+        }
+        Some(AppPopup::Picker(picker)) => {
+            picker.input.paste(text, false);
+            picker.selected = 0;
+            return;
+        }
+        _ => {}
+    }
+
+    match app.selected_block {
+        AppBlock::Endpoint => app.endpoint.paste(text, false),
+        AppBlock::ResponseFilter => {
+            app.response_filter.paste(text, false);
+            app.save_response_filter();
+            app.response_scroll = (0, 0);
+        }
+        AppBlock::RequestContent => {
+            if let RequestTab::Body = app.request_tab {
+                app.raw_body.paste(text, true);
+            }
+        }
+        AppBlock::Response => {
+            app.response_search.input.paste(text, false);
+            response::focus_first_match(app);
+        }
+        _ => {}
+    }
+}
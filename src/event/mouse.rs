@@ -0,0 +1,212 @@
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+
+use crate::app::{
+    areas::{contains, Areas},
+    App, AppBlock, AppPopup, InputMode, OrderNavigation, RequestTab, ResponseView,
+};
+
+use super::input::Input;
+
+/// Lines moved by a step of the scroll wheel
+const SCROLL_LINES: usize = 3;
+
+pub fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    let (column, row) = (mouse.column, mouse.row);
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => match app.popup {
+            Some(_) => click_popup(app, column, row),
+            None => click(app, column, row),
+        },
+        MouseEventKind::ScrollDown if app.popup.is_none() => scroll(app, column, row, true),
+        MouseEventKind::ScrollUp if app.popup.is_none() => scroll(app, column, row, false),
+        _ => {}
+    }
+}
+
+/// Move the cursor of a single line input drawn in `area` to the clicked
+/// column
+fn click_input(input: &mut Input, area: Rect, column: u16) {
+    let width = usize::from(area.width.saturating_sub(2));
+    let column = usize::from(column.saturating_sub(area.x + 1)) + input.horizontal_scroll(width);
+
+    input.move_cursor_to_column(0, column);
+}
+
+fn click(app: &mut App, column: u16, row: u16) {
+    let areas = app.areas.clone();
+
+    let block = [
+        (areas.method, AppBlock::Method),
+        (areas.endpoint, AppBlock::Endpoint),
+        (areas.request_tabs, AppBlock::Request),
+        (areas.request_content, AppBlock::RequestContent),
+        (areas.response_filter, AppBlock::ResponseFilter),
+        (areas.response, AppBlock::Response),
+    ]
+    .into_iter()
+    .find(|(area, _)| contains(*area, column, row))
+    .map(|(_, block)| block);
+
+    let Some(block) = block else {
+        return;
+    };
+
+    let was_editing = app.input_mode == InputMode::Insert && app.selected_block == block;
+
+    app.status_message = None;
+    app.pending_keys.clear();
+    app.input_mode = InputMode::Normal;
+    app.selected_block = block.clone();
+
+    match block {
+        AppBlock::Method => app.popup = Some(AppPopup::ChangeMethod),
+        AppBlock::Endpoint => {
+            app.input_mode = InputMode::Insert;
+            click_input(&mut app.endpoint, areas.endpoint, column);
+        }
+        AppBlock::ResponseFilter => {
+            app.input_mode = InputMode::Insert;
+            click_input(&mut app.response_filter, areas.response_filter, column);
+        }
+        AppBlock::Request => {
+            if let Some((tab, _)) = areas
+                .tab_titles
+                .iter()
+                .find(|(_, area)| contains(*area, column, row))
+            {
+                app.request_tab = tab.clone();
+            }
+        }
+        AppBlock::RequestContent => click_request_content(app, &areas, column, row, was_editing),
+        AppBlock::Response => click_response(app, &areas, row),
+    }
+}
+
+fn click_request_content(app: &mut App, areas: &Areas, column: u16, row: u16, was_editing: bool) {
+    if contains(areas.body_editor, column, row) {
+        let area = areas.body_editor;
+
+        if !was_editing {
+            app.raw_body.enter_insert_mode();
+        }
+
+        app.input_mode = InputMode::Insert;
+        app.raw_body.move_cursor_to_column(
+            app.raw_body.scroll + usize::from(row.saturating_sub(area.y + 1)),
+            usize::from(column.saturating_sub(area.x + 1)),
+        );
+
+        return;
+    }
+
+    if !contains(areas.table_rows, column, row) {
+        return;
+    }
+
+    let index = areas.table_offset + usize::from(row - areas.table_rows.y);
+
+    let (selected, count) = match app.request_tab {
+        RequestTab::Headers => (&mut app.selected_header, app.headers.len()),
+        RequestTab::Query => (&mut app.selected_query_param, app.query_params.len()),
+        RequestTab::Body => (&mut app.selected_form_field, app.body_form.len()),
+        _ => return,
+    };
+
+    if index < count {
+        *selected = u16::try_from(index).unwrap_or(u16::MAX);
+    }
+}
+
+fn click_response(app: &mut App, areas: &Areas, row: u16) {
+    if app.response.as_ref().is_some_and(|r| r.is_binary) || app.response_view != ResponseView::Tree
+    {
+        return;
+    }
+
+    if let Some(tree) = app.response_tree.as_mut() {
+        let index = tree.offset + usize::from(row.saturating_sub(areas.response.y + 1));
+
+        if index < tree.visible.len() {
+            tree.selected = index;
+        }
+    }
+}
+
+fn click_popup(app: &mut App, column: u16, row: u16) {
+    let Some(index) = app
+        .areas
+        .popup_items
+        .iter()
+        .position(|area| contains(*area, column, row))
+    else {
+        return;
+    };
+
+    let area = app.areas.popup_items[index];
+
+    match app.popup.as_mut() {
+        Some(AppPopup::ChangeMethod) => {
+            if let Some(method) = app.method.get_order().get(index) {
+                app.method = method.clone();
+                app.popup = None;
+            }
+        }
+        Some(AppPopup::FormPopup(form)) => {
+            if let Some(field) = form.fields.get_mut(index) {
+                if app.input_mode == InputMode::Normal || form.selected_field != index {
+                    field.input.enter_insert_mode();
+                }
+
+                click_input(&mut field.input, area, column);
+
+                form.selected_field = index;
+                app.input_mode = InputMode::Insert;
+            }
+        }
+        _ => {}
+    }
+}
+
+fn scroll(app: &mut App, column: u16, row: u16, down: bool) {
+    if contains(app.areas.body_editor, column, row) {
+        let height = usize::from(app.areas.body_editor.height.saturating_sub(2));
+        let lines = SCROLL_LINES as isize;
+
+        app.raw_body
+            .scroll_by(if down { lines } else { -lines }, height);
+    } else if contains(app.areas.response, column, row) {
+        scroll_response(app, down);
+    }
+}
+
+fn scroll_response(app: &mut App, down: bool) {
+    match app.response.as_ref() {
+        Some(response) if response.is_binary => match down {
+            true => app
+                .response_hex
+                .scroll_down(SCROLL_LINES, response.body.len()),
+            false => app.response_hex.scroll_up(SCROLL_LINES),
+        },
+        Some(_) if app.response_view == ResponseView::Tree && app.response_tree.is_some() => {
+            if let Some(tree) = app.response_tree.as_mut() {
+                for _ in 0..SCROLL_LINES {
+                    match down {
+                        true => tree.next(),
+                        false => tree.previous(),
+                    }
+                }
+            }
+        }
+        Some(_) => {
+            let lines = SCROLL_LINES as u16;
+
+            app.response_scroll.0 = match down {
+                true => app.response_scroll.0.saturating_add(lines),
+                false => app.response_scroll.0.saturating_sub(lines),
+            };
+        }
+        None => {}
+    }
+}
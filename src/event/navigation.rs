@@ -10,16 +10,6 @@ pub fn scroll_down_response(app: &mut App) {
 
 pub fn scroll_up_response(app: &mut App) {
     if let AppBlock::Response = app.selected_block {
-        let x = if app.response_scroll.0 == 0 {
-            0
-        } else {
-            if app.response_scroll.0 > 2 {
-                app.response_scroll.0 - 2
-            } else {
-                0
-            }
-        };
-
-        app.response_scroll.0 = x;
+        app.response_scroll.0 = app.response_scroll.0.saturating_sub(2);
     }
 }
@@ -1,103 +1,228 @@
 use crate::app::{
     form::{Form, FormKind},
-    App, AppPopup, InputMode, Navigation, OrderNavigation,
+    hex::parse_offset,
+    App, AppPopup, InputMode, Navigation, OrderNavigation, SaveTarget,
 };
-use crossterm::event::{KeyCode, KeyEvent};
+use crate::{
+    config::keymap::Action,
+    event::{collection, dispatch_request, run_action},
+    external::expand_home,
+    har, plural,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
-use std::collections::HashMap;
+use std::{collections::HashMap, path::Path};
 
-pub fn handle_popup_events(app: &mut App, key: KeyEvent) {
+pub async fn handle_popup_action(app: &mut App, action: Action) {
     match app.popup.as_mut().unwrap() {
-        AppPopup::FormPopup(f) => match app.input_mode {
-            InputMode::Normal => match key.code {
-                KeyCode::Char('i') => {
-                    app.input_mode = InputMode::Insert;
-
-                    f.fields
-                        .get_mut(f.selected_field as usize)
-                        .unwrap()
-                        .input
-                        .move_cursor_to_end_single_line();
-                }
-                KeyCode::Char('j') => f.next(),
-                KeyCode::Char('k') => f.previous(),
-                KeyCode::Enter => {
-                    handle_forms(f.clone(), app);
-                    app.popup = None;
-                }
-                _ => {}
-            },
-            InputMode::Insert => match key.code {
-                KeyCode::Esc => {
-                    app.input_mode = InputMode::Normal;
-                }
-                KeyCode::Enter => {
-                    handle_forms(f.clone(), app);
-                    app.input_mode = InputMode::Normal;
-                    app.popup = None;
-                }
-                KeyCode::BackTab => {
-                    f.previous();
-
-                    f.fields
-                        .get_mut(f.selected_field as usize)
-                        .unwrap()
-                        .input
-                        .move_cursor_to_end_single_line()
-                }
-                KeyCode::Tab => {
-                    f.next();
-                    f.fields
-                        .get_mut(f.selected_field as usize)
-                        .unwrap()
-                        .input
-                        .move_cursor_to_end_single_line()
-                }
-                KeyCode::Left => {
-                    f.fields
-                        .get_mut(f.selected_field as usize)
-                        .unwrap()
-                        .input
-                        .move_cursor_left();
-                }
-                KeyCode::Right => {
-                    f.fields
-                        .get_mut(f.selected_field as usize)
-                        .unwrap()
-                        .input
-                        .move_cursor_right();
-                }
-                KeyCode::Backspace => {
-                    f.fields
-                        .get_mut(f.selected_field as usize)
-                        .unwrap()
-                        .input
-                        .remove_char_before_cursor_single_line();
-                }
-                KeyCode::Char(c) => {
-                    f.fields
-                        .get_mut(f.selected_field as usize)
-                        .unwrap()
-                        .input
-                        .add_char_at_cursor(c);
-                }
-                _ => {}
-            },
+        AppPopup::FormPopup(f) => match action {
+            Action::Insert => {
+                app.input_mode = InputMode::Insert;
+
+                let input = &mut f.fields.get_mut(f.selected_field).unwrap().input;
+
+                input.enter_insert_mode();
+                input.move_cursor_to_end_single_line();
+            }
+            Action::Down => f.next(),
+            Action::Up => f.previous(),
+            Action::Select => {
+                let form = f.clone();
+
+                app.popup = None;
+                handle_forms(form, app);
+            }
+            Action::Cancel => app.popup = None,
+            _ => {}
+        },
+
+        AppPopup::ChangeMethod => match action {
+            Action::Up => app.method = app.method.previous(),
+            Action::Down => app.method = app.method.next(),
+            Action::Select | Action::Cancel => app.popup = None,
+            _ => {}
         },
 
-        AppPopup::ChangeMethod => match key.code {
-            KeyCode::Char('k') => app.method = app.method.previous(),
-            KeyCode::Char('j') => app.method = app.method.next(),
-            KeyCode::Enter => {
+        AppPopup::ConfirmSend(_) => match action {
+            Action::Confirm | Action::Select => {
                 app.popup = None;
+                dispatch_request(app).await;
+            }
+            Action::Cancel => app.popup = None,
+            _ => {}
+        },
+
+        AppPopup::ConfirmOverwrite(target, path) => match action {
+            Action::Confirm | Action::Select => {
+                let target = *target;
+                let path = path.clone();
+
+                app.popup = None;
+                save(app, target, &path);
+            }
+            Action::Cancel => app.popup = None,
+            _ => {}
+        },
+
+        AppPopup::Help(help) => match action {
+            Action::Down => help.scroll_down(),
+            Action::Up => help.scroll_up(),
+            Action::Select | Action::Cancel | Action::Help => {
+                app.popup = help.previous.take().map(|previous| *previous);
             }
-            KeyCode::Esc => app.popup = None,
             _ => {}
         },
-        _ => {}
+
+        AppPopup::Diff(diff) => match action {
+            Action::Down => diff.scroll_down(),
+            Action::Up => diff.scroll_up(),
+            Action::ToggleSideBySide => diff.toggle_side_by_side(),
+            Action::Select | Action::Cancel => app.popup = None,
+            _ => {}
+        },
+
+        // Typed in insert mode, see `handle_palette_input` and
+        // `handle_picker_input`
+        AppPopup::CommandPalette(_) | AppPopup::Picker(_) => {}
+    }
+}
+
+/// Filter the command palette as it's typed and run the action picked
+pub async fn handle_palette_input(app: &mut App, key: KeyEvent) {
+    let Some(AppPopup::CommandPalette(palette)) = app.popup.as_mut() else {
+        return;
+    };
+
+    let control = key.modifiers.contains(KeyModifiers::CONTROL);
+
+    match key.code {
+        KeyCode::Esc => {
+            app.popup = None;
+            app.input_mode = InputMode::Normal;
+        }
+        KeyCode::Enter => {
+            let action = palette.selected_action();
+
+            app.popup = None;
+            app.input_mode = InputMode::Normal;
+
+            if let Some(action) = action {
+                run_action(app, action).await;
+            }
+        }
+        KeyCode::Down | KeyCode::Tab => palette.next(),
+        KeyCode::Up | KeyCode::BackTab => palette.previous(),
+        KeyCode::Char('n') if control => palette.next(),
+        KeyCode::Char('p') if control => palette.previous(),
+        _ => {
+            if palette.input.handle_key(key, false) {
+                palette.selected = 0;
+            }
+        }
+    }
+}
+
+/// Filter a picker as it's typed and act on the item picked
+pub fn handle_picker_input(app: &mut App, key: KeyEvent) {
+    let Some(AppPopup::Picker(picker)) = app.popup.as_mut() else {
+        return;
+    };
+
+    let control = key.modifiers.contains(KeyModifiers::CONTROL);
+
+    match key.code {
+        KeyCode::Esc => {
+            app.popup = None;
+            app.input_mode = InputMode::Normal;
+        }
+        KeyCode::Enter => {
+            let kind = picker.kind;
+            let item = picker.selected_item();
+
+            app.popup = None;
+            app.input_mode = InputMode::Normal;
+
+            if let Some(item) = item {
+                collection::pick(app, kind, item);
+            }
+        }
+        KeyCode::Down | KeyCode::Tab => picker.next(),
+        KeyCode::Up | KeyCode::BackTab => picker.previous(),
+        KeyCode::Char('n') if control => picker.next(),
+        KeyCode::Char('p') if control => picker.previous(),
+        _ => {
+            if picker.input.handle_key(key, false) {
+                picker.selected = 0;
+            }
+        }
+    }
+}
+
+/// Edit the selected field of a form popup
+pub fn handle_form_input(app: &mut App, key: KeyEvent) {
+    let Some(AppPopup::FormPopup(f)) = app.popup.as_mut() else {
+        return;
+    };
+
+    match key.code {
+        KeyCode::Enter => {
+            let form = f.clone();
+
+            app.input_mode = InputMode::Normal;
+            app.popup = None;
+            handle_forms(form, app);
+        }
+        KeyCode::BackTab | KeyCode::Tab => {
+            if key.code == KeyCode::Tab {
+                f.next();
+            } else {
+                f.previous();
+            }
+
+            let input = &mut f.fields.get_mut(f.selected_field).unwrap().input;
+
+            input.enter_insert_mode();
+            input.move_cursor_to_end_single_line();
+        }
+        _ => {
+            let input = &mut f.fields.get_mut(f.selected_field).unwrap().input;
+
+            if !input.handle_modal_key(key, false) && key.code == KeyCode::Esc {
+                app.input_mode = InputMode::Normal;
+            }
+        }
     }
 }
 
+fn save_response(app: &mut App, path: &Path) {
+    if let Some(response) = app.response.as_ref() {
+        app.status_message = Some(match std::fs::write(path, &response.body) {
+            Ok(_) => format!("Saved {} bytes to {}", response.body.len(), path.display()),
+            Err(e) => format!("Could not save response: {e}"),
+        });
+    }
+}
+
+fn export_history(app: &mut App, path: &Path) {
+    app.status_message = Some(match har::save(path, &app.history) {
+        Ok(_) => format!(
+            "Exported {} to {}",
+            plural::count(app.history.len(), "request"),
+            path.display()
+        ),
+        Err(e) => format!("Could not export the history: {e}"),
+    });
+}
+
+fn save(app: &mut App, target: SaveTarget, path: &Path) {
+    match target {
+        SaveTarget::Response => save_response(app, path),
+        SaveTarget::History => export_history(app, path),
+    }
+}
+
+/// Act on a submitted form, after its popup is closed as it may open
+/// another one
 fn handle_forms(form: Form, app: &mut App) {
     let values = form
         .fields
@@ -160,5 +285,34 @@ fn handle_forms(form: Form, app: &mut App) {
                 app.body_form.insert(key, value);
             }
         }
+
+        FormKind::SaveResponse => {
+            let path = expand_home(values.get("path").unwrap().trim());
+
+            match path.exists() {
+                true => app.popup = Some(AppPopup::ConfirmOverwrite(SaveTarget::Response, path)),
+                false => save_response(app, &path),
+            }
+        }
+
+        FormKind::ExportHar => {
+            let path = expand_home(values.get("path").unwrap().trim());
+
+            match path.exists() {
+                true => app.popup = Some(AppPopup::ConfirmOverwrite(SaveTarget::History, path)),
+                false => export_history(app, &path),
+            }
+        }
+
+        FormKind::SaveExample => collection::add_example(app, values.get("name").unwrap()),
+
+        FormKind::GoToOffset => {
+            let len = app.response.as_ref().map(|r| r.body.len()).unwrap_or(0);
+
+            match parse_offset(values.get("offset").unwrap()) {
+                Some(offset) => app.response_hex.go_to(offset, len),
+                None => app.status_message = Some("Invalid offset".to_string()),
+            }
+        }
     }
 }
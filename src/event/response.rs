@@ -0,0 +1,210 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{
+    app::{
+        form::{Form, FormField, FormKind},
+        search::Search,
+        App, AppPopup, InputMode, ResponseView,
+    },
+    clipboard,
+    config::keymap::Action,
+    external,
+};
+
+/// Handle the actions specific to the Response block, returns `true` when
+/// the action was consumed
+pub fn handle_response_action(app: &mut App, action: Action) -> bool {
+    match action {
+        Action::ToggleView => toggle_view(app),
+        Action::SaveResponse => open_save_form(app),
+        Action::OpenInPager => open_in_pager(app),
+        Action::TogglePretty => {
+            app.response_pretty = !app.response_pretty;
+            app.response_scroll = (0, 0);
+            app.status_message = Some(
+                if app.response_pretty {
+                    "Showing pretty response"
+                } else {
+                    "Showing raw response"
+                }
+                .to_string(),
+            );
+        }
+        _ if app.response.as_ref().is_some_and(|r| r.is_binary) => {
+            return handle_hex_action(app, action)
+        }
+        _ if app.response_view == ResponseView::Tree => return handle_tree_action(app, action),
+        Action::Search => {
+            app.response_search = Search {
+                origin: app.response_scroll.0,
+                ..Search::default()
+            };
+            app.input_mode = InputMode::Insert;
+        }
+        Action::NextMatch => {
+            let matches = app.response_search_matches().unwrap_or_default();
+
+            if let Some(line) = app.response_search.focus_next(&matches) {
+                app.response_scroll.0 = line;
+            }
+        }
+        Action::PreviousMatch => {
+            let matches = app.response_search_matches().unwrap_or_default();
+
+            if let Some(line) = app.response_search.focus_previous(&matches) {
+                app.response_scroll.0 = line;
+            }
+        }
+        _ => return false,
+    }
+
+    true
+}
+
+/// Edit the search query, jumping to the first match as it's typed
+pub fn handle_search_input(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter => {
+            app.input_mode = InputMode::Normal;
+        }
+        KeyCode::Esc => {
+            app.response_scroll.0 = app.response_search.origin;
+            app.response_search = Search::default();
+            app.input_mode = InputMode::Normal;
+        }
+        _ => {
+            if app.response_search.input.handle_key(key, false) {
+                focus_first_match(app);
+            }
+        }
+    }
+}
+
+/// Scroll to the first match after the position the search started from
+pub fn focus_first_match(app: &mut App) {
+    let matches = app.response_search_matches().unwrap_or_default();
+
+    app.response_scroll.0 = app
+        .response_search
+        .focus_from_origin(&matches)
+        .unwrap_or(app.response_search.origin);
+}
+
+fn open_save_form(app: &mut App) {
+    let Some(response) = app.response.as_ref() else {
+        return;
+    };
+
+    let path_input = FormField::new("Path", "path").value(&response.suggested_filename());
+
+    let form = Form::new(FormKind::SaveResponse, vec![path_input]).title("Save Response");
+
+    app.popup = Some(AppPopup::FormPopup(form));
+}
+
+/// Write the raw body to a temp file and open it with `$PAGER` or `$EDITOR`
+fn open_in_pager(app: &mut App) {
+    let Some(response) = app.response.as_ref() else {
+        return;
+    };
+
+    let path = external::temp_path("response", response.extension());
+
+    if let Err(e) = std::fs::write(&path, &response.body) {
+        app.status_message = Some(format!("Could not write {}: {e}", path.display()));
+        return;
+    }
+
+    let command = external::command_from_env(&["PAGER", "EDITOR"], "less");
+
+    if let Err(e) = external::run_suspended(&command, &path) {
+        app.status_message = Some(format!("Could not run {command}: {e}"));
+    }
+
+    let _ = std::fs::remove_file(&path);
+
+    app.needs_redraw = true;
+}
+
+fn toggle_view(app: &mut App) {
+    match app.response_view {
+        ResponseView::Text => {
+            app.response_view = ResponseView::Tree;
+
+            if app.response_tree.is_none() {
+                app.build_response_tree();
+            }
+
+            if app.response_tree.is_none() {
+                app.status_message =
+                    Some("Tree view is only available for JSON responses".to_string());
+            }
+        }
+        ResponseView::Tree => app.response_view = ResponseView::Text,
+    }
+}
+
+fn handle_hex_action(app: &mut App, action: Action) -> bool {
+    let len = app.response.as_ref().map(|r| r.body.len()).unwrap_or(0);
+    let hex = &mut app.response_hex;
+
+    match action {
+        Action::Down => hex.scroll_down(1, len),
+        Action::Up => hex.scroll_up(1),
+        Action::PageDown => hex.scroll_down(hex.page_rows, len),
+        Action::PageUp => hex.scroll_up(hex.page_rows),
+        Action::Top => hex.first(),
+        Action::Bottom => hex.last(len),
+        Action::GoToOffset => {
+            let offset_input = FormField::new("Offset (decimal or 0x hex)", "offset")
+                .value(&format!("0x{:x}", hex.offset));
+
+            let form = Form::new(FormKind::GoToOffset, vec![offset_input]).title("Go to Offset");
+
+            app.popup = Some(AppPopup::FormPopup(form));
+        }
+        Action::Search => {
+            app.status_message = Some("Search is not available for binary responses".to_string());
+        }
+        _ => return false,
+    }
+
+    true
+}
+
+fn handle_tree_action(app: &mut App, action: Action) -> bool {
+    let Some(tree) = app.response_tree.as_mut() else {
+        return false;
+    };
+
+    match action {
+        Action::Down => tree.next(),
+        Action::Up => tree.previous(),
+        Action::Collapse => tree.collapse(),
+        Action::Expand => tree.expand(),
+        Action::ToggleNode => tree.toggle(),
+        Action::Top => tree.first(),
+        Action::Bottom => tree.last(),
+        Action::CollapseAll => tree.collapse_all(),
+        Action::ExpandAll => tree.expand_all(),
+        Action::CopyValue => {
+            let value = tree.value(tree.selected_node());
+
+            app.status_message = Some(match clipboard::copy(&value) {
+                Ok(_) => "Copied value to clipboard".to_string(),
+                Err(e) => format!("Could not copy value: {e}"),
+            });
+        }
+        Action::CopyPath => {
+            let path = tree.path(tree.selected_node());
+
+            app.status_message = Some(match clipboard::copy(&path) {
+                Ok(_) => format!("Copied {path} to clipboard"),
+                Err(e) => format!("Could not copy path: {e}"),
+            });
+        }
+        _ => return false,
+    }
+
+    true
+}
@@ -0,0 +1,71 @@
+use std::{
+    env,
+    io::{self, Error, ErrorKind},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crossterm::{
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+/// The first of the given environment variables that is set, e.g.
+/// `["PAGER", "EDITOR"]`
+pub fn command_from_env(variables: &[&str], fallback: &str) -> String {
+    variables
+        .iter()
+        .filter_map(|variable| env::var(variable).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+/// A path in the temp dir unique to this process
+pub fn temp_path(name: &str, extension: &str) -> PathBuf {
+    env::temp_dir().join(format!("tuapi-{}-{name}.{extension}", std::process::id()))
+}
+
+/// Expand a leading `~` to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), env::var("HOME")) {
+        (Some(rest), Ok(home)) if rest.is_empty() || rest.starts_with('/') => {
+            PathBuf::from(format!("{home}{rest}"))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Leave the TUI, run `command` on `path` until it exits and come back.
+///
+/// The screen must be fully redrawn afterwards.
+pub fn run_suspended(command: &str, path: &Path) -> Result<(), Error> {
+    let mut parts = command.split_whitespace();
+
+    let program = parts
+        .next()
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "empty command"))?;
+
+    disable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        LeaveAlternateScreen
+    )?;
+
+    let status = Command::new(program).args(parts).arg(path).status();
+
+    enable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        EnterAlternateScreen,
+        EnableBracketedPaste,
+        EnableMouseCapture
+    )?;
+
+    match status? {
+        s if s.success() => Ok(()),
+        s => Err(Error::other(format!("{program} exited with {s}"))),
+    }
+}
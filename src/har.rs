@@ -0,0 +1,447 @@
+use std::{fs, io, path::Path, time::Duration};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use url::{form_urlencoded, Url};
+
+use crate::{
+    app::{
+        history::{Exchange, Timings},
+        Response,
+    },
+    collection::{Body, KeyValue, SavedRequest},
+    request,
+};
+
+/// Request headers set by the client itself, left out when importing
+const CLIENT_HEADERS: [&str; 4] = ["host", "content-length", "connection", "accept-encoding"];
+
+/// Whether a file is opened as a HAR capture instead of a URL
+pub fn is_har_file(path: &Path) -> bool {
+    path.is_file() && path.extension().is_some_and(|extension| extension == "har")
+}
+
+#[derive(Serialize, Deserialize)]
+struct Har {
+    log: Log,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Log {
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    creator: Creator,
+    entries: Vec<Entry>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Creator {
+    name: String,
+    version: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Entry {
+    started_date_time: DateTime<Utc>,
+    /// Milliseconds
+    #[serde(default)]
+    time: f64,
+    request: HarRequest,
+    response: HarResponse,
+    #[serde(default)]
+    cache: serde_json::Map<String, serde_json::Value>,
+    #[serde(default)]
+    timings: HarTimings,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NameValue {
+    name: String,
+    #[serde(default)]
+    value: String,
+}
+
+impl NameValue {
+    fn list(values: &[KeyValue]) -> Vec<Self> {
+        values
+            .iter()
+            .filter(|value| value.enabled)
+            .map(|value| Self {
+                name: value.name.clone(),
+                value: value.value.clone(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarRequest {
+    method: String,
+    url: String,
+    #[serde(default)]
+    http_version: String,
+    #[serde(default)]
+    cookies: Vec<serde_json::Value>,
+    #[serde(default)]
+    headers: Vec<NameValue>,
+    #[serde(default)]
+    query_string: Vec<NameValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    post_data: Option<PostData>,
+    #[serde(default = "unknown_size")]
+    headers_size: i64,
+    #[serde(default = "unknown_size")]
+    body_size: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PostData {
+    #[serde(default)]
+    mime_type: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    params: Vec<Param>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Param {
+    name: String,
+    #[serde(default)]
+    value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    file_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarResponse {
+    status: u16,
+    #[serde(default)]
+    status_text: String,
+    #[serde(default)]
+    http_version: String,
+    #[serde(default)]
+    cookies: Vec<serde_json::Value>,
+    #[serde(default)]
+    headers: Vec<NameValue>,
+    #[serde(default)]
+    content: Content,
+    #[serde(default, rename = "redirectURL")]
+    redirect_url: String,
+    #[serde(default = "unknown_size")]
+    headers_size: i64,
+    #[serde(default = "unknown_size")]
+    body_size: i64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct Content {
+    #[serde(default)]
+    size: i64,
+    #[serde(default)]
+    mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encoding: Option<String>,
+}
+
+/// Milliseconds
+#[derive(Serialize, Deserialize, Default)]
+struct HarTimings {
+    send: f64,
+    wait: f64,
+    receive: f64,
+}
+
+fn unknown_size() -> i64 {
+    -1
+}
+
+fn milliseconds(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn duration(milliseconds: f64) -> Duration {
+    Duration::from_secs_f64(milliseconds.max(0.0) / 1000.0)
+}
+
+/// The URL of a request with its query parameters
+fn full_url(request: &SavedRequest) -> String {
+    let query: Vec<&KeyValue> = request.query.iter().filter(|value| value.enabled).collect();
+
+    if query.is_empty() {
+        return request.url.clone();
+    }
+
+    match Url::parse(&request.url) {
+        Ok(mut url) => {
+            url.query_pairs_mut().extend_pairs(
+                query
+                    .iter()
+                    .map(|value| (value.name.as_str(), value.value.as_str())),
+            );
+
+            url.to_string()
+        }
+        Err(_) => request.url.clone(),
+    }
+}
+
+fn post_data(body: &Body) -> PostData {
+    match body {
+        Body::Raw { content_type, text } => PostData {
+            mime_type: content_type.clone().unwrap_or_default(),
+            params: Vec::new(),
+            text: Some(text.clone()),
+        },
+        Body::Form { fields } | Body::Multipart { fields } => PostData {
+            mime_type: "application/x-www-form-urlencoded".to_string(),
+            params: NameValue::list(fields)
+                .into_iter()
+                .map(|field| Param {
+                    name: field.name,
+                    value: field.value,
+                    file_name: None,
+                })
+                .collect(),
+            text: Some(
+                form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(
+                        fields
+                            .iter()
+                            .filter(|field| field.enabled)
+                            .map(|field| (&field.name, &field.value)),
+                    )
+                    .finish(),
+            ),
+        },
+    }
+}
+
+fn entry(exchange: &Exchange) -> Entry {
+    let request = &exchange.request;
+    let response = &exchange.response;
+
+    let url = full_url(request);
+
+    let query_string = Url::parse(&url)
+        .map(|url| {
+            url.query_pairs()
+                .map(|(name, value)| NameValue {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let post_data = request.body.as_ref().map(post_data);
+
+    let body_size = post_data
+        .as_ref()
+        .and_then(|data| data.text.as_ref())
+        .map_or(0, |text| text.len() as i64);
+
+    let content = match response.is_binary {
+        true => Content {
+            size: response.body.len() as i64,
+            mime_type: response.content_type.clone(),
+            text: Some(STANDARD.encode(&response.body)),
+            encoding: Some("base64".to_string()),
+        },
+        false => Content {
+            size: response.body.len() as i64,
+            mime_type: response.content_type.clone(),
+            text: Some(response.text.clone()),
+            encoding: None,
+        },
+    };
+
+    Entry {
+        started_date_time: exchange.started,
+        time: milliseconds(exchange.timings.total()),
+        request: HarRequest {
+            method: request.method.clone(),
+            url,
+            http_version: response.http_version.clone(),
+            cookies: Vec::new(),
+            headers: NameValue::list(&request.headers),
+            query_string,
+            post_data,
+            headers_size: -1,
+            body_size,
+        },
+        response: HarResponse {
+            status: response.status_code,
+            status_text: StatusCode::from_u16(response.status_code)
+                .ok()
+                .and_then(|status| status.canonical_reason())
+                .unwrap_or_default()
+                .to_string(),
+            http_version: response.http_version.clone(),
+            cookies: Vec::new(),
+            headers: response
+                .headers
+                .iter()
+                .map(|(name, value)| NameValue {
+                    name: name.clone(),
+                    value: value.clone(),
+                })
+                .collect(),
+            redirect_url: response.header("location").unwrap_or_default().to_string(),
+            content,
+            headers_size: -1,
+            body_size: response.body.len() as i64,
+        },
+        cache: serde_json::Map::new(),
+        timings: HarTimings {
+            send: 0.0,
+            wait: milliseconds(exchange.timings.wait),
+            receive: milliseconds(exchange.timings.receive),
+        },
+    }
+}
+
+/// Write exchanges as a HAR 1.2 file
+pub fn save(path: &Path, exchanges: &[Exchange]) -> io::Result<()> {
+    let har = Har {
+        log: Log {
+            version: "1.2".to_string(),
+            creator: Creator {
+                name: env!("CARGO_PKG_NAME").to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            entries: exchanges.iter().map(entry).collect(),
+        },
+    };
+
+    let text = serde_json::to_string_pretty(&har).map_err(io::Error::other)?;
+
+    fs::write(path, text)
+}
+
+fn saved_request(request: &HarRequest) -> SavedRequest {
+    let headers: Vec<KeyValue> = request
+        .headers
+        .iter()
+        .filter(|header| {
+            !header.name.starts_with(':')
+                && !CLIENT_HEADERS.contains(&header.name.to_lowercase().as_str())
+        })
+        .map(|header| KeyValue::new(&header.name, &header.value))
+        .collect();
+
+    let body = request.post_data.as_ref().map(|data| {
+        let fields = || {
+            data.params
+                .iter()
+                .map(|param| KeyValue::new(&param.name, &param.value))
+                .collect()
+        };
+
+        let mime_type = data.mime_type.to_lowercase();
+
+        match data.params.is_empty() {
+            false if mime_type.starts_with("multipart/") => Body::Multipart { fields: fields() },
+            false => Body::Form { fields: fields() },
+            true => Body::Raw {
+                content_type: Some(data.mime_type.clone()).filter(|mime| !mime.is_empty()),
+                text: data.text.clone().unwrap_or_default(),
+            },
+        }
+    });
+
+    let url = Url::parse(&request.url);
+
+    let name = match &url {
+        Ok(url) => format!("{} {}", request.method, url.path()),
+        Err(_) => format!("{} {}", request.method, request.url),
+    };
+
+    SavedRequest {
+        name,
+        method: request.method.to_uppercase(),
+        url: request.url.clone(),
+        description: None,
+        headers,
+        query: Vec::new(),
+        body,
+        auth: None,
+        operation: None,
+        examples: Vec::new(),
+    }
+}
+
+fn response(entry: &Entry) -> Result<Response, String> {
+    let har = &entry.response;
+
+    let body = match (&har.content.text, har.content.encoding.as_deref()) {
+        (Some(text), Some("base64")) => STANDARD
+            .decode(text)
+            .map_err(|e| format!("{} {}: {e}", entry.request.method, entry.request.url))?,
+        (Some(text), _) => text.clone().into_bytes(),
+        (None, _) => Vec::new(),
+    };
+
+    let headers: Vec<(String, String)> = har
+        .headers
+        .iter()
+        .map(|header| (header.name.clone(), header.value.clone()))
+        .collect();
+
+    let content_type = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        .map(|(_, value)| value.clone())
+        .unwrap_or_else(|| har.content.mime_type.clone())
+        .to_lowercase();
+
+    let is_binary = request::is_binary(&content_type, &body);
+
+    Ok(Response {
+        status_code: har.status,
+        text: match is_binary {
+            true => String::new(),
+            false => String::from_utf8_lossy(&body).to_string(),
+        },
+        content_type,
+        headers,
+        body,
+        url: entry.request.url.clone(),
+        http_version: har.http_version.clone(),
+        is_binary,
+    })
+}
+
+/// The exchanges recorded in a HAR file
+pub fn load(path: &Path) -> Result<Vec<Exchange>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+
+    let har: Har = serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?;
+
+    har.log
+        .entries
+        .iter()
+        .map(|entry| {
+            Ok(Exchange {
+                request: saved_request(&entry.request),
+                response: response(entry)?,
+                started: entry.started_date_time,
+                timings: Timings {
+                    wait: duration(entry.timings.wait),
+                    receive: duration(entry.timings.receive),
+                },
+            })
+        })
+        .collect()
+}
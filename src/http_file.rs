@@ -0,0 +1,565 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use url::form_urlencoded;
+
+use crate::collection::{substitute, Body, KeyValue, SavedRequest};
+
+const METHODS: [&str; 9] = [
+    "GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS", "TRACE", "CONNECT",
+];
+
+/// Whether a file is opened as requests instead of a URL
+pub fn is_http_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .is_some_and(|extension| extension == "http" || extension == "rest")
+}
+
+fn is_comment(line: &str) -> bool {
+    let line = line.trim_start();
+
+    line.starts_with('#') || line.starts_with("//")
+}
+
+/// `@name = value`
+fn variable(line: &str) -> Option<(String, String)> {
+    let (name, value) = line.trim().strip_prefix('@')?.split_once('=')?;
+
+    Some((name.trim().to_string(), value.trim().to_string()))
+}
+
+/// `# @name value` or `// @name value`
+fn name_directive(line: &str) -> Option<String> {
+    let line = line.trim_start();
+    let rest = line
+        .strip_prefix('#')
+        .or_else(|| line.strip_prefix("//"))?
+        .trim_start();
+
+    let name = rest.strip_prefix("@name")?.trim();
+
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Url-encoded, except for the `{{variables}}` the client substitutes
+fn encode(text: &str) -> String {
+    let mut encoded = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}").map(|end| start + end + 2) else {
+            break;
+        };
+
+        encoded.extend(form_urlencoded::byte_serialize(&rest.as_bytes()[..start]));
+        encoded.push_str(&rest[start..end]);
+        rest = &rest[end..];
+    }
+
+    encoded.extend(form_urlencoded::byte_serialize(rest.as_bytes()));
+    encoded
+}
+
+/// `name=value` pairs joined with `&`, as a query or a form body
+pub fn encode_pairs<'a>(pairs: impl IntoIterator<Item = (&'a String, &'a String)>) -> String {
+    pairs
+        .into_iter()
+        .map(|(name, value)| format!("{}={}", encode(name), encode(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Lines ending the body: response handlers and references
+fn ends_body(line: &str) -> bool {
+    line.starts_with("> ") || line.starts_with(">>") || line.starts_with("<> ")
+}
+
+/// A request of the file, with where its parts are
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpRequest {
+    pub name: String,
+    pub method: String,
+    /// The URL with its continuation lines joined
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// Written as just the URL, meaning GET
+    implicit_method: bool,
+    /// `HTTP/1.1` after the URL
+    version: Option<String>,
+    /// The request line and its query continuation lines
+    request_lines: Range<usize>,
+    /// Header lines, comments included
+    header_lines: Range<usize>,
+    /// The blank line and the body, empty without a body
+    body_lines: Range<usize>,
+}
+
+impl HttpRequest {
+    pub fn to_saved(&self) -> SavedRequest {
+        let content_type = self
+            .headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+            .map(|(_, value)| value.clone());
+
+        SavedRequest {
+            name: self.name.clone(),
+            method: self.method.clone(),
+            url: self.url.clone(),
+            description: None,
+            headers: self
+                .headers
+                .iter()
+                .map(|(name, value)| KeyValue::new(name, value))
+                .collect(),
+            query: Vec::new(),
+            body: (!self.body.is_empty()).then(|| Body::Raw {
+                content_type,
+                text: self.body.clone(),
+            }),
+            auth: None,
+            operation: None,
+            examples: Vec::new(),
+        }
+    }
+}
+
+/// A VS Code REST Client or JetBrains HTTP client file
+pub struct HttpFile {
+    pub path: PathBuf,
+    lines: Vec<String>,
+    crlf: bool,
+    pub requests: Vec<HttpRequest>,
+    /// `@name = value` definitions in the order of the file
+    definitions: Vec<(String, String)>,
+}
+
+impl HttpFile {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+
+        Ok(Self::parse(path, &text))
+    }
+
+    pub fn parse(path: &Path, text: &str) -> Self {
+        let crlf = text.contains("\r\n");
+
+        let lines: Vec<String> = text
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line).to_string())
+            .collect();
+
+        let mut file = Self {
+            path: path.to_path_buf(),
+            lines,
+            crlf,
+            requests: Vec::new(),
+            definitions: Vec::new(),
+        };
+
+        file.parse_lines();
+        file
+    }
+
+    fn parse_lines(&mut self) {
+        self.requests.clear();
+        self.definitions.clear();
+
+        let mut start = 0;
+
+        while start < self.lines.len() {
+            let end = (start + 1..self.lines.len())
+                .find(|index| self.lines[*index].trim_start().starts_with("###"))
+                .unwrap_or(self.lines.len());
+
+            if let Some(request) = self.parse_block(start..end) {
+                self.requests.push(request);
+            }
+
+            start = end;
+        }
+    }
+
+    /// Parse the lines between two `###` separators
+    fn parse_block(&mut self, block: Range<usize>) -> Option<HttpRequest> {
+        let mut index = block.start;
+        let mut name = None;
+
+        if let Some(title) = self.lines[index].trim_start().strip_prefix("###") {
+            name = Some(title.trim().to_string()).filter(|title| !title.is_empty());
+            index += 1;
+        }
+
+        // Comments, variables and blank lines before the request line
+        while index < block.end {
+            let line = &self.lines[index];
+
+            if let Some(definition) = variable(line) {
+                self.definitions.push(definition);
+            } else if let Some(directive) = name_directive(line) {
+                name = name.or(Some(directive));
+            } else if !line.trim().is_empty() && !is_comment(line) {
+                break;
+            }
+
+            index += 1;
+        }
+
+        if index >= block.end {
+            return None;
+        }
+
+        let request_start = index;
+        let mut parts = self.lines[index].split_whitespace().peekable();
+
+        let implicit_method = !parts
+            .peek()
+            .is_some_and(|method| METHODS.contains(&method.to_uppercase().as_str()));
+
+        let method = match implicit_method {
+            true => "GET".to_string(),
+            false => parts.next().unwrap_or_default().to_uppercase(),
+        };
+
+        let mut parts: Vec<&str> = parts.collect();
+
+        let version = parts
+            .last()
+            .filter(|part| part.starts_with("HTTP/"))
+            .map(|part| part.to_string());
+
+        if version.is_some() {
+            parts.pop();
+        }
+
+        let mut url = parts.join(" ");
+        index += 1;
+
+        // Query parameters continued on the next lines
+        while index < block.end {
+            let line = self.lines[index].trim();
+
+            if !line.starts_with('?') && !line.starts_with('&') {
+                break;
+            }
+
+            url.push_str(line);
+            index += 1;
+        }
+
+        let request_lines = request_start..index;
+        let mut headers = Vec::new();
+
+        while index < block.end && !self.lines[index].trim().is_empty() {
+            let line = &self.lines[index];
+
+            if !is_comment(line) {
+                if let Some((name, value)) = line.split_once(':') {
+                    headers.push((name.trim().to_string(), value.trim().to_string()));
+                }
+            }
+
+            index += 1;
+        }
+
+        let header_lines = request_lines.end..index;
+
+        let mut body_end = (index..block.end)
+            .find(|line| ends_body(&self.lines[*line]))
+            .unwrap_or(block.end);
+
+        while body_end > index + 1 && self.lines[body_end - 1].trim().is_empty() {
+            body_end -= 1;
+        }
+
+        let body_lines = match body_end > index + 1 {
+            true => index..body_end,
+            false => index..index,
+        };
+
+        let body = match body_lines.is_empty() {
+            true => String::new(),
+            false => self.lines[body_lines.start + 1..body_lines.end].join("\n"),
+        };
+
+        Some(HttpRequest {
+            name: name.unwrap_or_else(|| format!("{method} {url}")),
+            method,
+            url,
+            headers,
+            body,
+            implicit_method,
+            version,
+            request_lines,
+            header_lines,
+            body_lines,
+        })
+    }
+
+    /// The `@name = value` definitions, with the variables they use
+    /// replaced
+    pub fn variables(&self) -> HashMap<String, String> {
+        let mut variables: HashMap<String, String> = self.definitions.iter().cloned().collect();
+
+        // Definitions may use each other in any order
+        for _ in 0..self.definitions.len() {
+            let resolved: HashMap<String, String> = variables
+                .iter()
+                .map(|(name, value)| (name.clone(), substitute(value, &variables)))
+                .collect();
+
+            if resolved == variables {
+                break;
+            }
+
+            variables = resolved;
+        }
+
+        variables
+    }
+
+    /// Write a request back into the file, keeping the lines of the parts
+    /// that didn't change as they were
+    pub fn update(&mut self, index: usize, edited: &HttpRequest) -> io::Result<()> {
+        let Some(original) = self.requests.get(index) else {
+            return Ok(());
+        };
+
+        let mut lines = Vec::new();
+
+        if original.method == edited.method && original.url == edited.url {
+            lines.extend_from_slice(&self.lines[original.request_lines.clone()]);
+        } else {
+            let mut line = match original.implicit_method && edited.method == "GET" {
+                true => edited.url.clone(),
+                false => format!("{} {}", edited.method, edited.url),
+            };
+
+            if let Some(version) = &original.version {
+                line.push_str(&format!(" {version}"));
+            }
+
+            lines.push(line);
+        }
+
+        if original.headers == edited.headers {
+            lines.extend_from_slice(&self.lines[original.header_lines.clone()]);
+        } else {
+            lines.extend(self.header_lines(original, &edited.headers));
+        }
+
+        if original.body == edited.body {
+            lines.extend_from_slice(&self.lines[original.body_lines.clone()]);
+        } else if !edited.body.is_empty() {
+            lines.push(String::new());
+            lines.extend(edited.body.split('\n').map(str::to_string));
+        }
+
+        let replaced = original.request_lines.start..original.body_lines.end;
+
+        self.lines.splice(replaced, lines);
+        self.parse_lines();
+
+        let newline = if self.crlf { "\r\n" } else { "\n" };
+
+        fs::write(&self.path, self.lines.join(newline))
+    }
+
+    /// Header lines updated in place, without removed headers and with new
+    /// ones at the end
+    fn header_lines(&self, original: &HttpRequest, headers: &[(String, String)]) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut written = Vec::new();
+
+        for line in &self.lines[original.header_lines.clone()] {
+            if is_comment(line) {
+                lines.push(line.clone());
+                continue;
+            }
+
+            let Some((name, _)) = line.split_once(':') else {
+                continue;
+            };
+
+            let name = name.trim();
+
+            if let Some((_, value)) = headers.iter().find(|(header, _)| header == name) {
+                lines.push(format!("{name}: {value}"));
+                written.push(name.to_string());
+            }
+        }
+
+        for (name, value) in headers {
+            if !written.contains(name) {
+                lines.push(format!("{name}: {value}"));
+            }
+        }
+
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Tests run in parallel, each writes its own file
+    static FILES: AtomicUsize = AtomicUsize::new(0);
+
+    const FILE: &str = "@host = https://example.com
+@token = secret
+
+### List users
+GET {{host}}/users
+    ?page=1
+    &size=20
+Accept: application/json
+# Authorization: Bearer {{token}}
+
+### Create a user
+# @name create
+POST {{host}}/users HTTP/1.1
+Content-Type: application/json
+
+{
+  \"name\": \"Ada\"
+}
+
+> {% client.global.set(\"id\", response.body.id); %}
+
+###
+
+{{host}}/health
+";
+
+    /// The file written by `update` at a unique path, and read back
+    fn round_trip(text: &str, index: usize, edit: impl FnOnce(&mut HttpRequest)) -> String {
+        let path = std::env::temp_dir().join(format!(
+            "tuapi-test-{}-{}.http",
+            std::process::id(),
+            FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let mut file = HttpFile::parse(&path, text);
+        let mut edited = file.requests[index].clone();
+
+        edit(&mut edited);
+        file.update(index, &edited).unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        written
+    }
+
+    #[test]
+    fn parses_requests() {
+        let file = HttpFile::parse(Path::new("test.http"), FILE);
+
+        assert_eq!(file.requests.len(), 3);
+
+        let list = &file.requests[0];
+        assert_eq!(list.name, "List users");
+        assert_eq!(list.method, "GET");
+        assert_eq!(list.url, "{{host}}/users?page=1&size=20");
+        assert_eq!(
+            list.headers,
+            vec![("Accept".to_string(), "application/json".to_string())]
+        );
+
+        let create = &file.requests[1];
+        assert_eq!(create.name, "Create a user");
+        assert_eq!(create.body, "{\n  \"name\": \"Ada\"\n}");
+
+        let health = &file.requests[2];
+        assert_eq!(health.method, "GET");
+        assert_eq!(health.name, "GET {{host}}/health");
+
+        assert_eq!(file.variables()["host"], "https://example.com");
+    }
+
+    #[test]
+    fn unchanged_requests_are_written_as_they_were() {
+        for index in 0..3 {
+            assert_eq!(round_trip(FILE, index, |_| {}), FILE);
+        }
+    }
+
+    #[test]
+    fn keeps_crlf_line_endings() {
+        let text = FILE.replace('\n', "\r\n");
+
+        assert_eq!(round_trip(&text, 1, |_| {}), text);
+
+        let written = round_trip(&text, 1, |request| {
+            request.body = "{\"name\": \"Grace\"}".to_string();
+        });
+
+        assert!(written.contains("\r\n\r\n{\"name\": \"Grace\"}\r\n\r\n> {%"));
+        assert!(!written.replace("\r\n", "").contains('\n'));
+    }
+
+    #[test]
+    fn updates_only_the_parts_edited() {
+        let written = round_trip(FILE, 0, |request| {
+            request
+                .headers
+                .push(("X-Trace".to_string(), "1".to_string()));
+        });
+
+        // The continuation lines and the comment are kept
+        assert_eq!(
+            written,
+            FILE.replace(
+                "# Authorization: Bearer {{token}}\n",
+                "# Authorization: Bearer {{token}}\nX-Trace: 1\n"
+            )
+        );
+
+        let written = round_trip(FILE, 0, |request| request.method = "DELETE".to_string());
+
+        assert!(written.contains("### List users\nDELETE {{host}}/users?page=1&size=20\nAccept:"));
+
+        let written = round_trip(FILE, 1, |request| request.method = "PUT".to_string());
+
+        assert!(written.contains("# @name create\nPUT {{host}}/users HTTP/1.1\n"));
+    }
+
+    #[test]
+    fn keeps_the_response_handler() {
+        let written = round_trip(FILE, 1, |request| {
+            request.body = "{}".to_string();
+        });
+
+        assert_eq!(written, FILE.replace("{\n  \"name\": \"Ada\"\n}", "{}"),);
+    }
+
+    #[test]
+    fn keeps_implicit_get() {
+        let written = round_trip(FILE, 2, |request| {
+            request.url = "{{host}}/ready".to_string();
+        });
+
+        assert_eq!(written, FILE.replace("/health", "/ready"));
+    }
+
+    #[test]
+    fn encodes_pairs_except_variables() {
+        let name = "q".to_string();
+        let value = "a b&c=d#e {{token}}".to_string();
+
+        assert_eq!(
+            encode_pairs([(&name, &value)]),
+            "q=a+b%26c%3Dd%23e+{{token}}"
+        );
+    }
+}
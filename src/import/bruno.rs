@@ -0,0 +1,831 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use serde_json::{json, Map, Value};
+
+use crate::{
+    app::RequestMethod,
+    collection::{
+        ApiKeyLocation, Auth, Body, Collection, Environment, Folder, Item, KeyValue, SavedRequest,
+    },
+    plural::count,
+};
+
+use super::{location, read_json, replace_path_variables, text, Import};
+
+const METHODS: [&str; 9] = [
+    "get", "post", "put", "delete", "patch", "options", "head", "connect", "trace",
+];
+
+/// Blocks holding text as written instead of `key: value` lines
+const TEXT_BLOCKS: [&str; 10] = [
+    "body:json",
+    "body:text",
+    "body:xml",
+    "body:sparql",
+    "body:graphql",
+    "body:graphql:vars",
+    "script:pre-request",
+    "script:post-response",
+    "tests",
+    "docs",
+];
+
+/// Blocks of what tuapi doesn't run, with how they are reported
+const UNSUPPORTED_BLOCKS: [(&str, &str); 5] = [
+    ("script:pre-request", "pre-request script"),
+    ("script:post-response", "post-response script"),
+    ("tests", "tests"),
+    ("assert", "assertions"),
+    ("vars:post-response", "post-response variables"),
+];
+
+enum Block {
+    /// `key: value` lines, disabled when starting with `~`
+    Dict(Vec<KeyValue>),
+    Text(String),
+    /// `name [ a, b ]`
+    List(Vec<String>),
+}
+
+/// A `.bru` file, as its blocks in order
+struct Bru {
+    blocks: Vec<(String, Block)>,
+}
+
+impl Bru {
+    fn parse(text: &str) -> Self {
+        let mut blocks = Vec::new();
+        let mut lines = text.lines();
+
+        while let Some(line) = lines.next() {
+            let line = line.trim_end();
+
+            let (name, closing) = if let Some(name) = line.strip_suffix('{') {
+                (name.trim(), "}")
+            } else if let Some(name) = line.strip_suffix('[') {
+                (name.trim(), "]")
+            } else {
+                continue;
+            };
+
+            let content: Vec<&str> = lines
+                .by_ref()
+                .take_while(|line| line.trim_end() != closing)
+                .collect();
+
+            let block = if closing == "]" {
+                Block::List(
+                    content
+                        .iter()
+                        .map(|item| item.trim().trim_end_matches(',').to_string())
+                        .filter(|item| !item.is_empty())
+                        .collect(),
+                )
+            } else if TEXT_BLOCKS.contains(&name) {
+                Block::Text(
+                    content
+                        .iter()
+                        .map(|line| line.strip_prefix("  ").unwrap_or(line))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                )
+            } else {
+                Block::Dict(
+                    content
+                        .iter()
+                        .filter_map(|line| {
+                            let line = line.trim();
+                            let (line, enabled) = match line.strip_prefix('~') {
+                                Some(line) => (line, false),
+                                None => (line, true),
+                            };
+
+                            let (name, value) = line.split_once(':')?;
+
+                            Some(KeyValue {
+                                name: name.trim().to_string(),
+                                value: value.trim().to_string(),
+                                enabled,
+                            })
+                        })
+                        .collect(),
+                )
+            };
+
+            blocks.push((name.to_string(), block));
+        }
+
+        Self { blocks }
+    }
+
+    fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+
+        Ok(Self::parse(&text))
+    }
+
+    fn block(&self, name: &str) -> Option<&Block> {
+        self.blocks
+            .iter()
+            .find(|(block, _)| block == name)
+            .map(|(_, block)| block)
+    }
+
+    fn dict(&self, name: &str) -> Vec<KeyValue> {
+        match self.block(name) {
+            Some(Block::Dict(values)) => values.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn text(&self, name: &str) -> Option<String> {
+        match self.block(name) {
+            Some(Block::Text(text)) if !text.trim().is_empty() => Some(text.clone()),
+            _ => None,
+        }
+    }
+
+    fn list(&self, name: &str) -> Vec<String> {
+        match self.block(name) {
+            Some(Block::List(items)) => items.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// A value of a `key: value` block
+    fn value(&self, block: &str, key: &str) -> Option<String> {
+        self.dict(block)
+            .into_iter()
+            .find(|value| value.name == key)
+            .map(|value| value.value)
+    }
+
+    /// Order of a request or folder among its siblings
+    fn seq(&self) -> u64 {
+        self.value("meta", "seq")
+            .and_then(|seq| seq.parse().ok())
+            .unwrap_or(u64::MAX)
+    }
+
+    /// Report what can't be imported
+    fn unsupported(&self, location: &str, skipped: &mut Vec<String>) {
+        for (block, label) in UNSUPPORTED_BLOCKS {
+            let has_content = match self.block(block) {
+                Some(Block::Text(text)) => !text.trim().is_empty(),
+                Some(Block::Dict(values)) => !values.is_empty(),
+                _ => false,
+            };
+
+            if has_content {
+                skipped.push(format!("{location}: {label}"));
+            }
+        }
+    }
+
+    /// The auth of the file, `inherited` from its folders when set so
+    fn auth(
+        &self,
+        mode: Option<&str>,
+        inherited: &Option<Auth>,
+        location: &str,
+        skipped: &mut Vec<String>,
+    ) -> Option<Auth> {
+        let value =
+            |kind: &str, key: &str| self.value(&format!("auth:{kind}"), key).unwrap_or_default();
+
+        match mode? {
+            "none" => None,
+            "inherit" => inherited.clone(),
+            "basic" => Some(Auth::Basic {
+                username: value("basic", "username"),
+                password: value("basic", "password"),
+            }),
+            "bearer" => Some(Auth::Bearer {
+                token: value("bearer", "token"),
+            }),
+            "apikey" => Some(Auth::ApiKey {
+                name: value("apikey", "key"),
+                value: value("apikey", "value"),
+                location: match value("apikey", "placement").as_str() {
+                    "queryparams" => ApiKeyLocation::Query,
+                    _ => ApiKeyLocation::Header,
+                },
+            }),
+            kind => {
+                skipped.push(format!("{location}: {kind} auth"));
+                None
+            }
+        }
+    }
+
+    /// The auth of a folder or collection file, which names it in its
+    /// `auth` block
+    fn folder_auth(
+        &self,
+        inherited: &Option<Auth>,
+        location: &str,
+        skipped: &mut Vec<String>,
+    ) -> Option<Auth> {
+        match self.value("auth", "mode") {
+            Some(mode) => self.auth(Some(&mode), inherited, location, skipped),
+            None => inherited.clone(),
+        }
+    }
+}
+
+/// Headers and auth set on a folder or the collection for its requests
+#[derive(Clone, Default)]
+struct Inherited {
+    headers: Vec<KeyValue>,
+    auth: Option<Auth>,
+}
+
+impl Inherited {
+    fn with(&self, bru: Option<&Bru>, location: &str, skipped: &mut Vec<String>) -> Self {
+        let Some(bru) = bru else {
+            return self.clone();
+        };
+
+        let mut headers = self.headers.clone();
+        headers.extend(bru.dict("headers"));
+
+        Self {
+            headers,
+            auth: bru.folder_auth(&self.auth, location, skipped),
+        }
+    }
+}
+
+fn body(bru: &Bru, mode: &str, location: &str, skipped: &mut Vec<String>) -> Option<Body> {
+    let raw = |block: &str, content_type: &str| {
+        bru.text(block).map(|text| Body::Raw {
+            content_type: Some(content_type.to_string()),
+            text,
+        })
+    };
+
+    match mode {
+        "none" => None,
+        "json" => raw("body:json", "application/json"),
+        "text" => raw("body:text", "text/plain"),
+        "xml" => raw("body:xml", "application/xml"),
+        "sparql" => raw("body:sparql", "application/sparql-query"),
+        "formUrlEncoded" => Some(Body::Form {
+            fields: bru.dict("body:form-urlencoded"),
+        }),
+        "multipartForm" => {
+            let (files, fields): (Vec<_>, Vec<_>) = bru
+                .dict("body:multipart-form")
+                .into_iter()
+                .partition(|field| field.value.starts_with("@file("));
+
+            for file in &files {
+                skipped.push(format!("{location}: file part '{}' of the form", file.name));
+            }
+
+            Some(Body::Multipart { fields })
+        }
+        "graphql" => {
+            let variables = bru
+                .text("body:graphql:vars")
+                .and_then(|text| serde_json::from_str(&text).ok())
+                .unwrap_or(Value::Object(Map::new()));
+
+            let body = json!({
+                "query": bru.text("body:graphql").unwrap_or_default(),
+                "variables": variables,
+            });
+
+            Some(Body::Raw {
+                content_type: Some("application/json".to_string()),
+                text: serde_json::to_string_pretty(&body).unwrap_or_default(),
+            })
+        }
+        mode => {
+            skipped.push(format!("{location}: {mode} body"));
+            None
+        }
+    }
+}
+
+fn request(
+    bru: &Bru,
+    path: &Path,
+    folders: &[String],
+    inherited: &Inherited,
+    skipped: &mut Vec<String>,
+) -> SavedRequest {
+    let name = bru.value("meta", "name").unwrap_or_else(|| {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    });
+
+    let location = location(folders, &name);
+
+    let method = METHODS
+        .iter()
+        .find(|method| bru.block(method).is_some())
+        .unwrap_or(&"get");
+
+    let value = |key: &str| bru.value(method, key);
+
+    let url = value("url").unwrap_or_default();
+    let query = bru.dict("params:query");
+
+    // The URL repeats the query parameters, which may be disabled
+    let url = match query.is_empty() {
+        true => url,
+        false => url.split('?').next().unwrap_or_default().to_string(),
+    };
+
+    let method = method.to_uppercase();
+
+    if RequestMethod::from_str(&method).is_err() {
+        skipped.push(format!("{location}: {method} requests can't be sent yet"));
+    }
+
+    let variables = bru.dict("vars:pre-request");
+
+    if !variables.is_empty() {
+        skipped.push(format!(
+            "{location}: {}",
+            count(variables.len(), "request variable")
+        ));
+    }
+
+    bru.unsupported(&location, skipped);
+
+    let own_headers = bru.dict("headers");
+
+    let mut headers: Vec<KeyValue> = inherited
+        .headers
+        .iter()
+        .filter(|header| {
+            !own_headers
+                .iter()
+                .any(|own| own.name.eq_ignore_ascii_case(&header.name))
+        })
+        .cloned()
+        .collect();
+
+    headers.extend(own_headers);
+
+    SavedRequest {
+        method,
+        url: replace_path_variables(&url, &bru.dict("params:path")),
+        description: bru.text("docs"),
+        headers,
+        query,
+        body: body(
+            bru,
+            value("body").as_deref().unwrap_or("none"),
+            &location,
+            skipped,
+        ),
+        auth: bru.auth(
+            value("auth").as_deref(),
+            &inherited.auth,
+            &location,
+            skipped,
+        ),
+        operation: None,
+        examples: Vec::new(),
+        name,
+    }
+}
+
+/// The requests and folders of a directory, folders first as Bruno shows
+/// them
+fn items(
+    dir: &Path,
+    folders: &[String],
+    inherited: &Inherited,
+    skipped: &mut Vec<String>,
+) -> Result<Vec<Item>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+
+    paths.sort();
+
+    let mut requests = Vec::new();
+    let mut subfolders = Vec::new();
+
+    for path in paths {
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        if file_name.starts_with('.') || file_name == "node_modules" {
+            continue;
+        }
+
+        if path.is_dir() {
+            if folders.is_empty() && file_name == "environments" {
+                continue;
+            }
+
+            let meta = path.join("folder.bru");
+            let meta = match meta.is_file() {
+                true => Some(Bru::load(&meta)?),
+                false => None,
+            };
+
+            let name = meta
+                .as_ref()
+                .and_then(|meta| meta.value("meta", "name"))
+                .unwrap_or(file_name);
+
+            let location = location(folders, &name);
+
+            if let Some(meta) = &meta {
+                meta.unsupported(&location, skipped);
+            }
+
+            let inherited = inherited.with(meta.as_ref(), &location, skipped);
+
+            let mut path_folders = folders.to_vec();
+            path_folders.push(name.clone());
+
+            let seq = meta.as_ref().map_or(u64::MAX, Bru::seq);
+
+            let folder = Folder {
+                description: meta.as_ref().and_then(|meta| meta.text("docs")),
+                items: items(&path, &path_folders, &inherited, skipped)?,
+                name,
+            };
+
+            subfolders.push((seq, Item::Folder(folder)));
+        } else if path.extension().is_some_and(|extension| extension == "bru")
+            && file_name != "folder.bru"
+            && file_name != "collection.bru"
+        {
+            let bru = Bru::load(&path)?;
+            let seq = bru.seq();
+
+            requests.push((
+                seq,
+                Item::Request(Box::new(request(&bru, &path, folders, inherited, skipped))),
+            ));
+        }
+    }
+
+    // Stable, ties keep the order of the names
+    subfolders.sort_by_key(|(seq, _)| *seq);
+    requests.sort_by_key(|(seq, _)| *seq);
+
+    Ok(subfolders
+        .into_iter()
+        .chain(requests)
+        .map(|(_, item)| item)
+        .collect())
+}
+
+fn environments(dir: &Path, skipped: &mut Vec<String>) -> Result<Vec<Environment>, String> {
+    let dir = dir.join("environments");
+
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "bru"))
+        .collect();
+
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let bru = Bru::load(path)?;
+
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            Ok(environment(&bru, name, skipped))
+        })
+        .collect()
+}
+
+/// An environment file, secrets are kept on the machine they were set on
+fn environment(bru: &Bru, name: String, skipped: &mut Vec<String>) -> Environment {
+    let mut variables = bru.dict("vars");
+    let secrets = bru.list("vars:secret");
+
+    if !secrets.is_empty() {
+        skipped.push(format!(
+            "{name} environment: values of {}, set them in the collection",
+            count(secrets.len(), "secret variable")
+        ));
+    }
+
+    variables.extend(
+        secrets
+            .into_iter()
+            .map(|secret| secret.trim_start_matches('~').to_string())
+            .map(|secret| KeyValue::new(secret, "")),
+    );
+
+    Environment { name, variables }
+}
+
+/// Convert a Bruno collection folder, the one with `bruno.json`
+pub fn import(dir: &Path) -> Result<Import, String> {
+    let config = dir.join("bruno.json");
+
+    if !config.is_file() {
+        return Err(format!(
+            "{}: not a Bruno collection, there is no bruno.json in it",
+            dir.display()
+        ));
+    }
+
+    let config = read_json(&config)?;
+
+    let name = config
+        .get("name")
+        .and_then(text)
+        .or_else(|| {
+            dir.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| "Bruno".to_string());
+
+    let mut skipped = Vec::new();
+
+    let root = dir.join("collection.bru");
+    let root = match root.is_file() {
+        true => Some(Bru::load(&root)?),
+        false => None,
+    };
+
+    if let Some(root) = &root {
+        root.unsupported(&name, &mut skipped);
+    }
+
+    let inherited = Inherited::default().with(root.as_ref(), &name, &mut skipped);
+
+    let collection = Collection {
+        description: root.as_ref().and_then(|root| root.text("docs")),
+        variables: root
+            .as_ref()
+            .map(|root| root.dict("vars:pre-request"))
+            .unwrap_or_default(),
+        environments: environments(dir, &mut skipped)?,
+        items: items(dir, &[], &inherited, &mut skipped)?,
+        spec: None,
+        name,
+    };
+
+    Ok(Import {
+        collection,
+        skipped,
+        notes: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLLECTION: &str = "\
+headers {
+  X-Client: tuapi
+}
+
+auth {
+  mode: bearer
+}
+
+auth:bearer {
+  token: {{token}}
+}
+";
+
+    const FOLDER: &str = "\
+meta {
+  name: Users
+  seq: 1
+}
+
+auth {
+  mode: basic
+}
+
+auth:basic {
+  username: admin
+  password: secret
+}
+";
+
+    const GET_USER: &str = "\
+meta {
+  name: Get user
+  seq: 2
+}
+
+get {
+  url: {{host}}/users/:id?expand=all
+  body: none
+  auth: inherit
+}
+
+params:query {
+  expand: all
+  ~debug: true
+}
+
+params:path {
+  id: 7
+}
+
+headers {
+  X-Client: test
+}
+
+tests {
+  test(\"ok\", () => {});
+}
+";
+
+    const CREATE_USER: &str = "\
+meta {
+  name: Create user
+  seq: 1
+}
+
+post {
+  url: {{host}}/users
+  body: json
+  auth: none
+}
+
+body:json {
+  {
+    \"name\": \"a\"
+  }
+}
+";
+
+    const UPLOAD: &str = "\
+meta {
+  name: Upload
+}
+
+put {
+  url: {{host}}/files
+  body: multipartForm
+  auth: inherit
+}
+
+body:multipart-form {
+  title: report
+  file: @file(report.pdf)
+}
+";
+
+    const ENVIRONMENT: &str = "\
+vars {
+  host: https://shop.test
+}
+
+vars:secret [
+  token
+]
+";
+
+    fn parse(text: &str) -> Bru {
+        Bru::parse(text)
+    }
+
+    fn convert(text: &str, skipped: &mut Vec<String>) -> SavedRequest {
+        let inherited = Inherited::default()
+            .with(Some(&parse(COLLECTION)), "Shop", skipped)
+            .with(Some(&parse(FOLDER)), "Users", skipped);
+
+        request(
+            &parse(text),
+            Path::new("users/request.bru"),
+            &["Users".to_string()],
+            &inherited,
+            skipped,
+        )
+    }
+
+    #[test]
+    fn parses_meta_and_falls_back_to_the_file_name() {
+        let mut skipped = Vec::new();
+
+        assert_eq!(parse(GET_USER).seq(), 2);
+        assert_eq!(parse(UPLOAD).seq(), u64::MAX);
+        assert_eq!(convert(GET_USER, &mut skipped).name, "Get user");
+        assert_eq!(
+            request(
+                &parse("put {\n  url: /files\n}\n"),
+                Path::new("upload.bru"),
+                &[],
+                &Inherited::default(),
+                &mut skipped,
+            )
+            .name,
+            "upload"
+        );
+    }
+
+    #[test]
+    fn imports_requests() {
+        let mut skipped = Vec::new();
+        let get = convert(GET_USER, &mut skipped);
+        let create = convert(CREATE_USER, &mut skipped);
+
+        assert_eq!(get.method, "GET");
+        assert_eq!(get.url, "{{host}}/users/7");
+        assert_eq!(get.query[0], KeyValue::new("expand", "all"));
+        assert!(!get.query[1].enabled);
+        assert_eq!(get.headers, vec![KeyValue::new("X-Client", "test")]);
+
+        assert_eq!(create.method, "POST");
+        assert_eq!(create.headers, vec![KeyValue::new("X-Client", "tuapi")]);
+        assert_eq!(
+            create.body,
+            Some(Body::Raw {
+                content_type: Some("application/json".to_string()),
+                text: "{\n  \"name\": \"a\"\n}".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn inherits_auth_from_folders_and_the_collection() {
+        let mut skipped = Vec::new();
+        let collection = Inherited::default().with(Some(&parse(COLLECTION)), "Shop", &mut skipped);
+
+        assert_eq!(convert(CREATE_USER, &mut skipped).auth, None);
+        assert_eq!(
+            convert(GET_USER, &mut skipped).auth,
+            Some(Auth::Basic {
+                username: "admin".to_string(),
+                password: "secret".to_string(),
+            })
+        );
+        assert_eq!(
+            request(
+                &parse(UPLOAD),
+                Path::new("upload.bru"),
+                &[],
+                &collection,
+                &mut skipped
+            )
+            .auth,
+            Some(Auth::Bearer {
+                token: "{{token}}".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn imports_environments_and_reports_what_was_skipped() {
+        let mut skipped = Vec::new();
+
+        assert_eq!(
+            environment(&parse(ENVIRONMENT), "Local".to_string(), &mut skipped),
+            Environment {
+                name: "Local".to_string(),
+                variables: vec![
+                    KeyValue::new("host", "https://shop.test"),
+                    KeyValue::new("token", ""),
+                ],
+            }
+        );
+
+        convert(UPLOAD, &mut skipped);
+        convert(GET_USER, &mut skipped);
+
+        assert_eq!(
+            skipped,
+            vec![
+                "Local environment: values of 1 secret variable, set them in the collection",
+                "Users / Upload: file part 'file' of the form",
+                "Users / Get user: tests",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_folders_without_bruno_json() {
+        let error = import(Path::new("missing-collection")).err().unwrap();
+
+        assert!(error.ends_with("not a Bruno collection, there is no bruno.json in it"));
+    }
+}
@@ -0,0 +1,82 @@
+use std::{path::Path, str::FromStr};
+
+use url::Url;
+
+use crate::{
+    app::RequestMethod,
+    collection::{Collection, Folder, Item},
+    har,
+    plural::count,
+};
+
+use super::Import;
+
+/// A HAR capture, with a folder per host when there are several
+pub fn import(path: &Path) -> Result<Import, String> {
+    let exchanges = har::load(path)?;
+
+    let mut skipped = Vec::new();
+    let mut hosts: Vec<(String, Vec<Item>)> = Vec::new();
+
+    for exchange in &exchanges {
+        let request = &exchange.request;
+
+        if RequestMethod::from_str(&request.method).is_err() {
+            skipped.push(format!(
+                "{}: {} requests can't be sent yet",
+                request.name, request.method
+            ));
+            continue;
+        }
+
+        let host = Url::parse(&request.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+
+        let item = Item::Request(Box::new(request.clone()));
+
+        match hosts.iter_mut().find(|(name, _)| *name == host) {
+            Some((_, items)) => items.push(item),
+            None => hosts.push((host, vec![item])),
+        }
+    }
+
+    let mut notes = Vec::new();
+
+    if !exchanges.is_empty() {
+        notes.push(format!(
+            "The {} aren't part of the collection, open the file with tuapi to browse them",
+            count(exchanges.len(), "recorded response")
+        ));
+    }
+
+    let items = match hosts.len() {
+        1 => hosts.remove(0).1,
+        _ => hosts
+            .into_iter()
+            .map(|(name, items)| {
+                Item::Folder(Folder {
+                    name,
+                    description: None,
+                    items,
+                })
+            })
+            .collect(),
+    };
+
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "HAR".to_string());
+
+    Ok(Import {
+        collection: Collection {
+            name,
+            items,
+            ..Collection::default()
+        },
+        skipped,
+        notes,
+    })
+}
@@ -0,0 +1,574 @@
+use std::{path::Path, str::FromStr};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    app::RequestMethod,
+    collection::{
+        ApiKeyLocation, Auth, Body, Collection, Environment, Folder, Item, KeyValue, SavedRequest,
+    },
+    plural::count,
+};
+
+use super::{location, read_json, text, Import};
+
+/// `{{ _.name }}`, how Insomnia refers to environment variables
+static VARIABLE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{\s*_\.([^\s}]+)\s*\}\}").unwrap());
+
+/// Resources that can be sent but not by tuapi
+const OTHER_REQUESTS: [(&str, &str); 2] = [
+    ("grpc_request", "gRPC request"),
+    ("websocket_request", "WebSocket request"),
+];
+
+/// Template with variables in the collection syntax, template tags like
+/// `{% uuid %}` are reported
+fn template(value: Option<&Value>, location: &str, skipped: &mut Vec<String>) -> String {
+    let value = value.and_then(text).unwrap_or_default();
+
+    if value.contains("{%") {
+        skipped.push(format!("{location}: template tag in '{value}'"));
+    }
+
+    VARIABLE.replace_all(&value, "{{$1}}").to_string()
+}
+
+fn string<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or_default()
+}
+
+fn is_disabled(value: &Value) -> bool {
+    value.get("disabled").and_then(Value::as_bool) == Some(true)
+}
+
+/// Name and value pairs, as headers, parameters and form fields are
+fn key_values(value: Option<&Value>, location: &str, skipped: &mut Vec<String>) -> Vec<KeyValue> {
+    let Some(values) = value.and_then(Value::as_array) else {
+        return Vec::new();
+    };
+
+    values
+        .iter()
+        .filter(|entry| !string(entry, "name").is_empty())
+        .map(|entry| KeyValue {
+            name: template(entry.get("name"), location, skipped),
+            value: template(entry.get("value"), location, skipped),
+            enabled: !is_disabled(entry),
+        })
+        .collect()
+}
+
+/// Nested environment data becomes `parent.child` variables, as Insomnia
+/// refers to it with `{{ _.parent.child }}`
+fn flatten(prefix: &str, value: &Value, variables: &mut Vec<KeyValue>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let name = match prefix.is_empty() {
+                    true => key.clone(),
+                    false => format!("{prefix}.{key}"),
+                };
+
+                flatten(&name, value, variables);
+            }
+        }
+        Value::Array(_) => variables.push(KeyValue::new(prefix, value.to_string())),
+        value => variables.push(KeyValue::new(prefix, text(value).unwrap_or_default())),
+    }
+}
+
+fn variables(environment: &Value) -> Vec<KeyValue> {
+    let mut variables = Vec::new();
+
+    flatten(
+        "",
+        environment.get("data").unwrap_or(&Value::Null),
+        &mut variables,
+    );
+
+    variables
+}
+
+/// The auth of a request or folder, `inherited` from its folders when it
+/// has none
+fn auth(
+    value: Option<&Value>,
+    inherited: &Option<Auth>,
+    location: &str,
+    skipped: &mut Vec<String>,
+) -> Option<Auth> {
+    let Some(value) = value.filter(|value| value.get("type").is_some()) else {
+        return inherited.clone();
+    };
+
+    if is_disabled(value) {
+        return None;
+    }
+
+    let mut param = |key: &str| template(value.get(key), location, skipped);
+
+    match string(value, "type") {
+        "none" => None,
+        "inherit" => inherited.clone(),
+        "basic" => Some(Auth::Basic {
+            username: param("username"),
+            password: param("password"),
+        }),
+        "bearer" => {
+            let token = param("token");
+
+            match param("prefix").as_str() {
+                "" | "Bearer" => Some(Auth::Bearer { token }),
+                prefix => Some(Auth::ApiKey {
+                    name: "Authorization".to_string(),
+                    value: format!("{prefix} {token}"),
+                    location: ApiKeyLocation::Header,
+                }),
+            }
+        }
+        "apikey" => {
+            let name = param("key");
+            let key = param("value");
+
+            let location = match string(value, "addTo") {
+                "queryParams" => ApiKeyLocation::Query,
+                "cookie" => {
+                    skipped.push(format!("{location}: API key in a cookie"));
+                    return None;
+                }
+                _ => ApiKeyLocation::Header,
+            };
+
+            Some(Auth::ApiKey {
+                name,
+                value: key,
+                location,
+            })
+        }
+        kind => {
+            skipped.push(format!("{location}: {kind} auth"));
+            None
+        }
+    }
+}
+
+fn body(value: Option<&Value>, location: &str, skipped: &mut Vec<String>) -> Option<Body> {
+    let value = value.filter(|value| value.is_object())?;
+    let mime_type = string(value, "mimeType");
+
+    if value.get("fileName").is_some() {
+        skipped.push(format!("{location}: file body"));
+        return None;
+    }
+
+    match mime_type {
+        "application/x-www-form-urlencoded" => Some(Body::Form {
+            fields: key_values(value.get("params"), location, skipped),
+        }),
+        "multipart/form-data" => {
+            let parts = value
+                .get("params")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+
+            let (files, texts): (Vec<_>, Vec<_>) = parts
+                .into_iter()
+                .partition(|part| string(part, "type") == "file");
+
+            for file in &files {
+                skipped.push(format!(
+                    "{location}: file part '{}' of the form",
+                    string(file, "name")
+                ));
+            }
+
+            Some(Body::Multipart {
+                fields: key_values(Some(&Value::Array(texts)), location, skipped),
+            })
+        }
+        "application/graphql" => {
+            let graphql: Value = serde_json::from_str(string(value, "text")).ok()?;
+
+            let body = json!({
+                "query": string(&graphql, "query"),
+                "variables": graphql.get("variables").cloned().unwrap_or(Value::Object(Map::new())),
+            });
+
+            Some(Body::Raw {
+                content_type: Some("application/json".to_string()),
+                text: serde_json::to_string_pretty(&body).unwrap_or_default(),
+            })
+        }
+        mime_type => {
+            let text = template(value.get("text"), location, skipped);
+
+            (!text.is_empty()).then(|| Body::Raw {
+                content_type: Some(mime_type.to_string()).filter(|mime| !mime.is_empty()),
+                text,
+            })
+        }
+    }
+}
+
+fn description(value: &Value) -> Option<String> {
+    Some(string(value, "description").to_string()).filter(|text| !text.is_empty())
+}
+
+fn request(
+    resource: &Value,
+    folders: &[String],
+    inherited: &Option<Auth>,
+    skipped: &mut Vec<String>,
+) -> SavedRequest {
+    let name = resource
+        .get("name")
+        .and_then(text)
+        .unwrap_or_else(|| "Untitled".to_string());
+
+    let location = location(folders, &name);
+
+    let method = resource
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or("GET")
+        .to_uppercase();
+
+    if RequestMethod::from_str(&method).is_err() {
+        skipped.push(format!("{location}: {method} requests can't be sent yet"));
+    }
+
+    SavedRequest {
+        method,
+        url: template(resource.get("url"), &location, skipped),
+        description: description(resource),
+        headers: key_values(resource.get("headers"), &location, skipped),
+        query: key_values(resource.get("parameters"), &location, skipped),
+        body: body(resource.get("body"), &location, skipped),
+        auth: auth(
+            resource.get("authentication"),
+            inherited,
+            &location,
+            skipped,
+        ),
+        operation: None,
+        examples: Vec::new(),
+        name,
+    }
+}
+
+/// The resources whose parent is `parent`, in the order Insomnia shows
+/// them
+fn children<'a>(resources: &'a [Value], parent: &str) -> Vec<&'a Value> {
+    let mut children: Vec<&Value> = resources
+        .iter()
+        .filter(|resource| string(resource, "parentId") == parent)
+        .collect();
+
+    let sort_key = |resource: &Value| {
+        resource
+            .get("metaSortKey")
+            .and_then(Value::as_f64)
+            .unwrap_or_default()
+    };
+
+    children.sort_by(|a, b| sort_key(a).total_cmp(&sort_key(b)));
+    children
+}
+
+fn items(
+    resources: &[Value],
+    parent: &str,
+    folders: &[String],
+    inherited: &Option<Auth>,
+    skipped: &mut Vec<String>,
+) -> Vec<Item> {
+    let mut converted = Vec::new();
+
+    for resource in children(resources, parent) {
+        let name = string(resource, "name").to_string();
+
+        match string(resource, "_type") {
+            "request" => converted.push(Item::Request(Box::new(request(
+                resource, folders, inherited, skipped,
+            )))),
+            "request_group" => {
+                let location = location(folders, &name);
+
+                let folder_variables = resource
+                    .get("environment")
+                    .and_then(Value::as_object)
+                    .map_or(0, Map::len);
+
+                if folder_variables > 0 {
+                    skipped.push(format!(
+                        "{location}: {} of the folder",
+                        count(folder_variables, "variable")
+                    ));
+                }
+
+                let auth = auth(
+                    resource.get("authentication"),
+                    inherited,
+                    &location,
+                    skipped,
+                );
+
+                let mut path = folders.to_vec();
+                path.push(name.clone());
+
+                converted.push(Item::Folder(Folder {
+                    description: description(resource),
+                    items: items(resources, string(resource, "_id"), &path, &auth, skipped),
+                    name,
+                }));
+            }
+            kind => {
+                if let Some((_, label)) = OTHER_REQUESTS.iter().find(|(other, _)| *other == kind) {
+                    skipped.push(format!("{}: {label}", location(folders, &name)));
+                }
+            }
+        }
+    }
+
+    converted
+}
+
+/// Convert the first workspace of an Insomnia v4 export, its base
+/// environment gives the variables of the collection and its
+/// sub-environments the environments
+pub fn import(path: &Path) -> Result<Import, String> {
+    let value = read_json(path)?;
+
+    convert(&value).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn convert(value: &Value) -> Result<Import, String> {
+    let is_v4 = string(value, "_type") == "export"
+        && value.get("__export_format").and_then(Value::as_u64) == Some(4);
+
+    if !is_v4 {
+        return Err("not an Insomnia v4 export, export it again as Insomnia v4 (JSON)".to_string());
+    }
+
+    let resources = value
+        .get("resources")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+
+    let workspaces: Vec<&Value> = resources
+        .iter()
+        .filter(|resource| string(resource, "_type") == "workspace")
+        .collect();
+
+    let Some(workspace) = workspaces.first() else {
+        return Err("no workspace in the export".to_string());
+    };
+
+    let mut skipped = Vec::new();
+
+    for other in &workspaces[1..] {
+        skipped.push(format!(
+            "{}: only the first workspace of an export is imported",
+            string(other, "name")
+        ));
+    }
+
+    let id = string(workspace, "_id");
+    let name = string(workspace, "name").to_string();
+
+    let environments: Vec<&Value> = resources
+        .iter()
+        .filter(|resource| string(resource, "_type") == "environment")
+        .collect();
+
+    let base = environments
+        .iter()
+        .find(|environment| string(environment, "parentId") == id);
+
+    let collection = Collection {
+        description: description(workspace),
+        variables: base.map(|base| variables(base)).unwrap_or_default(),
+        environments: base
+            .map(|base| {
+                children(&resources, string(base, "_id"))
+                    .into_iter()
+                    .filter(|environment| string(environment, "_type") == "environment")
+                    .map(|environment| Environment {
+                        name: string(environment, "name").to_string(),
+                        variables: variables(environment),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        items: items(&resources, id, &[], &None, &mut skipped),
+        spec: None,
+        name,
+    };
+
+    let cookies = resources
+        .iter()
+        .filter(|resource| string(resource, "_type") == "cookie_jar")
+        .filter_map(|jar| jar.get("cookies").and_then(Value::as_array))
+        .map(Vec::len)
+        .sum();
+
+    if cookies > 0 {
+        skipped.push(format!("cookie jar: {}", count(cookies, "cookie")));
+    }
+
+    Ok(Import {
+        collection,
+        skipped,
+        notes: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{
+        "_type": "export",
+        "__export_format": 4,
+        "resources": [
+            {"_id": "wrk_1", "_type": "workspace", "parentId": null, "name": "Shop"},
+            {
+                "_id": "env_base", "_type": "environment", "parentId": "wrk_1", "name": "Base",
+                "data": {"host": "https://shop.test", "api": {"key": "abc"}}
+            },
+            {
+                "_id": "env_prod", "_type": "environment", "parentId": "env_base", "name": "Prod",
+                "data": {"host": "https://shop.example"}
+            },
+            {
+                "_id": "fld_1", "_type": "request_group", "parentId": "wrk_1", "name": "Users",
+                "metaSortKey": -2,
+                "environment": {"page": 1},
+                "authentication": {"type": "bearer", "token": "{{ _.api.key }}"}
+            },
+            {
+                "_id": "req_1", "_type": "request", "parentId": "fld_1", "name": "List users",
+                "method": "GET",
+                "url": "{{ _.host }}/users",
+                "headers": [{"name": "Accept", "value": "application/json", "disabled": true}],
+                "parameters": [{"name": "page", "value": "2"}],
+                "authentication": {"type": "inherit"}
+            },
+            {
+                "_id": "req_2", "_type": "request", "parentId": "wrk_1", "name": "Login",
+                "metaSortKey": -1,
+                "method": "post",
+                "url": "{{ _.host }}/login",
+                "body": {
+                    "mimeType": "application/x-www-form-urlencoded",
+                    "params": [{"name": "id", "value": "{% uuid 'v4' %}"}]
+                },
+                "authentication": {"type": "basic", "username": "admin", "password": "secret"}
+            },
+            {"_id": "grpc_1", "_type": "grpc_request", "parentId": "wrk_1", "name": "Stream"},
+            {"_id": "jar_1", "_type": "cookie_jar", "parentId": "wrk_1", "cookies": [{"key": "sid"}]}
+        ]
+    }"#;
+
+    fn import() -> Import {
+        convert(&serde_json::from_str(EXPORT).unwrap()).unwrap()
+    }
+
+    fn request(item: &Item) -> &SavedRequest {
+        match item {
+            Item::Request(request) => request,
+            Item::Folder(folder) => panic!("{} is a folder", folder.name),
+        }
+    }
+
+    #[test]
+    fn imports_requests_in_order_with_variables() {
+        let collection = import().collection;
+
+        let Item::Folder(users) = &collection.items[0] else {
+            panic!("Users is not a folder");
+        };
+        let list = request(&users.items[0]);
+
+        assert_eq!(list.url, "{{host}}/users");
+        assert_eq!(list.query, vec![KeyValue::new("page", "2")]);
+        assert!(!list.headers[0].enabled);
+
+        let login = request(&collection.items[1]);
+
+        assert_eq!(login.method, "POST");
+        assert_eq!(
+            login.body,
+            Some(Body::Form {
+                fields: vec![KeyValue::new("id", "{% uuid 'v4' %}")],
+            })
+        );
+    }
+
+    #[test]
+    fn inherits_auth_from_folders() {
+        let collection = import().collection;
+
+        let Item::Folder(users) = &collection.items[0] else {
+            panic!("Users is not a folder");
+        };
+
+        assert_eq!(
+            request(&users.items[0]).auth,
+            Some(Auth::Bearer {
+                token: "{{api.key}}".to_string(),
+            })
+        );
+        assert_eq!(
+            request(&collection.items[1]).auth,
+            Some(Auth::Basic {
+                username: "admin".to_string(),
+                password: "secret".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn flattens_environments() {
+        let collection = import().collection;
+
+        assert_eq!(
+            collection.variables,
+            vec![
+                KeyValue::new("host", "https://shop.test"),
+                KeyValue::new("api.key", "abc"),
+            ]
+        );
+        assert_eq!(collection.environments.len(), 1);
+        assert_eq!(collection.environments[0].name, "Prod");
+        assert_eq!(
+            collection.environments[0].variables,
+            vec![KeyValue::new("host", "https://shop.example")]
+        );
+    }
+
+    #[test]
+    fn reports_what_was_skipped() {
+        assert_eq!(
+            import().skipped,
+            vec![
+                "Users: 1 variable of the folder",
+                "Login: template tag in '{% uuid 'v4' %}'",
+                "Stream: gRPC request",
+                "cookie jar: 1 cookie",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_other_exports() {
+        assert_eq!(
+            convert(&serde_json::json!({"_type": "export", "__export_format": 3})).err(),
+            Some("not an Insomnia v4 export, export it again as Insomnia v4 (JSON)".to_string())
+        );
+    }
+}
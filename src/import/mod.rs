@@ -0,0 +1,139 @@
+pub mod bruno;
+pub mod har;
+pub mod insomnia;
+pub mod openapi;
+pub mod postman;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use crate::{
+    cli::{ImportFormat, ImportOutput},
+    collection::{self, Collection, KeyValue},
+    plural::count,
+};
+
+/// A collection converted from another tool
+pub struct Import {
+    pub collection: Collection,
+    /// What couldn't be converted, with where it was found
+    pub skipped: Vec<String>,
+    /// Told after the summary, like where else to find what was converted
+    pub notes: Vec<String>,
+}
+
+fn output_path(collection: &Collection, output: &ImportOutput) -> Result<PathBuf, String> {
+    let path = match output.output.clone() {
+        Some(path) => path,
+        None => collection::collections_dir()
+            .ok_or("no home directory to save the collection to, use --output")?
+            .join(format!("{}.yaml", collection::slug(&collection.name))),
+    };
+
+    if path.exists() && !output.force && !output.dry_run {
+        return Err(format!(
+            "{} already exists, use --force to replace it",
+            path.display()
+        ));
+    }
+
+    Ok(path)
+}
+
+pub fn read_json(path: &Path) -> Result<Value, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+
+    serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Strings as they are, numbers and booleans as written
+pub fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(boolean) => Some(boolean.to_string()),
+        _ => None,
+    }
+}
+
+/// Where an item is, like `Users / Create user`, to report what was skipped
+pub fn location(folders: &[String], name: &str) -> String {
+    let mut parts = folders.to_vec();
+    parts.push(name.to_string());
+
+    parts.join(" / ")
+}
+
+/// `:name` path segments become their value, or a `{{name}}` variable
+/// when there is none
+pub fn replace_path_variables(url: &str, variables: &[KeyValue]) -> String {
+    url.split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(name) if !name.is_empty() => variables
+                .iter()
+                .find(|variable| variable.name == name && !variable.value.is_empty())
+                .map(|variable| variable.value.clone())
+                .unwrap_or_else(|| format!("{{{{{name}}}}}")),
+            _ => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Convert a file, save the collection and report what was left out
+pub fn run(format: ImportFormat) -> Result<(), String> {
+    let (import, output) = match format {
+        ImportFormat::Postman {
+            file,
+            environments,
+            output,
+        } => (postman::import(&file, &environments)?, output),
+        ImportFormat::Openapi { file, output } => (openapi::import(&file)?, output),
+        ImportFormat::Har { file, output } => (har::import(&file)?, output),
+        ImportFormat::Insomnia { file, output } => (insomnia::import(&file)?, output),
+        ImportFormat::Bruno { dir, output } => (bruno::import(&dir)?, output),
+    };
+
+    let collection = &import.collection;
+    let path = output_path(collection, &output)?;
+
+    if !output.dry_run {
+        collection
+            .save(&path)
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+    }
+
+    println!(
+        "{} '{}': {}, {}, {}",
+        match output.dry_run {
+            true => "Would import",
+            false => "Imported",
+        },
+        collection.name,
+        count(collection.requests().len(), "request"),
+        count(collection.folder_count(), "folder"),
+        count(collection.environments.len(), "environment"),
+    );
+    match output.dry_run {
+        true => println!("Would save to {}", path.display()),
+        false => println!("Saved to {}", path.display()),
+    }
+
+    if !import.skipped.is_empty() {
+        println!("Not imported:");
+
+        for skipped in &import.skipped {
+            println!("  {skipped}");
+        }
+    }
+
+    for note in &import.notes {
+        println!("{note}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,1137 @@
+use std::{fs, path::Path, str::FromStr};
+
+use percent_encoding::percent_decode_str;
+use serde_json::{Map, Value};
+
+use crate::{
+    app::RequestMethod,
+    collection::{
+        ApiKeyLocation, Auth, Body, Collection, Environment, Example, Folder, Item, KeyValue,
+        SavedRequest,
+    },
+};
+
+use super::Import;
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Deepest nesting of references and schemas followed, recursive schemas
+/// stop there
+const MAX_DEPTH: usize = 8;
+
+static NULL: Value = Value::Null;
+
+/// Values as they would be written in a URL or a form
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(values) => values.iter().map(text).collect::<Vec<_>>().join(","),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+/// Example of a string schema from its format
+fn string_example(format: Option<&str>) -> Value {
+    let example = match format {
+        Some("date-time") => "2024-01-01T00:00:00Z",
+        Some("date") => "2024-01-01",
+        Some("time") => "00:00:00",
+        Some("email") => "user@example.com",
+        Some("uuid") => "3fa85f64-5717-4562-b3fc-2c963f66afa6",
+        Some("uri" | "url") => "https://example.com",
+        Some("hostname") => "example.com",
+        Some("ipv4") => "127.0.0.1",
+        Some("ipv6") => "::1",
+        Some("byte") => "c3RyaW5n",
+        _ => "string",
+    };
+
+    Value::String(example.to_string())
+}
+
+/// The spec being converted and what was found along the way
+struct Spec<'a> {
+    root: &'a Value,
+    is_swagger: bool,
+    skipped: Vec<String>,
+    /// Variables the auth of the requests is sent with
+    credentials: Vec<String>,
+    /// References of the schemas being made examples of
+    expanding: Vec<String>,
+}
+
+impl<'a> Spec<'a> {
+    fn skip(&mut self, message: String) {
+        if !self.skipped.contains(&message) {
+            self.skipped.push(message);
+        }
+    }
+
+    fn credential(&mut self, name: &str) -> String {
+        if !self.credentials.iter().any(|credential| credential == name) {
+            self.credentials.push(name.to_string());
+        }
+
+        format!("{{{{{name}}}}}")
+    }
+
+    /// Follow local `$ref`s, external ones aren't loaded
+    fn resolve(&mut self, value: &'a Value, location: &str) -> &'a Value {
+        let mut value = value;
+
+        for _ in 0..MAX_DEPTH {
+            let Some(reference) = value.get("$ref").and_then(Value::as_str) else {
+                return value;
+            };
+
+            let pointer = reference
+                .strip_prefix('#')
+                .map(|pointer| percent_decode_str(pointer).decode_utf8_lossy().to_string());
+
+            match pointer.and_then(|pointer| self.root.pointer(&pointer)) {
+                Some(target) => value = target,
+                None => {
+                    self.skip(format!("{location}: reference {reference}"));
+                    return &NULL;
+                }
+            }
+        }
+
+        value
+    }
+
+    /// A value matching a schema, from its examples and defaults or made up
+    /// from its types
+    fn example(&mut self, schema: &'a Value, location: &str, depth: usize) -> Value {
+        let reference = schema.get("$ref").and_then(Value::as_str);
+
+        // A schema containing itself ends where it repeats
+        if depth > MAX_DEPTH || reference.is_some_and(|r| self.expanding.iter().any(|e| e == r)) {
+            return Value::Null;
+        }
+
+        if let Some(reference) = reference {
+            self.expanding.push(reference.to_string());
+        }
+
+        let example = self.expand(schema, location, depth);
+
+        if reference.is_some() {
+            self.expanding.pop();
+        }
+
+        example
+    }
+
+    fn expand(&mut self, schema: &'a Value, location: &str, depth: usize) -> Value {
+        let schema = self.resolve(schema, location);
+
+        let given = schema
+            .get("example")
+            .or_else(|| {
+                schema
+                    .get("examples")
+                    .and_then(Value::as_array)
+                    .and_then(|examples| examples.first())
+            })
+            .or_else(|| schema.get("default"))
+            .or_else(|| schema.get("const"))
+            .or_else(|| {
+                schema
+                    .get("enum")
+                    .and_then(Value::as_array)
+                    .and_then(|values| values.first())
+            });
+
+        if let Some(given) = given {
+            return given.clone();
+        }
+
+        if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+            let mut merged = Map::new();
+
+            for part in parts {
+                if let Value::Object(properties) = self.example(part, location, depth + 1) {
+                    merged.extend(properties);
+                }
+            }
+
+            return Value::Object(merged);
+        }
+
+        let choice = ["oneOf", "anyOf"].into_iter().find_map(|key| {
+            schema
+                .get(key)
+                .and_then(Value::as_array)
+                .and_then(|choices| choices.first())
+        });
+
+        if let Some(choice) = choice {
+            return self.example(choice, location, depth + 1);
+        }
+
+        let kind = match schema.get("type") {
+            Some(Value::String(kind)) => kind.as_str(),
+            // OpenAPI 3.1 lists the types, possibly with null
+            Some(Value::Array(kinds)) => kinds
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|kind| *kind != "null")
+                .unwrap_or("null"),
+            _ if schema.get("properties").is_some() => "object",
+            _ if schema.get("items").is_some() => "array",
+            _ => "",
+        };
+
+        match kind {
+            "object" => {
+                let properties = schema
+                    .get("properties")
+                    .and_then(Value::as_object)
+                    .map(|properties| {
+                        properties
+                            .iter()
+                            .map(|(name, property)| {
+                                (name.clone(), self.example(property, location, depth + 1))
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                Value::Object(properties)
+            }
+            "array" => Value::Array(
+                schema
+                    .get("items")
+                    .map(|items| self.example(items, location, depth + 1))
+                    .filter(|item| !item.is_null())
+                    .into_iter()
+                    .collect(),
+            ),
+            "string" => string_example(schema.get("format").and_then(Value::as_str)),
+            "integer" | "number" => schema
+                .get("minimum")
+                .cloned()
+                .unwrap_or_else(|| Value::from(0)),
+            "boolean" => Value::Bool(true),
+            _ => Value::Null,
+        }
+    }
+
+    /// The value a parameter is documented with, if any
+    fn parameter_example(&mut self, parameter: &'a Value, location: &str) -> Option<String> {
+        let example = parameter.get("example").or_else(|| {
+            parameter
+                .get("examples")
+                .and_then(Value::as_object)
+                .and_then(|examples| examples.values().next())
+                .map(|example| self.resolve(example, location))
+                .and_then(|example| example.get("value"))
+        });
+
+        if let Some(example) = example {
+            return Some(text(example));
+        }
+
+        // Swagger 2.0 describes the type on the parameter itself
+        let schema = match parameter.get("schema") {
+            Some(schema) => self.resolve(schema, location),
+            None => parameter,
+        };
+
+        schema
+            .get("example")
+            .or_else(|| schema.get("default"))
+            .or_else(|| {
+                schema
+                    .get("enum")
+                    .and_then(Value::as_array)
+                    .and_then(|values| values.first())
+            })
+            .map(text)
+    }
+
+    /// Parameters of the path and the operation, the operation's replacing
+    /// those with the same name and location
+    fn parameters(
+        &mut self,
+        path_item: &'a Value,
+        operation: &'a Value,
+        location: &str,
+    ) -> Vec<&'a Value> {
+        let mut parameters: Vec<&'a Value> = Vec::new();
+
+        for list in [path_item.get("parameters"), operation.get("parameters")] {
+            for parameter in list.and_then(Value::as_array).into_iter().flatten() {
+                let parameter = self.resolve(parameter, location);
+                let key = (parameter.get("name"), parameter.get("in"));
+
+                parameters.retain(|other| (other.get("name"), other.get("in")) != key);
+                parameters.push(parameter);
+            }
+        }
+
+        parameters
+    }
+
+    /// Form fields from the properties of an object schema
+    fn form_fields(&mut self, schema: &'a Value, location: &str) -> Vec<KeyValue> {
+        let schema = self.resolve(schema, location);
+
+        let Value::Object(example) = self.example(schema, location, 0) else {
+            return Vec::new();
+        };
+
+        example
+            .into_iter()
+            .filter(|(name, _)| {
+                let format = schema
+                    .pointer(&format!(
+                        "/properties/{}/format",
+                        name.replace('~', "~0").replace('/', "~1")
+                    ))
+                    .and_then(Value::as_str);
+
+                let is_file = format == Some("binary");
+
+                if is_file {
+                    self.skip(format!("{location}: file part '{name}' of the form"));
+                }
+
+                !is_file
+            })
+            .map(|(name, value)| KeyValue::new(name, text(&value)))
+            .collect()
+    }
+
+    /// The body of an OpenAPI 3 operation, preferring JSON
+    fn request_body(&mut self, operation: &'a Value, location: &str) -> Option<Body> {
+        let body = self.resolve(operation.get("requestBody")?, location);
+        let content = body.get("content")?.as_object()?;
+
+        let (content_type, media) = content
+            .iter()
+            .find(|(content_type, _)| content_type.contains("json"))
+            .or_else(|| {
+                ["application/x-www-form-urlencoded", "multipart/form-data"]
+                    .into_iter()
+                    .find_map(|wanted| {
+                        content
+                            .iter()
+                            .find(|(content_type, _)| *content_type == wanted)
+                    })
+            })
+            .or_else(|| content.iter().next())?;
+
+        let example = media.get("example").cloned().or_else(|| {
+            media
+                .get("examples")
+                .and_then(Value::as_object)
+                .and_then(|examples| examples.values().next())
+                .map(|example| self.resolve(example, location))
+                .and_then(|example| example.get("value"))
+                .cloned()
+        });
+
+        let schema = media.get("schema").unwrap_or(&NULL);
+
+        match content_type.as_str() {
+            content_type if content_type.contains("json") => {
+                let example = example.unwrap_or_else(|| self.example(schema, location, 0));
+
+                Some(Body::Raw {
+                    content_type: Some(content_type.to_string()),
+                    text: serde_json::to_string_pretty(&example).unwrap_or_default(),
+                })
+            }
+            "application/x-www-form-urlencoded" => Some(Body::Form {
+                fields: self.form_fields(schema, location),
+            }),
+            "multipart/form-data" => Some(Body::Multipart {
+                fields: self.form_fields(schema, location),
+            }),
+            content_type => {
+                if example.is_none() && !content_type.starts_with("text/") {
+                    self.skip(format!("{location}: {content_type} example body"));
+                }
+
+                Some(Body::Raw {
+                    content_type: Some(content_type.to_string()),
+                    text: example.as_ref().map(text).unwrap_or_default(),
+                })
+            }
+        }
+    }
+
+    /// The body of a Swagger 2.0 operation, from its `body` or `formData`
+    /// parameters
+    fn swagger_body(
+        &mut self,
+        operation: &'a Value,
+        parameters: &[&'a Value],
+        location: &str,
+    ) -> Option<Body> {
+        let consumes: Vec<&str> = operation
+            .get("consumes")
+            .or_else(|| self.root.get("consumes"))
+            .and_then(Value::as_array)
+            .map(|types| types.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        let located = |wanted: &str| -> Vec<&'a Value> {
+            parameters
+                .iter()
+                .copied()
+                .filter(|parameter| parameter.get("in").and_then(Value::as_str) == Some(wanted))
+                .collect()
+        };
+
+        if let Some(body) = located("body").first() {
+            let example = self.example(body.get("schema").unwrap_or(&NULL), location, 0);
+
+            let content_type = consumes
+                .iter()
+                .find(|content_type| content_type.contains("json"))
+                .unwrap_or(&"application/json");
+
+            return Some(Body::Raw {
+                content_type: Some(content_type.to_string()),
+                text: serde_json::to_string_pretty(&example).unwrap_or_default(),
+            });
+        }
+
+        let form = located("formData");
+
+        if form.is_empty() {
+            return None;
+        }
+
+        let mut fields = Vec::new();
+
+        for parameter in form {
+            let name = parameter.get("name").map(text).unwrap_or_default();
+
+            if parameter.get("type").and_then(Value::as_str) == Some("file") {
+                self.skip(format!("{location}: file part '{name}' of the form"));
+                continue;
+            }
+
+            let value = self
+                .parameter_example(parameter, location)
+                .unwrap_or_default();
+
+            fields.push(KeyValue::new(name, value));
+        }
+
+        match consumes.contains(&"multipart/form-data") {
+            true => Some(Body::Multipart { fields }),
+            false => Some(Body::Form { fields }),
+        }
+    }
+
+    /// A response body and its content type, from the example of a media
+    /// type or made up from its schema
+    fn media_bodies(
+        &mut self,
+        name: &str,
+        content_type: &str,
+        media: &'a Value,
+        location: &str,
+    ) -> Vec<(String, Value)> {
+        if let Some(example) = media.get("example") {
+            return vec![(name.to_string(), example.clone())];
+        }
+
+        let named = media
+            .get("examples")
+            .and_then(Value::as_object)
+            .filter(|examples| !examples.is_empty());
+
+        if let Some(named) = named {
+            return named
+                .iter()
+                .map(|(key, example)| {
+                    let example = self.resolve(example, location);
+
+                    let name = example
+                        .get("summary")
+                        .map(text)
+                        .unwrap_or_else(|| key.clone());
+
+                    (name, example.get("value").cloned().unwrap_or_default())
+                })
+                .collect();
+        }
+
+        let body = match (content_type.contains("json"), media.get("schema")) {
+            (true, Some(schema)) => self.example(schema, location, 0),
+            _ => Value::Null,
+        };
+
+        vec![(name.to_string(), body)]
+    }
+
+    /// The responses declared with a status code, to be served by the mock
+    fn examples(&mut self, operation: &'a Value, location: &str) -> Vec<Example> {
+        let Some(responses) = operation.get("responses").and_then(Value::as_object) else {
+            return Vec::new();
+        };
+
+        let produces: Vec<&str> = operation
+            .get("produces")
+            .or_else(|| self.root.get("produces"))
+            .and_then(Value::as_array)
+            .map(|types| types.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        let mut examples = Vec::new();
+
+        for (code, response) in responses {
+            // Ranges like `4XX` and `default` have no status to respond with
+            let Ok(status) = code.parse::<u16>() else {
+                continue;
+            };
+
+            let response = self.resolve(response, location);
+
+            let name = response
+                .get("description")
+                .map(text)
+                .filter(|description| !description.trim().is_empty())
+                .unwrap_or_else(|| code.clone());
+
+            let bodies: Vec<(String, Option<String>, Value)> =
+                match self.is_swagger {
+                    true => {
+                        let example = response
+                            .get("examples")
+                            .and_then(Value::as_object)
+                            .and_then(|examples| {
+                                examples
+                                    .iter()
+                                    .find(|(content_type, _)| content_type.contains("json"))
+                                    .or_else(|| examples.iter().next())
+                            });
+
+                        let content_type = produces
+                            .iter()
+                            .find(|content_type| content_type.contains("json"))
+                            .or(produces.first())
+                            .unwrap_or(&"application/json")
+                            .to_string();
+
+                        match (example, response.get("schema")) {
+                            (Some((content_type, example)), _) => {
+                                vec![(name, Some(content_type.clone()), example.clone())]
+                            }
+                            (None, Some(schema)) => {
+                                let body = self.example(schema, location, 0);
+
+                                vec![(name, Some(content_type), body)]
+                            }
+                            (None, None) => vec![(name, None, Value::Null)],
+                        }
+                    }
+                    false => {
+                        let media = response.get("content").and_then(Value::as_object).and_then(
+                            |content| {
+                                content
+                                    .iter()
+                                    .find(|(content_type, _)| content_type.contains("json"))
+                                    .or_else(|| content.iter().next())
+                            },
+                        );
+
+                        match media {
+                            Some((content_type, media)) => self
+                                .media_bodies(&name, content_type, media, location)
+                                .into_iter()
+                                .map(|(name, body)| (name, Some(content_type.clone()), body))
+                                .collect(),
+                            None => vec![(name, None, Value::Null)],
+                        }
+                    }
+                };
+
+            examples.extend(bodies.into_iter().map(|(name, content_type, body)| {
+                Example {
+                    name,
+                    status,
+                    headers: content_type
+                        .map(|content_type| vec![KeyValue::new("Content-Type", content_type)])
+                        .unwrap_or_default(),
+                    body: match body {
+                        Value::Null => String::new(),
+                        Value::String(text) => text,
+                        body => serde_json::to_string_pretty(&body).unwrap_or_default(),
+                    },
+                    delay: None,
+                }
+            }));
+        }
+
+        examples
+    }
+
+    /// The auth of the first security requirement of an operation, sent
+    /// with variables for the credentials
+    fn auth(&mut self, operation: &'a Value, location: &str) -> Option<Auth> {
+        let requirements = operation
+            .get("security")
+            .or_else(|| self.root.get("security"))?
+            .as_array()?;
+
+        // An empty requirement makes the auth optional
+        let name = requirements.first()?.as_object()?.keys().next()?;
+
+        let schemes = self
+            .root
+            .pointer("/components/securitySchemes")
+            .or_else(|| self.root.get("securityDefinitions"));
+
+        let Some(scheme) = schemes.and_then(|schemes| schemes.get(name)) else {
+            self.skip(format!("{location}: undefined security scheme '{name}'"));
+            return None;
+        };
+
+        let scheme = self.resolve(scheme, location);
+        let kind = scheme
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+
+        let http_scheme = scheme
+            .get("scheme")
+            .and_then(Value::as_str)
+            .map(str::to_lowercase);
+
+        match (kind, http_scheme.as_deref()) {
+            ("basic", _) | ("http", Some("basic")) => Some(Auth::Basic {
+                username: self.credential("username"),
+                password: self.credential("password"),
+            }),
+            ("http", Some("bearer")) => Some(Auth::Bearer {
+                token: self.credential("token"),
+            }),
+            ("apiKey", _) => {
+                let location_in = match scheme.get("in").and_then(Value::as_str) {
+                    Some("header") => ApiKeyLocation::Header,
+                    Some("query") => ApiKeyLocation::Query,
+                    _ => {
+                        self.skip(format!("security scheme '{name}': cookie API keys"));
+                        return None;
+                    }
+                };
+
+                Some(Auth::ApiKey {
+                    name: scheme.get("name").map(text).unwrap_or_default(),
+                    value: self.credential(name),
+                    location: location_in,
+                })
+            }
+            ("oauth2" | "openIdConnect", _) => {
+                self.skip(format!(
+                    "security scheme '{name}': {kind} flows, set {{{{token}}}} to an access token"
+                ));
+
+                Some(Auth::Bearer {
+                    token: self.credential("token"),
+                })
+            }
+            (kind, scheme) => {
+                self.skip(format!(
+                    "security scheme '{name}': {kind} {}",
+                    scheme.unwrap_or_default()
+                ));
+                None
+            }
+        }
+    }
+
+    fn request(
+        &mut self,
+        path: &str,
+        method: &str,
+        path_item: &'a Value,
+        operation: &'a Value,
+    ) -> SavedRequest {
+        let method = method.to_uppercase();
+        let location = format!("{method} {path}");
+
+        if RequestMethod::from_str(&method).is_err() {
+            self.skip(format!("{location}: {method} requests can't be sent yet"));
+        }
+
+        let parameters = self.parameters(path_item, operation, &location);
+
+        let mut url = format!("{{{{baseUrl}}}}{path}");
+        let mut headers = Vec::new();
+        let mut query = Vec::new();
+
+        for parameter in &parameters {
+            let name = parameter.get("name").map(text).unwrap_or_default();
+            let required = parameter.get("required").and_then(Value::as_bool) == Some(true);
+            let example = self.parameter_example(parameter, &location);
+
+            match parameter.get("in").and_then(Value::as_str) {
+                Some("path") => {
+                    let value = example.unwrap_or_else(|| format!("{{{{{name}}}}}"));
+
+                    url = url.replace(&format!("{{{name}}}"), &value);
+                }
+                Some("query") => query.push(KeyValue {
+                    enabled: required || example.is_some(),
+                    value: example.unwrap_or_default(),
+                    name,
+                }),
+                // Described by the body and the security instead
+                Some("header")
+                    if ["accept", "content-type", "authorization"]
+                        .contains(&name.to_lowercase().as_str()) => {}
+                Some("header") => headers.push(KeyValue {
+                    enabled: required || example.is_some(),
+                    value: example.unwrap_or_default(),
+                    name,
+                }),
+                Some("cookie") => self.skip(format!("{location}: cookie parameter '{name}'")),
+                _ => {}
+            }
+        }
+
+        let body = match self.is_swagger {
+            true => self.swagger_body(operation, &parameters, &location),
+            false => self.request_body(operation, &location),
+        };
+
+        let name = operation
+            .get("summary")
+            .or_else(|| operation.get("operationId"))
+            .map(text)
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| location.clone());
+
+        SavedRequest {
+            name,
+            method,
+            url,
+            description: operation
+                .get("description")
+                .map(text)
+                .filter(|description| !description.trim().is_empty()),
+            headers,
+            query,
+            body,
+            auth: self.auth(operation, &location),
+            examples: self.examples(operation, &location),
+            operation: Some(location),
+        }
+    }
+
+    /// One environment per server, setting `baseUrl`
+    fn environments(&mut self) -> Vec<Environment> {
+        let servers: Vec<(String, String)> = match self.is_swagger {
+            true => {
+                let Some(host) = self.root.get("host").map(text) else {
+                    self.skip("servers: no host, set {{baseUrl}}".to_string());
+                    return Vec::new();
+                };
+
+                let base_path = self.root.get("basePath").map(text).unwrap_or_default();
+
+                let schemes: Vec<String> = self
+                    .root
+                    .get("schemes")
+                    .and_then(Value::as_array)
+                    .map(|schemes| schemes.iter().map(text).collect())
+                    .unwrap_or_else(|| vec!["https".to_string()]);
+
+                schemes
+                    .into_iter()
+                    .map(|scheme| {
+                        let url = format!("{scheme}://{host}{base_path}");
+
+                        (url.clone(), url)
+                    })
+                    .collect()
+            }
+            false => self
+                .root
+                .get("servers")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .map(|server| {
+                    let mut url = server.get("url").map(text).unwrap_or_default();
+
+                    let variables = server.get("variables").and_then(Value::as_object);
+
+                    for (name, variable) in variables.into_iter().flatten() {
+                        let default = variable.get("default").map(text).unwrap_or_default();
+
+                        url = url.replace(&format!("{{{name}}}"), &default);
+                    }
+
+                    let name = server
+                        .get("description")
+                        .map(text)
+                        .filter(|description| !description.trim().is_empty())
+                        .unwrap_or_else(|| url.clone());
+
+                    (name, url.trim_end_matches('/').to_string())
+                })
+                .collect(),
+        };
+
+        for (_, url) in &servers {
+            if !url.contains("://") {
+                self.skip(format!(
+                    "servers: relative URL '{url}', set {{{{baseUrl}}}}"
+                ));
+            }
+        }
+
+        servers
+            .into_iter()
+            .map(|(name, url)| Environment {
+                name,
+                variables: vec![KeyValue::new("baseUrl", url)],
+            })
+            .collect()
+    }
+}
+
+/// Convert an OpenAPI 3.x or Swagger 2.0 spec, in JSON or YAML
+pub fn import(path: &Path) -> Result<Import, String> {
+    let source = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+
+    // JSON is read as YAML too
+    let root: Value =
+        serde_yaml::from_str(&source).map_err(|e| format!("{}: {e}", path.display()))?;
+
+    let mut import = convert(&root).map_err(|e| format!("{}: {e}", path.display()))?;
+
+    import.collection.spec = Some(fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()));
+
+    Ok(import)
+}
+
+fn convert(root: &Value) -> Result<Import, String> {
+    let is_swagger = match (root.get("openapi"), root.get("swagger")) {
+        (Some(version), _) if text(version).starts_with('3') => false,
+        (_, Some(version)) if text(version).starts_with('2') => true,
+        _ => return Err("not an OpenAPI 3 or Swagger 2.0 specification".to_string()),
+    };
+
+    let mut spec = Spec {
+        root,
+        is_swagger,
+        skipped: Vec::new(),
+        credentials: Vec::new(),
+        expanding: Vec::new(),
+    };
+
+    let mut items: Vec<Item> = Vec::new();
+
+    let paths = root.get("paths").and_then(Value::as_object);
+
+    for (path, path_item) in paths.into_iter().flatten() {
+        let path_item = spec.resolve(path_item, path);
+
+        for method in METHODS {
+            let Some(operation) = path_item.get(method) else {
+                continue;
+            };
+
+            let request = spec.request(path, method, path_item, operation);
+
+            let tag = operation
+                .get("tags")
+                .and_then(Value::as_array)
+                .and_then(|tags| tags.first())
+                .map(text);
+
+            let Some(tag) = tag else {
+                items.push(Item::Request(Box::new(request)));
+                continue;
+            };
+
+            let folder = items.iter_mut().find_map(|item| match item {
+                Item::Folder(folder) if folder.name == tag => Some(folder),
+                _ => None,
+            });
+
+            match folder {
+                Some(folder) => folder.items.push(Item::Request(Box::new(request))),
+                None => items.push(Item::Folder(Folder {
+                    description: tag_description(root, &tag),
+                    name: tag,
+                    items: vec![Item::Request(Box::new(request))],
+                })),
+            }
+        }
+    }
+
+    let environments = spec.environments();
+
+    let base_url = environments
+        .first()
+        .and_then(|environment| environment.variables.first())
+        .map(|variable| variable.value.clone())
+        .unwrap_or_default();
+
+    let variables = std::iter::once(KeyValue::new("baseUrl", base_url))
+        .chain(
+            spec.credentials
+                .iter()
+                .map(|credential| KeyValue::new(credential, "")),
+        )
+        .collect();
+
+    let collection = Collection {
+        name: root
+            .pointer("/info/title")
+            .map(text)
+            .unwrap_or_else(|| "OpenAPI".to_string()),
+        description: root
+            .pointer("/info/description")
+            .map(text)
+            .filter(|description| !description.trim().is_empty()),
+        variables,
+        environments,
+        items,
+        spec: None,
+    };
+
+    Ok(Import {
+        collection,
+        skipped: spec.skipped,
+        notes: Vec::new(),
+    })
+}
+
+fn tag_description(root: &Value, tag: &str) -> Option<String> {
+    root.get("tags")?
+        .as_array()?
+        .iter()
+        .find(|entry| entry.get("name").and_then(Value::as_str) == Some(tag))?
+        .get("description")
+        .map(text)
+        .filter(|description| !description.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPENAPI: &str = r#"openapi: 3.0.0
+info:
+  title: Petstore
+servers:
+  - url: https://petstore.test/v1
+    description: Production
+  - url: /v1
+tags:
+  - name: pets
+    description: Everything about pets
+components:
+  securitySchemes:
+    key:
+      type: apiKey
+      in: header
+      name: X-Api-Key
+  schemas:
+    Pet:
+      type: object
+      required: [name]
+      properties:
+        id: {type: integer, format: int64}
+        name: {type: string}
+        born: {type: string, format: date}
+        tags: {type: array, items: {type: string}}
+security:
+  - key: []
+paths:
+  /pets/{petId}:
+    parameters:
+      - {name: petId, in: path, required: true, schema: {type: integer}, example: 3}
+    get:
+      tags: [pets]
+      operationId: getPet
+      parameters:
+        - {name: fields, in: query, schema: {type: string}, example: name}
+        - {name: X-Trace, in: header, schema: {type: string}}
+      responses:
+        '200':
+          description: A pet
+          content:
+            application/json:
+              schema: {$ref: '#/components/schemas/Pet'}
+  /pets:
+    post:
+      tags: [pets]
+      requestBody:
+        content:
+          application/json:
+            schema: {$ref: '#/components/schemas/Pet'}
+      responses:
+        '201': {description: Created}
+  /health:
+    get:
+      security: []
+      responses:
+        '200': {description: OK}
+"#;
+
+    const SWAGGER: &str = r#"{
+        "swagger": "2.0",
+        "info": {"title": "Legacy"},
+        "host": "legacy.test",
+        "basePath": "/api",
+        "schemes": ["https"],
+        "securityDefinitions": {"basic": {"type": "basic"}},
+        "security": [{"basic": []}],
+        "paths": {
+            "/login": {
+                "post": {
+                    "consumes": ["application/x-www-form-urlencoded"],
+                    "parameters": [
+                        {"name": "user", "in": "formData", "type": "string"},
+                        {"name": "remember", "in": "formData", "type": "boolean"}
+                    ],
+                    "responses": {"200": {"description": "OK"}}
+                }
+            }
+        }
+    }"#;
+
+    const PET: &str = r#"{
+  "id": 0,
+  "name": "string",
+  "born": "2024-01-01",
+  "tags": [
+    "string"
+  ]
+}"#;
+
+    fn import(text: &str) -> Import {
+        convert(&serde_yaml::from_str(text).unwrap()).unwrap()
+    }
+
+    fn request(item: &Item) -> &SavedRequest {
+        match item {
+            Item::Request(request) => request,
+            Item::Folder(folder) => panic!("{} is a folder", folder.name),
+        }
+    }
+
+    #[test]
+    fn groups_operations_by_tag() {
+        let collection = import(OPENAPI).collection;
+
+        let Item::Folder(pets) = &collection.items[0] else {
+            panic!("pets is not a folder");
+        };
+
+        assert_eq!(pets.name, "pets");
+        assert_eq!(pets.description.as_deref(), Some("Everything about pets"));
+        assert_eq!(request(&pets.items[0]).name, "getPet");
+        assert_eq!(request(&pets.items[1]).name, "POST /pets");
+        assert_eq!(request(&collection.items[1]).name, "GET /health");
+    }
+
+    #[test]
+    fn fills_parameters_and_examples() {
+        let collection = import(OPENAPI).collection;
+
+        let Item::Folder(pets) = &collection.items[0] else {
+            panic!("pets is not a folder");
+        };
+        let get = request(&pets.items[0]);
+
+        assert_eq!(get.url, "{{baseUrl}}/pets/3");
+        assert_eq!(get.operation.as_deref(), Some("GET /pets/{petId}"));
+        assert_eq!(get.query, vec![KeyValue::new("fields", "name")]);
+        assert_eq!(get.headers[0].name, "X-Trace");
+        assert!(!get.headers[0].enabled);
+        assert_eq!(get.examples[0].body, PET);
+
+        assert_eq!(
+            request(&pets.items[1]).body,
+            Some(Body::Raw {
+                content_type: Some("application/json".to_string()),
+                text: PET.to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn maps_security_schemes_to_auth() {
+        let collection = import(OPENAPI).collection;
+
+        let Item::Folder(pets) = &collection.items[0] else {
+            panic!("pets is not a folder");
+        };
+
+        assert_eq!(
+            request(&pets.items[0]).auth,
+            Some(Auth::ApiKey {
+                name: "X-Api-Key".to_string(),
+                value: "{{key}}".to_string(),
+                location: ApiKeyLocation::Header,
+            })
+        );
+        assert_eq!(request(&collection.items[1]).auth, None);
+        assert_eq!(collection.variables[1], KeyValue::new("key", ""));
+    }
+
+    #[test]
+    fn turns_servers_into_environments() {
+        let import = import(OPENAPI);
+        let environments = &import.collection.environments;
+
+        assert_eq!(environments[0].name, "Production");
+        assert_eq!(
+            environments[0].variables,
+            vec![KeyValue::new("baseUrl", "https://petstore.test/v1")]
+        );
+        assert_eq!(
+            import.skipped,
+            vec!["servers: relative URL '/v1', set {{baseUrl}}"]
+        );
+    }
+
+    #[test]
+    fn imports_swagger_forms_and_basic_auth() {
+        let collection = import(SWAGGER).collection;
+        let login = request(&collection.items[0]);
+
+        assert_eq!(
+            collection.variables[0],
+            KeyValue::new("baseUrl", "https://legacy.test/api")
+        );
+        assert_eq!(
+            login.body,
+            Some(Body::Form {
+                fields: vec![KeyValue::new("user", ""), KeyValue::new("remember", "")],
+            })
+        );
+        assert_eq!(
+            login.auth,
+            Some(Auth::Basic {
+                username: "{{username}}".to_string(),
+                password: "{{password}}".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        assert_eq!(
+            convert(&serde_yaml::from_str("name: not a spec\n").unwrap()).err(),
+            Some("not an OpenAPI 3 or Swagger 2.0 specification".to_string())
+        );
+    }
+}
@@ -0,0 +1,678 @@
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use percent_encoding::percent_decode_str;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    app::RequestMethod,
+    collection::{
+        ApiKeyLocation, Auth, Body, Collection, Environment, Example, Folder, Item, KeyValue,
+        SavedRequest,
+    },
+};
+
+use super::{location, read_json, replace_path_variables, text, Import};
+
+/// Descriptions are either a string or an object with the content
+fn description(value: Option<&Value>) -> Option<String> {
+    let value = value?;
+
+    value
+        .as_str()
+        .or_else(|| value.get("content").and_then(Value::as_str))
+        .filter(|text| !text.trim().is_empty())
+        .map(str::to_string)
+}
+
+/// `[{"key": .., "value": .., "disabled": ..}]`
+fn key_values(value: Option<&Value>) -> Vec<KeyValue> {
+    value
+        .and_then(Value::as_array)
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|entry| {
+                    let name = entry.get("key").and_then(text)?;
+
+                    Some(KeyValue {
+                        name,
+                        value: entry.get("value").and_then(text).unwrap_or_default(),
+                        enabled: !entry
+                            .get("disabled")
+                            .and_then(Value::as_bool)
+                            .unwrap_or(false),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Headers are a list of entries or raw `Name: value` lines
+fn headers(value: Option<&Value>) -> Vec<KeyValue> {
+    match value {
+        Some(Value::String(raw)) => raw
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| KeyValue::new(name.trim(), value.trim()))
+            .collect(),
+        value => key_values(value),
+    }
+}
+
+/// The URL without its query, and the query parameters
+fn url(value: Option<&Value>) -> (String, Vec<KeyValue>) {
+    let Some(value) = value else {
+        return (String::new(), Vec::new());
+    };
+
+    let raw = match value {
+        Value::String(raw) => raw.clone(),
+        value => value
+            .get("raw")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| url_from_parts(value)),
+    };
+
+    let raw = raw.split('#').next().unwrap_or_default();
+
+    let (base, query_string) = raw.split_once('?').unwrap_or((raw, ""));
+
+    let query = match value.get("query") {
+        Some(query) => key_values(Some(query)),
+        None => query_string
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+                KeyValue::new(decode(name), decode(value))
+            })
+            .collect(),
+    };
+
+    (
+        replace_path_variables(base, &key_values(value.get("variable"))),
+        query,
+    )
+}
+
+fn decode(text: &str) -> String {
+    percent_decode_str(&text.replace('+', " "))
+        .decode_utf8_lossy()
+        .to_string()
+}
+
+/// Build the URL of an object without `raw`
+fn url_from_parts(url: &Value) -> String {
+    let join = |value: Option<&Value>, separator: &str| match value {
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(text)
+            .collect::<Vec<_>>()
+            .join(separator),
+        Some(value) => text(value).unwrap_or_default(),
+        None => String::new(),
+    };
+
+    let mut result = String::new();
+
+    if let Some(protocol) = url.get("protocol").and_then(Value::as_str) {
+        result.push_str(&format!("{protocol}://"));
+    }
+
+    result.push_str(&join(url.get("host"), "."));
+
+    if let Some(port) = url.get("port").and_then(text) {
+        result.push_str(&format!(":{port}"));
+    }
+
+    let path = join(url.get("path"), "/");
+
+    if !path.is_empty() {
+        result.push('/');
+        result.push_str(&path);
+    }
+
+    result
+}
+
+/// A parameter of an auth, a list of entries in v2.1 and an object in v2.0
+fn auth_param(auth: &Value, kind: &str, key: &str) -> Option<String> {
+    match auth.get(kind)? {
+        Value::Array(entries) => entries
+            .iter()
+            .find(|entry| entry.get("key").and_then(Value::as_str) == Some(key))
+            .and_then(|entry| entry.get("value"))
+            .and_then(text),
+        Value::Object(params) => params.get(key).and_then(text),
+        _ => None,
+    }
+}
+
+/// The auth of an item, `inherited` from its folders when it has none
+fn auth(
+    value: Option<&Value>,
+    inherited: &Option<Auth>,
+    location: &str,
+    skipped: &mut Vec<String>,
+) -> Option<Auth> {
+    let Some(value) = value.filter(|value| !value.is_null()) else {
+        return inherited.clone();
+    };
+
+    let kind = value
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or("noauth");
+
+    match kind {
+        "noauth" => None,
+        "basic" => Some(Auth::Basic {
+            username: auth_param(value, kind, "username").unwrap_or_default(),
+            password: auth_param(value, kind, "password").unwrap_or_default(),
+        }),
+        "bearer" => Some(Auth::Bearer {
+            token: auth_param(value, kind, "token").unwrap_or_default(),
+        }),
+        "apikey" => Some(Auth::ApiKey {
+            name: auth_param(value, kind, "key").unwrap_or_default(),
+            value: auth_param(value, kind, "value").unwrap_or_default(),
+            location: match auth_param(value, kind, "in").as_deref() {
+                Some("query") => ApiKeyLocation::Query,
+                _ => ApiKeyLocation::Header,
+            },
+        }),
+        kind => {
+            skipped.push(format!("{location}: {kind} auth"));
+            None
+        }
+    }
+}
+
+fn raw_content_type(language: Option<&str>) -> Option<String> {
+    let content_type = match language? {
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "html" => "text/html",
+        "javascript" => "application/javascript",
+        "text" => "text/plain",
+        _ => return None,
+    };
+
+    Some(content_type.to_string())
+}
+
+fn body(value: Option<&Value>, location: &str, skipped: &mut Vec<String>) -> Option<Body> {
+    let value = value.filter(|value| !value.is_null())?;
+
+    if value.get("disabled").and_then(Value::as_bool) == Some(true) {
+        return None;
+    }
+
+    match value.get("mode").and_then(Value::as_str)? {
+        "raw" => {
+            let text = value.get("raw").and_then(Value::as_str).unwrap_or_default();
+
+            (!text.is_empty()).then(|| Body::Raw {
+                content_type: raw_content_type(
+                    value
+                        .pointer("/options/raw/language")
+                        .and_then(Value::as_str),
+                ),
+                text: text.to_string(),
+            })
+        }
+        "urlencoded" => Some(Body::Form {
+            fields: key_values(value.get("urlencoded")),
+        }),
+        "formdata" => {
+            let parts = value
+                .get("formdata")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+
+            let (files, texts): (Vec<_>, Vec<_>) = parts
+                .into_iter()
+                .partition(|part| part.get("type").and_then(Value::as_str) == Some("file"));
+
+            for file in &files {
+                let name = file.get("key").and_then(text).unwrap_or_default();
+
+                skipped.push(format!("{location}: file part '{name}' of the form"));
+            }
+
+            Some(Body::Multipart {
+                fields: key_values(Some(&Value::Array(texts))),
+            })
+        }
+        "graphql" => {
+            let graphql = value.get("graphql")?;
+
+            let variables = match graphql.get("variables") {
+                Some(Value::String(text)) if !text.trim().is_empty() => {
+                    serde_json::from_str(text).unwrap_or(Value::Object(Map::new()))
+                }
+                Some(Value::Object(variables)) => Value::Object(variables.clone()),
+                _ => Value::Object(Map::new()),
+            };
+
+            let body = json!({
+                "query": graphql.get("query").and_then(Value::as_str).unwrap_or_default(),
+                "variables": variables,
+            });
+
+            Some(Body::Raw {
+                content_type: Some("application/json".to_string()),
+                text: serde_json::to_string_pretty(&body).unwrap_or_default(),
+            })
+        }
+        mode => {
+            skipped.push(format!("{location}: {mode} body"));
+            None
+        }
+    }
+}
+
+/// Report the scripts of an item, which can't run in tuapi
+fn scripts(item: &Value, location: &str, skipped: &mut Vec<String>) {
+    let Some(events) = item.get("event").and_then(Value::as_array) else {
+        return;
+    };
+
+    for event in events {
+        let has_script = match event.pointer("/script/exec") {
+            Some(Value::Array(lines)) => lines
+                .iter()
+                .any(|line| line.as_str().is_some_and(|line| !line.trim().is_empty())),
+            Some(Value::String(script)) => !script.trim().is_empty(),
+            _ => false,
+        };
+
+        if !has_script {
+            continue;
+        }
+
+        let kind = match event.get("listen").and_then(Value::as_str) {
+            Some("prerequest") => "pre-request script",
+            Some("test") => "test script",
+            _ => "script",
+        };
+
+        skipped.push(format!("{location}: {kind}"));
+    }
+}
+
+fn request(
+    item: &Value,
+    name: String,
+    folders: &[String],
+    inherited: &Option<Auth>,
+    skipped: &mut Vec<String>,
+) -> SavedRequest {
+    let location = location(folders, &name);
+    let value = item.get("request").unwrap_or(&Value::Null);
+
+    // A request can be just its URL
+    let (url, query) = match value {
+        Value::String(_) => url(Some(value)),
+        value => url(value.get("url")),
+    };
+
+    let method = value
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or("GET")
+        .to_uppercase();
+
+    if RequestMethod::from_str(&method).is_err() {
+        skipped.push(format!("{location}: {method} requests can't be sent yet"));
+    }
+
+    scripts(item, &location, skipped);
+
+    SavedRequest {
+        name,
+        method,
+        url,
+        description: description(value.get("description")),
+        headers: headers(value.get("header")),
+        query,
+        body: body(value.get("body"), &location, skipped),
+        auth: auth(value.get("auth"), inherited, &location, skipped),
+        operation: None,
+        examples: examples(item.get("response")),
+    }
+}
+
+/// The responses saved with a request
+fn examples(value: Option<&Value>) -> Vec<Example> {
+    let Some(responses) = value.and_then(Value::as_array) else {
+        return Vec::new();
+    };
+
+    responses
+        .iter()
+        .map(|response| Example {
+            name: response
+                .get("name")
+                .and_then(text)
+                .unwrap_or_else(|| "Example".to_string()),
+            status: response
+                .get("code")
+                .and_then(Value::as_u64)
+                .and_then(|code| u16::try_from(code).ok())
+                .unwrap_or(200),
+            headers: headers(response.get("header")),
+            body: response.get("body").and_then(text).unwrap_or_default(),
+            delay: None,
+        })
+        .collect()
+}
+
+fn items(
+    values: Option<&Value>,
+    folders: &[String],
+    inherited: &Option<Auth>,
+    skipped: &mut Vec<String>,
+) -> Vec<Item> {
+    let Some(values) = values.and_then(Value::as_array) else {
+        return Vec::new();
+    };
+
+    values
+        .iter()
+        .map(|item| {
+            let name = item
+                .get("name")
+                .and_then(text)
+                .unwrap_or_else(|| "Untitled".to_string());
+
+            if item.get("request").is_some() {
+                return Item::Request(Box::new(request(item, name, folders, inherited, skipped)));
+            }
+
+            let location = location(folders, &name);
+
+            scripts(item, &location, skipped);
+
+            let auth = auth(item.get("auth"), inherited, &location, skipped);
+
+            let mut path = folders.to_vec();
+            path.push(name.clone());
+
+            Item::Folder(Folder {
+                name,
+                description: description(item.get("description")),
+                items: items(item.get("item"), &path, &auth, skipped),
+            })
+        })
+        .collect()
+}
+
+fn environment(path: &Path) -> Result<Environment, String> {
+    let value = read_json(path)?;
+
+    let file_stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    convert_environment(&value, file_stem)
+        .ok_or_else(|| format!("{}: not a Postman environment", path.display()))
+}
+
+/// An environment export, named after its file when it has no name
+fn convert_environment(value: &Value, file_stem: String) -> Option<Environment> {
+    let values = value.get("values").and_then(Value::as_array)?;
+
+    let name = value.get("name").and_then(text).unwrap_or(file_stem);
+
+    let variables = values
+        .iter()
+        .filter_map(|entry| {
+            Some(KeyValue {
+                name: entry.get("key").and_then(text)?,
+                value: entry.get("value").and_then(text).unwrap_or_default(),
+                enabled: entry
+                    .get("enabled")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(true),
+            })
+        })
+        .collect();
+
+    Some(Environment { name, variables })
+}
+
+/// Convert a Postman v2.0 or v2.1 collection export, and environment
+/// exports to go with it
+pub fn import(path: &Path, environments: &[PathBuf]) -> Result<Import, String> {
+    let value = read_json(path)?;
+
+    let environments = environments
+        .iter()
+        .map(|path| environment(path))
+        .collect::<Result<_, _>>()?;
+
+    convert(&value, environments).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn convert(value: &Value, environments: Vec<Environment>) -> Result<Import, String> {
+    let is_v2 = value
+        .pointer("/info/schema")
+        .and_then(Value::as_str)
+        .is_some_and(|schema| schema.contains("/collection/v2."));
+
+    if !is_v2 {
+        return Err("not a Postman v2.1 collection, export it again as v2.1".to_string());
+    }
+
+    let mut skipped = Vec::new();
+
+    let name = value
+        .pointer("/info/name")
+        .and_then(text)
+        .unwrap_or_else(|| "Postman".to_string());
+
+    scripts(value, &name, &mut skipped);
+
+    let auth = auth(value.get("auth"), &None, &name, &mut skipped);
+
+    let collection = Collection {
+        description: description(value.pointer("/info/description")),
+        variables: key_values(value.get("variable")),
+        environments,
+        items: items(value.get("item"), &[], &auth, &mut skipped),
+        spec: None,
+        name,
+    };
+
+    Ok(Import {
+        collection,
+        skipped,
+        notes: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn collection() -> Value {
+        json!({
+            "info": {
+                "name": "Shop",
+                "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+            },
+            "auth": {"type": "bearer", "bearer": [{"key": "token", "value": "{{token}}"}]},
+            "variable": [{"key": "host", "value": "https://shop.test"}],
+            "item": [
+                {
+                    "name": "Users",
+                    "auth": {"type": "basic", "basic": [
+                        {"key": "username", "value": "admin"},
+                        {"key": "password", "value": "secret"}
+                    ]},
+                    "item": [{
+                        "name": "Get user",
+                        "event": [{"listen": "test", "script": {"exec": ["pm.test()"]}}],
+                        "request": {
+                            "method": "get",
+                            "header": [
+                                {"key": "Accept", "value": "application/json", "disabled": true}
+                            ],
+                            "url": {
+                                "raw": "{{host}}/users/:id?expand=all#top",
+                                "variable": [{"key": "id", "value": "7"}]
+                            }
+                        }
+                    }]
+                },
+                {
+                    "name": "Create order",
+                    "request": {
+                        "method": "POST",
+                        "url": "{{host}}/orders?note=a%20b",
+                        "body": {
+                            "mode": "raw",
+                            "raw": "{\"id\": 1}",
+                            "options": {"raw": {"language": "json"}}
+                        }
+                    },
+                    "response": [{"name": "Created", "code": 201, "body": "{}"}]
+                },
+                {
+                    "name": "Upload",
+                    "request": {
+                        "method": "PUT",
+                        "url": "{{host}}/files",
+                        "body": {"mode": "formdata", "formdata": [
+                            {"key": "title", "value": "report", "type": "text"},
+                            {"key": "file", "src": "report.pdf", "type": "file"}
+                        ]}
+                    }
+                }
+            ]
+        })
+    }
+
+    fn import() -> Import {
+        convert(&collection(), Vec::new()).unwrap()
+    }
+
+    fn request(item: &Item) -> &SavedRequest {
+        match item {
+            Item::Request(request) => request,
+            Item::Folder(folder) => panic!("{} is a folder", folder.name),
+        }
+    }
+
+    fn folder(item: &Item) -> &Folder {
+        match item {
+            Item::Folder(folder) => folder,
+            Item::Request(request) => panic!("{} is a request", request.name),
+        }
+    }
+
+    #[test]
+    fn imports_folders_and_requests() {
+        let collection = import().collection;
+
+        assert_eq!(collection.name, "Shop");
+        assert_eq!(
+            collection.variables,
+            vec![KeyValue::new("host", "https://shop.test")]
+        );
+
+        let user = request(&folder(&collection.items[0]).items[0]);
+
+        assert_eq!(user.method, "GET");
+        assert_eq!(user.url, "{{host}}/users/7");
+        assert_eq!(user.query, vec![KeyValue::new("expand", "all")]);
+        assert!(!user.headers[0].enabled);
+
+        let order = request(&collection.items[1]);
+
+        assert_eq!(order.query, vec![KeyValue::new("note", "a b")]);
+        assert_eq!(order.examples[0].name, "Created");
+        assert_eq!(order.examples[0].status, 201);
+        assert_eq!(
+            order.body,
+            Some(Body::Raw {
+                content_type: Some("application/json".to_string()),
+                text: "{\"id\": 1}".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn inherits_auth_from_folders() {
+        let collection = import().collection;
+
+        assert_eq!(
+            request(&folder(&collection.items[0]).items[0]).auth,
+            Some(Auth::Basic {
+                username: "admin".to_string(),
+                password: "secret".to_string(),
+            })
+        );
+        assert_eq!(
+            request(&collection.items[1]).auth,
+            Some(Auth::Bearer {
+                token: "{{token}}".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn reports_what_was_skipped() {
+        let import = import();
+
+        assert_eq!(
+            request(&import.collection.items[2]).body,
+            Some(Body::Multipart {
+                fields: vec![KeyValue::new("title", "report")],
+            })
+        );
+        assert_eq!(
+            import.skipped,
+            vec![
+                "Users / Get user: test script",
+                "Upload: file part 'file' of the form",
+            ]
+        );
+    }
+
+    #[test]
+    fn converts_environments() {
+        let environment = json!({
+            "values": [
+                {"key": "token", "value": "abc", "enabled": true},
+                {"key": "unused", "value": "x", "enabled": false}
+            ]
+        });
+
+        let environment = convert_environment(&environment, "staging".to_string()).unwrap();
+
+        assert_eq!(environment.name, "staging");
+        assert_eq!(environment.variables[0], KeyValue::new("token", "abc"));
+        assert!(!environment.variables[1].enabled);
+        assert!(convert_environment(&json!({"name": "x"}), String::new()).is_none());
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        assert_eq!(
+            convert(&json!({"info": {"schema": "v1"}}), Vec::new()).err(),
+            Some("not a Postman v2.1 collection, export it again as v2.1".to_string())
+        );
+    }
+}
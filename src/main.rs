@@ -1,16 +1,38 @@
 mod app;
 mod cli;
+mod clipboard;
+mod collection;
+mod config;
+mod content_type;
+mod contract;
+mod diff;
 mod event;
+mod external;
+mod har;
+mod http_file;
+mod import;
+mod mock;
+mod plural;
+mod pretty;
 mod request;
 mod ui;
+mod validation;
 
-use app::{App, InputMode};
+use app::App;
 use clap::Parser;
+use cli::Command;
+use collection::Collection;
+use config::Config;
+use contract::Contract;
 use crossterm::{
-    event::{self as crossterm_event, Event, KeyCode},
+    event::{
+        self as crossterm_event, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste,
+        EnableMouseCapture, Event,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use http_file::HttpFile;
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::{
     io::{self, Error, Stdout},
@@ -21,11 +43,112 @@ use std::{
 async fn main() -> Result<(), Error> {
     let cli = cli::Cli::parse();
 
-    let mut terminal = setup_terminal()?;
+    if let Some(command) = cli.command {
+        let result = match command {
+            Command::Import { format } => import::run(format),
+            Command::Run {
+                collection,
+                environment,
+                har,
+            } => collection::run::run(&collection, environment.as_deref(), har.as_deref()).await,
+            Command::Mock {
+                collection,
+                environment,
+                host,
+                port,
+            } => mock::run(&collection, environment.as_deref(), &host, port).await,
+        };
+
+        if let Err(e) = result {
+            eprintln!("error: {e}");
+            std::process::exit(1);
+        }
+
+        return Ok(());
+    }
 
     let mut app = App::default();
 
-    if let Some(url) = cli.url {
+    if let Some(name) = cli.collection {
+        let Some(path) = collection::find(&name) else {
+            eprintln!("error: no collection named {name}");
+            std::process::exit(1);
+        };
+
+        match Collection::load(&path) {
+            Ok(collection) => app.collection = Some(collection),
+            Err(e) => {
+                eprintln!("error: {e}");
+                std::process::exit(1);
+            }
+        }
+
+        let spec = app
+            .collection
+            .as_ref()
+            .and_then(|collection| collection.spec_path(&path));
+
+        if let Some(spec) = spec {
+            match Contract::load(&spec) {
+                Ok(contract) => app.contract = Some(contract),
+                Err(e) => {
+                    app.status_message = Some(format!("Responses aren't checked, {e}"));
+                }
+            }
+        }
+
+        app.collection_path = Some(path);
+    }
+
+    let file = cli
+        .url
+        .as_deref()
+        .map(std::path::Path::new)
+        .filter(|path| http_file::is_http_file(path));
+
+    if let Some(path) = file {
+        match HttpFile::load(path) {
+            Ok(file) => app.http_file = Some(file),
+            Err(e) => {
+                eprintln!("error: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let capture = cli
+        .url
+        .as_deref()
+        .map(std::path::Path::new)
+        .filter(|path| har::is_har_file(path));
+
+    if let Some(path) = capture {
+        match har::load(path) {
+            Ok(exchanges) => app.history = exchanges,
+            Err(e) => {
+                eprintln!("error: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let mut terminal = setup_terminal()?;
+
+    let (config, problems) = Config::load();
+
+    app.keymap = config.keymap;
+    app.theme = config.theme;
+    app.layout = config.layout;
+
+    if !problems.is_empty() {
+        app.status_message = Some(format!("Config: {}", problems.join("; ")));
+    }
+
+    if app.http_file.is_some() {
+        event::collection::open_requests(&mut app);
+    } else if capture.is_some() {
+        event::collection::open_history(&mut app);
+    } else if let Some(url) = cli.url {
         app.endpoint.text = url;
     }
 
@@ -50,43 +173,51 @@ async fn main() -> Result<(), Error> {
 fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>, Error> {
     let mut stdout = io::stdout();
     enable_raw_mode()?;
-    execute!(stdout, EnterAlternateScreen)?;
-    Ok(Terminal::new(CrosstermBackend::new(stdout))?)
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableBracketedPaste,
+        EnableMouseCapture
+    )?;
+    Terminal::new(CrosstermBackend::new(stdout))
 }
 
 fn restore_terminal() -> Result<(), Error> {
     disable_raw_mode()?;
-    execute!(io::stdout(), LeaveAlternateScreen)?;
+    execute!(
+        io::stdout(),
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        LeaveAlternateScreen
+    )?;
     Ok(())
 }
 
 async fn run(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App) -> io::Result<()> {
     loop {
+        if app.needs_redraw {
+            terminal.clear()?;
+            app.needs_redraw = false;
+        }
+
         terminal.draw(|frame| ui::draw(frame, app))?;
 
         if let Ok(res) = app.res_rx.try_recv() {
-            app.response = res;
-            app.is_loading = false;
+            app.receive_response(res);
         }
 
         if crossterm_event::poll(Duration::from_millis(250))? {
-            if let Event::Key(key) = crossterm_event::read()? {
-                match app.input_mode {
-                    InputMode::Normal => match key.code {
-                        KeyCode::Char('q') => match app.popup {
-                            Some(_) => {
-                                app.popup = None;
-                            }
-                            None => {
-                                return Ok(());
-                            }
-                        },
-                        _ => {}
-                    },
-                    _ => {}
-                }
+            match crossterm_event::read()? {
+                Event::Key(key) => {
+                    event::handle_input(app, key).await;
 
-                event::handle_input(app, key).await;
+                    if app.should_quit {
+                        return Ok(());
+                    }
+                }
+                Event::Paste(text) => event::handle_paste(app, &text),
+                Event::Mouse(mouse) => event::mouse::handle_mouse(app, mouse),
+                _ => {}
             }
         }
     }
@@ -0,0 +1,240 @@
+pub mod routes;
+
+use std::{
+    convert::Infallible,
+    io,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use hyper::{
+    header::{HeaderName, HeaderValue, CONTENT_TYPE},
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use serde_json::json;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use crate::{
+    collection::{find, Collection},
+    config::{theme::Theme, Config},
+    ui,
+};
+
+use routes::{Match, Route};
+
+/// Requests kept in the log, the oldest are dropped
+const LOG_LIMIT: usize = 1000;
+
+/// A request the mock answered
+pub struct Hit {
+    pub time: DateTime<Utc>,
+    pub method: String,
+    /// With the query
+    pub path: String,
+    pub status: u16,
+    /// The request and example responded with, or why none was
+    pub detail: String,
+    pub took: Duration,
+}
+
+/// What the mock panel shows
+pub struct Mock {
+    pub name: String,
+    pub address: SocketAddr,
+    pub routes: Arc<Vec<Route>>,
+    pub hits: Vec<Hit>,
+    pub theme: Theme,
+}
+
+fn error(status: StatusCode, message: String) -> (Response<Body>, String) {
+    let body = json!({ "error": message }).to_string();
+
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+    (response, message)
+}
+
+async fn respond(routes: &[Route], request: &Request<Body>) -> (Response<Body>, String) {
+    let method = request.method().as_str();
+    let path = request.uri().path();
+
+    let route = match routes::find(routes, method, path) {
+        Match::Found(route) => route,
+        Match::MethodNotAllowed => {
+            return error(
+                StatusCode::METHOD_NOT_ALLOWED,
+                format!("no {method} request for {path} in the collection"),
+            )
+        }
+        Match::NotFound => {
+            return error(
+                StatusCode::NOT_FOUND,
+                format!("no request for {path} in the collection"),
+            )
+        }
+    };
+
+    let prefer = request
+        .headers()
+        .get("prefer")
+        .and_then(|value| value.to_str().ok());
+
+    let Some(example) = route.example(prefer) else {
+        let message = match (route.examples.is_empty(), prefer) {
+            (false, Some(prefer)) => format!("no example of {} matches '{prefer}'", route.label),
+            _ => format!("no example saved for {}", route.label),
+        };
+
+        return error(StatusCode::NOT_IMPLEMENTED, message);
+    };
+
+    if let Some(delay) = example.delay {
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+    }
+
+    let mut response = Response::new(Body::from(example.body.clone()));
+    *response.status_mut() = StatusCode::from_u16(example.status).unwrap_or(StatusCode::OK);
+
+    for header in example.headers.iter().filter(|header| header.enabled) {
+        let name = HeaderName::from_bytes(header.name.as_bytes());
+        let value = HeaderValue::from_str(&header.value);
+
+        if let (Ok(name), Ok(value)) = (name, value) {
+            response.headers_mut().append(name, value);
+        }
+    }
+
+    (response, format!("{} · {}", route.label, example.name))
+}
+
+/// Serve the examples of a collection, logging the requests in a panel
+/// until `q` is pressed
+pub async fn run(
+    name: &str,
+    environment: Option<&str>,
+    host: &str,
+    port: u16,
+) -> Result<(), String> {
+    let path = find(name).ok_or(format!("no collection named {name}"))?;
+    let collection = Collection::load(&path)?;
+
+    let environment = environment
+        .map(|environment| collection.find_environment(environment))
+        .transpose()?;
+
+    let routes = Arc::new(Route::all(&collection, &collection.variables(environment)));
+
+    let address: SocketAddr = format!("{host}:{port}")
+        .parse()
+        .map_err(|e| format!("{host}:{port}: {e}"))?;
+
+    let (hit_tx, hit_rx) = unbounded_channel();
+
+    let server = Server::try_bind(&address)
+        .map_err(|e| format!("{address}: {e}"))?
+        .serve(make_service_fn({
+            let routes = routes.clone();
+
+            move |_| {
+                let routes = routes.clone();
+                let hit_tx: UnboundedSender<Hit> = hit_tx.clone();
+
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                        let routes = routes.clone();
+                        let hit_tx = hit_tx.clone();
+
+                        async move {
+                            let started = Instant::now();
+                            let time = Utc::now();
+
+                            let (response, detail) = respond(&routes, &request).await;
+
+                            let _ = hit_tx.send(Hit {
+                                time,
+                                method: request.method().to_string(),
+                                path: request
+                                    .uri()
+                                    .path_and_query()
+                                    .map(|path| path.to_string())
+                                    .unwrap_or_default(),
+                                status: response.status().as_u16(),
+                                detail,
+                                took: started.elapsed(),
+                            });
+
+                            Ok::<_, Infallible>(response)
+                        }
+                    }))
+                }
+            }
+        }));
+
+    let address = server.local_addr();
+
+    tokio::spawn(server);
+
+    let (config, _) = Config::load();
+
+    let mut mock = Mock {
+        name: collection.name.clone(),
+        address,
+        routes,
+        hits: Vec::new(),
+        theme: config.theme,
+    };
+
+    show(&mut mock, hit_rx).map_err(|e| e.to_string())
+}
+
+fn show(mock: &mut Mock, mut hit_rx: UnboundedReceiver<Hit>) -> io::Result<()> {
+    let mut terminal = crate::setup_terminal()?;
+
+    let original_hook = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |panic| {
+        crate::restore_terminal().unwrap();
+        original_hook(panic);
+    }));
+
+    loop {
+        while let Ok(hit) = hit_rx.try_recv() {
+            mock.hits.push(hit);
+
+            if mock.hits.len() > LOG_LIMIT {
+                mock.hits.remove(0);
+            }
+        }
+
+        terminal.draw(|frame| ui::mock::draw(frame, mock))?;
+
+        if !event::poll(Duration::from_millis(100))? {
+            continue;
+        }
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+
+        if key.kind == KeyEventKind::Release {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => break,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+            KeyCode::Char('c') => mock.hits.clear(),
+            _ => {}
+        }
+    }
+
+    crate::restore_terminal()
+}
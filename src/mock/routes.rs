@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+
+use crate::collection::{substitute, Collection, Example};
+
+enum Segment {
+    Literal(String),
+    /// `{id}`, `:id` or a variable without a value, matching any segment
+    Parameter,
+}
+
+/// A saved request served by the mock, by its method and path
+pub struct Route {
+    pub method: String,
+    /// Like `/users/{id}`
+    pub path: String,
+    pub label: String,
+    pub examples: Vec<Example>,
+    segments: Vec<Segment>,
+}
+
+/// The path of a URL, whether it has a scheme and host or starts with a
+/// variable like `{{baseUrl}}`
+fn url_path(url: &str) -> &str {
+    let url = url.split(['?', '#']).next().unwrap_or_default();
+
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest,
+        None if url.starts_with('/') => return url,
+        None => url,
+    };
+
+    rest.find('/').map_or("/", |start| &rest[start..])
+}
+
+fn segments(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+impl Route {
+    /// Every request of a collection, its variables substituted, those left
+    /// in the path match anything
+    pub fn all(collection: &Collection, variables: &HashMap<String, String>) -> Vec<Self> {
+        collection
+            .requests()
+            .iter()
+            .map(|entry| {
+                let url = substitute(&entry.request.url, variables);
+                let path = url_path(&url).to_string();
+
+                let segments = segments(&path)
+                    .into_iter()
+                    .map(|segment| {
+                        let is_parameter = (segment.starts_with('{') && segment.ends_with('}'))
+                            || segment.starts_with(':');
+
+                        match is_parameter {
+                            true => Segment::Parameter,
+                            false => Segment::Literal(segment.to_string()),
+                        }
+                    })
+                    .collect();
+
+                Self {
+                    method: entry.request.method.clone(),
+                    path,
+                    label: entry.label(),
+                    examples: entry.request.examples.clone(),
+                    segments,
+                }
+            })
+            .collect()
+    }
+
+    /// How many literal segments match the path, if it matches at all
+    fn score(&self, path: &str) -> Option<usize> {
+        let parts = segments(path);
+
+        if parts.len() != self.segments.len() {
+            return None;
+        }
+
+        let mut score = 0;
+
+        for (segment, part) in self.segments.iter().zip(parts) {
+            match segment {
+                Segment::Literal(literal) if literal == part => score += 1,
+                Segment::Literal(_) => return None,
+                Segment::Parameter => {}
+            }
+        }
+
+        Some(score)
+    }
+
+    /// The example asked for with `Prefer: example=name` or `Prefer:
+    /// code=404`, the first one otherwise
+    pub fn example(&self, prefer: Option<&str>) -> Option<&Example> {
+        let preference = |key: &str| {
+            prefer?
+                .split([',', ';'])
+                .filter_map(|part| part.trim().split_once('='))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case(key))
+                .map(|(_, value)| value.trim().trim_matches('"').to_string())
+        };
+
+        if let Some(name) = preference("example") {
+            return self
+                .examples
+                .iter()
+                .find(|example| example.name.eq_ignore_ascii_case(&name));
+        }
+
+        if let Some(code) = preference("code") {
+            return self
+                .examples
+                .iter()
+                .find(|example| example.status.to_string() == code);
+        }
+
+        self.examples.first()
+    }
+}
+
+/// What a request to the mock matched
+pub enum Match<'a> {
+    Found(&'a Route),
+    /// The path is known but not with this method
+    MethodNotAllowed,
+    NotFound,
+}
+
+/// The route of a request, the one with the most literal segments when
+/// several match, like `/users/me` over `/users/{id}`
+pub fn find<'a>(routes: &'a [Route], method: &str, path: &str) -> Match<'a> {
+    let matching: Vec<(&Route, usize)> = routes
+        .iter()
+        .filter_map(|route| route.score(path).map(|score| (route, score)))
+        .collect();
+
+    let best = matching
+        .iter()
+        .filter(|(route, _)| route.method.eq_ignore_ascii_case(method))
+        // The first of the best, as `max_by_key` keeps the last
+        .rev()
+        .max_by_key(|(_, score)| *score);
+
+    match (best, matching.is_empty()) {
+        (Some((route, _)), _) => Match::Found(route),
+        (None, false) => Match::MethodNotAllowed,
+        (None, true) => Match::NotFound,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLLECTION: &str = r#"
+name: Shop
+items:
+  - request:
+      name: Get user
+      method: GET
+      url: "{{baseUrl}}/users/{id}?expand=all"
+      examples:
+        - name: Found
+          body: '{"id": 1}'
+        - name: Missing
+          status: 404
+  - request:
+      name: Me
+      method: GET
+      url: https://shop.test/users/me
+  - request:
+      name: Delete user
+      method: DELETE
+      url: /users/:id
+"#;
+
+    fn routes() -> Vec<Route> {
+        let collection: Collection = serde_yaml::from_str(COLLECTION).unwrap();
+        let variables = HashMap::from([("baseUrl".to_string(), "https://shop.test".to_string())]);
+
+        Route::all(&collection, &variables)
+    }
+
+    fn found(routes: &[Route], method: &str, path: &str) -> Option<String> {
+        match find(routes, method, path) {
+            Match::Found(route) => Some(route.label.clone()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn takes_the_path_of_urls() {
+        let routes = routes();
+
+        assert_eq!(routes[0].path, "/users/{id}");
+        assert_eq!(routes[1].path, "/users/me");
+        assert_eq!(routes[2].path, "/users/:id");
+        assert_eq!(url_path("{{host}}"), "/");
+    }
+
+    #[test]
+    fn scores_literal_segments() {
+        let routes = routes();
+
+        assert_eq!(routes[0].score("/users/7"), Some(1));
+        assert_eq!(routes[1].score("/users/me"), Some(2));
+        assert_eq!(routes[1].score("/users/7"), None);
+        assert_eq!(routes[0].score("/users/7/orders"), None);
+    }
+
+    #[test]
+    fn finds_the_most_specific_route() {
+        let routes = routes();
+
+        assert_eq!(found(&routes, "GET", "/users/me").as_deref(), Some("Me"));
+        assert_eq!(
+            found(&routes, "get", "/users/7").as_deref(),
+            Some("Get user")
+        );
+        assert_eq!(
+            found(&routes, "DELETE", "/users/me").as_deref(),
+            Some("Delete user")
+        );
+        assert!(matches!(
+            find(&routes, "POST", "/users/7"),
+            Match::MethodNotAllowed
+        ));
+        assert!(matches!(find(&routes, "GET", "/orders"), Match::NotFound));
+    }
+
+    #[test]
+    fn picks_the_preferred_example() {
+        let route = &routes()[0];
+        let name = |prefer| route.example(prefer).map(|example| example.name.as_str());
+
+        assert_eq!(name(None), Some("Found"));
+        assert_eq!(name(Some("example=missing")), Some("Missing"));
+        assert_eq!(name(Some("return=minimal; code=404")), Some("Missing"));
+        assert_eq!(name(Some(r#"example="Found""#)), Some("Found"));
+        assert_eq!(name(Some("code=500")), None);
+        assert_eq!(routes()[1].example(None), None);
+    }
+}
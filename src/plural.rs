@@ -0,0 +1,7 @@
+/// `1 request`, `2 requests`
+pub fn count(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {noun}"),
+        count => format!("{count} {noun}s"),
+    }
+}
@@ -0,0 +1,415 @@
+use crate::content_type::ContentKind;
+
+const INDENT: &str = "  ";
+
+/// CSV cells wider than this are cut with an ellipsis
+const MAX_CELL_WIDTH: usize = 40;
+
+/// Elements that never have children or a closing tag in HTML
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements whose content is kept verbatim in HTML
+const RAW_TEXT_ELEMENTS: [&str; 4] = ["script", "style", "pre", "textarea"];
+
+/// Reformat a body for display, falling back to the original text when it
+/// can't be parsed
+pub fn prettify(text: String, kind: ContentKind, delimiter: char) -> String {
+    let pretty = match kind {
+        ContentKind::Json => json(&text),
+        ContentKind::Xml => Some(markup(&text, false)),
+        ContentKind::Html => Some(markup(&text, true)),
+        ContentKind::Yaml => yaml(&text),
+        ContentKind::Csv => Some(csv_table(&text, delimiter)),
+        ContentKind::Form => Some(form(&text)),
+        _ => None,
+    };
+
+    pretty.unwrap_or(text)
+}
+
+fn json(text: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+
+    Some(format!("{:#}\n", value))
+}
+
+fn yaml(text: &str) -> Option<String> {
+    let documents = serde_yaml::Deserializer::from_str(text)
+        .map(|document| {
+            let value = <serde_yaml::Value as serde::Deserialize>::deserialize(document).ok()?;
+
+            serde_yaml::to_string(&value).ok()
+        })
+        .collect::<Option<Vec<String>>>()?;
+
+    Some(documents.join("---\n"))
+}
+
+fn form(text: &str) -> String {
+    let pairs: Vec<(String, String)> = url::form_urlencoded::parse(text.trim().as_bytes())
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+
+    let width = pairs
+        .iter()
+        .map(|(key, _)| key.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    pairs
+        .iter()
+        .map(|(key, value)| format!("{key:width$} = {value}\n"))
+        .collect()
+}
+
+/// Split CSV text into records, handling quoted fields
+pub fn parse_csv(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.is_empty() => in_quotes = true,
+            c if c == delimiter && !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    records
+}
+
+/// Guess the delimiter from the first line
+pub fn sniff_delimiter(text: &str) -> char {
+    let first_line = text.lines().next().unwrap_or("");
+
+    [',', ';', '\t', '|']
+        .into_iter()
+        .max_by_key(|d| first_line.matches(*d).count())
+        .filter(|d| first_line.contains(*d))
+        .unwrap_or(',')
+}
+
+fn truncate(cell: &str) -> String {
+    let cell = cell.replace('\n', " ");
+
+    if cell.chars().count() <= MAX_CELL_WIDTH {
+        return cell;
+    }
+
+    let mut truncated: String = cell.chars().take(MAX_CELL_WIDTH - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+fn csv_table(text: &str, delimiter: char) -> String {
+    let records: Vec<Vec<String>> = parse_csv(text, delimiter)
+        .into_iter()
+        .map(|record| record.iter().map(|cell| truncate(cell)).collect())
+        .collect();
+
+    let columns = records.iter().map(|r| r.len()).max().unwrap_or(0);
+
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| {
+            records
+                .iter()
+                .filter_map(|r| r.get(i))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let format_row = |record: &Vec<String>| {
+        let cells: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(i, width)| {
+                let cell = record.get(i).map(|c| c.as_str()).unwrap_or("");
+                let padding = width - cell.chars().count();
+
+                format!("{cell}{}", " ".repeat(padding))
+            })
+            .collect();
+
+        format!("{}\n", cells.join(" │ ").trim_end())
+    };
+
+    let mut output = String::new();
+
+    for (i, record) in records.iter().enumerate() {
+        output.push_str(&format_row(record));
+
+        if i == 0 {
+            let separator: Vec<String> = widths.iter().map(|w| "─".repeat(*w)).collect();
+
+            output.push_str(&format!("{}\n", separator.join("─┼─")));
+        }
+    }
+
+    output
+}
+
+enum Token<'a> {
+    /// `<?xml ...?>`, `<!DOCTYPE ...>` and comments
+    Declaration(&'a str),
+    Open(&'a str, &'a str),
+    Close(&'a str),
+    SelfClosing(&'a str),
+    Text(&'a str),
+}
+
+fn tag_name(tag: &str) -> &str {
+    tag.trim_start_matches(['<', '/'])
+        .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .next()
+        .unwrap_or("")
+}
+
+fn tokenize(text: &str, html: bool) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+
+            tokens.push(Token::Text(&rest[..end]));
+            rest = &rest[end..];
+            continue;
+        }
+
+        let terminator = if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with("<![CDATA[") {
+            "]]>"
+        } else if rest.starts_with("<?") {
+            "?>"
+        } else {
+            ">"
+        };
+
+        let end = match rest[1..].find(terminator) {
+            Some(i) => i + 1 + terminator.len(),
+            None => rest.len(),
+        };
+
+        let tag = &rest[..end];
+        rest = &rest[end..];
+
+        if tag.starts_with("<![CDATA[") {
+            tokens.push(Token::Text(tag));
+        } else if tag.starts_with("<!") || tag.starts_with("<?") {
+            tokens.push(Token::Declaration(tag));
+        } else if tag.starts_with("</") {
+            tokens.push(Token::Close(tag));
+        } else if tag.ends_with("/>") {
+            tokens.push(Token::SelfClosing(tag));
+        } else {
+            let name = tag_name(tag).to_lowercase();
+
+            if html && VOID_ELEMENTS.contains(&name.as_str()) {
+                tokens.push(Token::SelfClosing(tag));
+                continue;
+            }
+
+            tokens.push(Token::Open(tag, ""));
+
+            // Keep the content of <script>, <pre>... verbatim
+            if html && RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+                let closing = format!("</{name}");
+                let end = rest
+                    .to_ascii_lowercase()
+                    .find(&closing)
+                    .unwrap_or(rest.len());
+
+                if let Some(Token::Open(_, content)) = tokens.last_mut() {
+                    *content = &rest[..end];
+                }
+
+                rest = &rest[end..];
+            }
+        }
+    }
+
+    tokens
+}
+
+/// Indent XML or HTML, elements holding only a short text stay on one line
+fn markup(text: &str, html: bool) -> String {
+    let tokens = tokenize(text, html);
+    let mut output = String::new();
+    let mut depth: usize = 0;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let indent = INDENT.repeat(depth);
+
+        match &tokens[i] {
+            Token::Declaration(tag) | Token::SelfClosing(tag) => {
+                output.push_str(&format!("{indent}{tag}\n"));
+            }
+            Token::Text(text) => {
+                let text = text.trim();
+
+                if !text.is_empty() {
+                    output.push_str(&format!("{indent}{text}\n"));
+                }
+            }
+            Token::Open(tag, raw_content) if !raw_content.is_empty() => {
+                output.push_str(&format!("{indent}{tag}{raw_content}"));
+
+                if let Some(Token::Close(close)) = tokens.get(i + 1) {
+                    output.push_str(close);
+                    i += 1;
+                }
+
+                output.push('\n');
+            }
+            Token::Open(tag, _) => {
+                // <a>text</a> or <a></a> on a single line
+                match (tokens.get(i + 1), tokens.get(i + 2)) {
+                    (Some(Token::Text(text)), Some(Token::Close(close)))
+                        if !text.trim().contains('\n') =>
+                    {
+                        output.push_str(&format!("{indent}{tag}{}{close}\n", text.trim()));
+                        i += 2;
+                    }
+                    (Some(Token::Close(close)), _) => {
+                        output.push_str(&format!("{indent}{tag}{close}\n"));
+                        i += 1;
+                    }
+                    _ => {
+                        output.push_str(&format!("{indent}{tag}\n"));
+                        depth += 1;
+                    }
+                }
+            }
+            Token::Close(tag) => {
+                depth = depth.saturating_sub(1);
+                output.push_str(&format!("{}{tag}\n", INDENT.repeat(depth)));
+            }
+        }
+
+        i += 1;
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pretty(text: &str, kind: ContentKind) -> String {
+        prettify(text.to_string(), kind, ',')
+    }
+
+    #[test]
+    fn indents_xml() {
+        let xml = r#"<?xml version="1.0"?><root><item id="1">one</item><empty/><list><a></a></list></root>"#;
+
+        assert_eq!(
+            pretty(xml, ContentKind::Xml),
+            concat!(
+                "<?xml version=\"1.0\"?>\n",
+                "<root>\n",
+                "  <item id=\"1\">one</item>\n",
+                "  <empty/>\n",
+                "  <list>\n",
+                "    <a></a>\n",
+                "  </list>\n",
+                "</root>\n",
+            )
+        );
+    }
+
+    #[test]
+    fn keeps_html_void_and_raw_text_elements() {
+        let html = "<!DOCTYPE html><html><head><meta charset=utf-8><script>if (a < b) {}</script></head><body><br><p>Hi</p></body></html>";
+
+        assert_eq!(
+            pretty(html, ContentKind::Html),
+            concat!(
+                "<!DOCTYPE html>\n",
+                "<html>\n",
+                "  <head>\n",
+                "    <meta charset=utf-8>\n",
+                "    <script>if (a < b) {}</script>\n",
+                "  </head>\n",
+                "  <body>\n",
+                "    <br>\n",
+                "    <p>Hi</p>\n",
+                "  </body>\n",
+                "</html>\n",
+            )
+        );
+    }
+
+    #[test]
+    fn reformats_yaml_documents() {
+        assert_eq!(
+            pretty("a:   1\nb: [x, y]\n---\nc: true", ContentKind::Yaml),
+            "a: 1\nb:\n- x\n- y\n---\nc: true\n"
+        );
+    }
+
+    #[test]
+    fn keeps_invalid_bodies() {
+        assert_eq!(pretty("{\"a\":", ContentKind::Json), "{\"a\":");
+        assert_eq!(pretty("a: [", ContentKind::Yaml), "a: [");
+    }
+
+    #[test]
+    fn aligns_form_fields() {
+        assert_eq!(
+            pretty("name=J%C3%B6rg+B&id=1", ContentKind::Form),
+            "name = Jörg B\nid   = 1\n"
+        );
+    }
+
+    #[test]
+    fn draws_csv_tables() {
+        assert_eq!(
+            pretty(
+                "id,name\n1,\"Smith, J\"\n22,\"a \"\"b\"\"\"\n",
+                ContentKind::Csv
+            ),
+            concat!(
+                "id │ name\n",
+                "───┼─────────\n",
+                "1  │ Smith, J\n",
+                "22 │ a \"b\"\n",
+            )
+        );
+    }
+
+    #[test]
+    fn sniffs_the_delimiter_from_the_first_line() {
+        assert_eq!(sniff_delimiter("a;b;c\n1,2;3"), ';');
+        assert_eq!(sniff_delimiter("a\tb\n1\t2"), '\t');
+        assert_eq!(sniff_delimiter("a|b,c|d"), '|');
+        assert_eq!(sniff_delimiter("single column"), ',');
+    }
+}
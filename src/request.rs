@@ -1,10 +1,19 @@
-use std::str::FromStr;
-
-use reqwest::header::{HeaderMap, HeaderName};
-
-use crate::app::{BodyContentType, Request, RequestMethod, Response};
-
-pub async fn send(req: Request) -> Response {
+use std::{str::FromStr, time::Instant};
+
+use chrono::Utc;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+use crate::{
+    app::{
+        history::{Exchange, Timings},
+        BodyContentType, Request, RequestMethod, Response,
+    },
+    content_type::{self, ContentKind},
+};
+
+/// Send a request, with the time it took
+pub async fn send(req: Request) -> Result<Exchange, String> {
+    let sent = req.to_saved();
     let method = match req.method {
         RequestMethod::Get => reqwest::Method::GET,
         RequestMethod::Post => reqwest::Method::POST,
@@ -15,12 +24,13 @@ pub async fn send(req: Request) -> Response {
 
     let mut headers = HeaderMap::new();
 
-    req.headers.iter().for_each(|(key, value)| {
-        headers.insert(
-            HeaderName::from_str(key.as_str()).unwrap(),
-            value.parse().unwrap(),
-        );
-    });
+    for (key, value) in &req.headers {
+        let name = HeaderName::from_str(key).map_err(|_| format!("invalid header name {key}"))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|_| format!("invalid value for the {key} header"))?;
+
+        headers.insert(name, value);
+    }
 
     let client = reqwest::Client::new();
 
@@ -40,34 +50,106 @@ pub async fn send(req: Request) -> Response {
         }
     }
 
-    let response = builder.send().await.unwrap();
+    let started = Utc::now();
+    let start = Instant::now();
+
+    let response = builder.send().await.map_err(|e| e.to_string())?;
+
+    let wait = start.elapsed();
+
+    let http_version = format!("{:?}", response.version());
 
     let status_code = response.status().as_u16();
 
-    let content_type = response.headers().get("content-type").unwrap();
+    let url = response.url().to_string();
+
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.to_string(),
+                String::from_utf8_lossy(value.as_bytes()).to_string(),
+            )
+        })
+        .collect();
+
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let body = response.bytes().await.map_err(|e| e.to_string())?.to_vec();
+
+    let timings = Timings {
+        wait,
+        receive: start.elapsed() - wait,
+    };
 
-    let text: String;
+    let is_binary = is_binary(&content_type, &body);
 
-    let content_type_value: String;
+    let text = if is_binary {
+        String::new()
+    } else {
+        String::from_utf8_lossy(&body).to_string()
+    };
 
-    match content_type.clone().to_str().unwrap().to_lowercase() {
-        h if h.contains("application/json") => {
-            let data = response.json::<serde_json::Value>().await.unwrap();
+    let response = Response {
+        status_code,
+        text,
+        content_type,
+        headers,
+        body,
+        url,
+        http_version,
+        is_binary,
+    };
 
-            content_type_value = "application/json".to_string();
-            text = format!("{:#}\n", data);
-        }
+    Ok(Exchange {
+        request: sent,
+        response,
+        started,
+        timings,
+    })
+}
 
-        header_value => {
-            content_type_value = header_value.clone().to_string();
+/// Bytes sniffed to guess whether a body is binary
+const SNIFF_LENGTH: usize = 8192;
 
-            text = response.text().await.unwrap();
-        }
+/// Decide if a body should be shown as a hex dump, from the content type and
+/// by looking at the bytes themselves
+pub fn is_binary(content_type: &str, body: &[u8]) -> bool {
+    let kind = content_type::classify(content_type);
+
+    if kind == ContentKind::Binary {
+        return true;
     }
 
-    Response {
-        status_code,
-        text,
-        content_type: content_type_value,
+    let sample = &body[..body.len().min(SNIFF_LENGTH)];
+
+    if sample.contains(&0) {
+        return true;
     }
+
+    if kind != ContentKind::Text || content_type::essence(content_type).starts_with("text/") {
+        return false;
+    }
+
+    let text = match std::str::from_utf8(sample) {
+        Ok(text) => text,
+        // The sample may cut a multi-byte character in half
+        Err(e) if e.error_len().is_none() => {
+            std::str::from_utf8(&sample[..e.valid_up_to()]).unwrap()
+        }
+        Err(_) => return true,
+    };
+
+    let control_chars = text
+        .chars()
+        .filter(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t' | '\x0c'))
+        .count();
+
+    control_chars * 10 > text.chars().count()
 }
@@ -0,0 +1,116 @@
+use std::io::Stdout;
+
+use ratatui::{
+    layout::Rect,
+    prelude::CrosstermBackend,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use crate::{app::hex::HexView, config::theme::Theme};
+
+/// Width of `00000000  ` before the bytes
+const OFFSET_WIDTH: usize = 10;
+
+fn row_width(bytes_per_row: usize) -> usize {
+    // "xx " per byte, a space between groups of 8, then " |ascii|"
+    OFFSET_WIDTH + bytes_per_row * 3 + bytes_per_row / 8 + bytes_per_row + 2
+}
+
+fn byte_style(byte: u8, theme: &Theme) -> Style {
+    Style::default().fg(match byte {
+        0x00 => theme.hex_null,
+        b if b.is_ascii_graphic() || b == b' ' => theme.hex_printable,
+        b if b.is_ascii_whitespace() || b.is_ascii_control() => theme.hex_whitespace,
+        _ => theme.hex_other,
+    })
+}
+
+fn row_line(offset: usize, bytes: &[u8], bytes_per_row: usize, theme: &Theme) -> Line<'static> {
+    let muted = Style::default().fg(theme.muted);
+
+    let mut spans = vec![Span::styled(format!("{offset:08x}  "), muted)];
+
+    for i in 0..bytes_per_row {
+        if i > 0 && i % 8 == 0 {
+            spans.push(Span::raw(" "));
+        }
+
+        match bytes.get(i) {
+            Some(byte) => spans.push(Span::styled(
+                format!("{byte:02x} "),
+                byte_style(*byte, theme),
+            )),
+            None => spans.push(Span::raw("   ")),
+        }
+    }
+
+    spans.push(Span::styled(" |", muted));
+
+    for byte in bytes {
+        let c = if byte.is_ascii_graphic() || *byte == b' ' {
+            *byte as char
+        } else {
+            '.'
+        };
+
+        spans.push(Span::styled(c.to_string(), byte_style(*byte, theme)));
+    }
+
+    spans.push(Span::styled("|", muted));
+
+    Line::from(spans)
+}
+
+fn format_size(len: usize) -> String {
+    match len {
+        n if n >= 1024 * 1024 => format!("{:.1} MiB", n as f64 / (1024.0 * 1024.0)),
+        n if n >= 1024 => format!("{:.1} KiB", n as f64 / 1024.0),
+        n => format!("{n} B"),
+    }
+}
+
+pub fn render_hex(
+    hex: &mut HexView,
+    body: &[u8],
+    block: Block,
+    theme: &Theme,
+    frame: &mut Frame<CrosstermBackend<Stdout>>,
+    area: Rect,
+) {
+    let width = usize::from(area.width.saturating_sub(2));
+
+    hex.bytes_per_row = [16, 8, 4]
+        .into_iter()
+        .find(|n| row_width(*n) <= width)
+        .unwrap_or(4);
+    hex.page_rows = usize::from(area.height.saturating_sub(2)).max(1);
+    hex.clamp(body.len());
+
+    let lines: Vec<Line> = body[hex.offset.min(body.len())..]
+        .chunks(hex.bytes_per_row)
+        .take(hex.page_rows)
+        .enumerate()
+        .map(|(i, bytes)| {
+            row_line(
+                hex.offset + i * hex.bytes_per_row,
+                bytes,
+                hex.bytes_per_row,
+                theme,
+            )
+        })
+        .collect();
+
+    let title = Span::styled(
+        format!(
+            " binary · {} · 0x{:x} ",
+            format_size(body.len()),
+            hex.offset
+        ),
+        Style::default().fg(theme.accent),
+    );
+
+    frame.render_widget(Paragraph::new(lines).block(block.title(title)), area);
+}
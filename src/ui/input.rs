@@ -1,39 +1,105 @@
 use ratatui::{
-    style::{Color, Style},
+    style::Style,
     text::{Line, Span},
     widgets::Paragraph,
 };
 use syntect::easy::HighlightLines;
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::app::{App, AppBlock, BodyContentType, BodyType, InputMode};
+use crate::config::theme::Theme;
 use crate::event::input::Input;
 
-use super::syntax::{translate_colour, PS, TS};
-
-pub fn create_input<'a>(input: &'a Input, app: &App, is_selected: bool) -> Paragraph<'a> {
-    let (left, right) = input.text.split_at(input.cursor_position.x.into());
-
-    let line = Line::from(vec![
-        Span::raw(left),
-        Span::styled(
-            match right.get(0..1) {
-                Some(c) => c,
-                None => " ",
-            },
-            match app.input_mode {
-                InputMode::Insert if is_selected => {
-                    Style::default().bg(Color::Green).fg(Color::Black)
-                }
-                _ => Style::default(),
-            },
-        ),
-        match right.get(1..) {
-            Some(c) => Span::raw(c),
-            None => Span::raw(""),
-        },
-    ]);
+use super::syntax::PS;
+
+/// Patch the style of the grapheme clusters in `ranges`, given as
+/// `(start, end, style)` in cursor positions
+fn overlay(spans: Vec<Span<'static>>, ranges: &[(usize, usize, Style)]) -> Vec<Span<'static>> {
+    let mut result: Vec<Span<'static>> = Vec::new();
+    let mut index = 0;
+
+    for span in spans {
+        for grapheme in span.content.graphemes(true) {
+            let style = ranges
+                .iter()
+                .filter(|(start, end, _)| (*start..*end).contains(&index))
+                .fold(span.style, |style, (_, _, patch)| style.patch(*patch));
 
-    Paragraph::new(line)
+            match result.last_mut() {
+                Some(last) if last.style == style => last.content.to_mut().push_str(grapheme),
+                _ => result.push(Span::styled(grapheme.to_string(), style)),
+            }
+
+            index += 1;
+        }
+    }
+
+    result
+}
+
+/// Draw the selection and the cursor over a line of `input`
+fn decorate_line(
+    spans: Vec<Span<'static>>,
+    input: &Input,
+    line_index: usize,
+    show_cursor: bool,
+    theme: &Theme,
+) -> Line<'static> {
+    let len = input.line_len(line_index);
+    let mut ranges = Vec::new();
+
+    if let Some((start, end)) = input.selection_columns(line_index) {
+        ranges.push((start, end, theme.selection()));
+    }
+
+    let has_cursor = show_cursor && usize::from(input.cursor_position.y) == line_index;
+    let cursor_x = usize::from(input.cursor_position.x);
+    let cursor_style = theme.cursor(input.is_normal_mode());
+
+    if has_cursor && cursor_x < len {
+        ranges.push((cursor_x, cursor_x + 1, cursor_style));
+    }
+
+    let mut spans = overlay(spans, &ranges);
+
+    if has_cursor && cursor_x >= len {
+        spans.push(Span::styled(" ", cursor_style));
+    }
+
+    Line::from(spans)
+}
+
+/// A single line input `width` columns wide, scrolled horizontally to keep
+/// the cursor visible
+pub fn create_input<'a>(
+    input: &'a Input,
+    app: &App,
+    is_selected: bool,
+    width: u16,
+) -> Paragraph<'a> {
+    let show_cursor = app.input_mode == InputMode::Insert && is_selected;
+
+    let line = decorate_line(
+        vec![Span::raw(input.text.clone())],
+        input,
+        0,
+        show_cursor,
+        &app.theme,
+    );
+
+    let scroll = input.horizontal_scroll(width.into());
+
+    Paragraph::new(line).scroll((0, u16::try_from(scroll).unwrap_or(u16::MAX)))
+}
+
+/// A single line input after `prefix`, like the `/query` of the response
+/// search
+pub fn prompt_line(prefix: &str, input: &Input, theme: &Theme) -> Line<'static> {
+    let mut line = decorate_line(vec![Span::raw(input.text.clone())], input, 0, true, theme);
+
+    line.spans.insert(0, Span::raw(prefix.to_string()));
+
+    line
 }
 
 pub fn create_textarea<'a>(input: &'a Input, app: &App) -> Paragraph<'a> {
@@ -47,139 +113,77 @@ pub fn create_textarea<'a>(input: &'a Input, app: &App) -> Paragraph<'a> {
     };
 
     let syntax = PS.find_syntax_by_extension(syntax_name).unwrap();
-    let mut h = HighlightLines::new(syntax, &TS.themes["base16-ocean.dark"]);
-
-    let mut lines = Vec::new();
-
-    for (index, line) in input.text.split("\n").enumerate() {
-        let ranges = h.highlight_line(line, &PS).unwrap();
-
-        let is_cursor_in_line = index == usize::from(input.cursor_position.y);
-
-        if ranges.is_empty() && is_cursor_in_line {
-            lines.push(Line::from(Span::styled(
-                " ",
-                match app.input_mode {
-                    InputMode::Insert if app.selected_block == AppBlock::RequestContent => {
-                        Style::default().bg(Color::Green).fg(Color::Black)
-                    }
-                    _ => Style::default(),
-                },
-            )));
-
-            continue;
-        }
-
-        if !is_cursor_in_line {
-            let spans: Vec<Span> = ranges
-                .iter()
-                .map(|segment| {
-                    let (style, content) = segment;
-
-                    Span::styled(
-                        content.to_string(),
-                        Style {
-                            fg: translate_colour(style.foreground),
-                            ..Style::default()
-                        },
-                    )
-                })
-                .collect();
-
-            lines.push(Line::from(spans));
-
-            continue;
-        }
-
-        let spans: Vec<Vec<Span>> = ranges
-            .iter()
-            .enumerate()
-            .map(|(i, segment)| {
-                let (style, content) = segment;
-
-                let position = get_current_position(&ranges, i);
-
-                let is_cursor_in_segment = input.cursor_position.x as usize >= position.0
-                    && position.1 > input.cursor_position.x as usize;
-
-                let cursor_styles = match app.input_mode {
-                    InputMode::Insert if app.selected_block == AppBlock::RequestContent => {
-                        Style::default().bg(Color::Green).fg(Color::Black)
-                    }
-                    _ => Style::default(),
-                };
-
-                if !is_cursor_in_segment {
-                    let is_last_segment =
-                        i == ranges.len() - 1 && input.cursor_position.x as usize == position.1;
-                    let is_first_segment = i == 0 && input.cursor_position.x as usize == position.0;
-
-                    vec![
-                        match is_first_segment {
-                            true => Span::styled(" ", cursor_styles),
-                            false => Span::raw(""),
-                        },
+    let mut h = app
+        .theme
+        .syntax
+        .as_ref()
+        .map(|theme| HighlightLines::new(syntax, theme));
+
+    let show_cursor =
+        app.input_mode == InputMode::Insert && app.selected_block == AppBlock::RequestContent;
+
+    let lines: Vec<Line> = input
+        .text
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            let spans = match h.as_mut() {
+                Some(h) => h
+                    .highlight_line(line, &PS)
+                    .unwrap()
+                    .into_iter()
+                    .map(|(style, content)| {
                         Span::styled(
                             content.to_string(),
                             Style {
-                                fg: translate_colour(style.foreground),
-                                ..Style::default()
-                            },
-                        ),
-                        match is_last_segment {
-                            true => Span::styled(" ", cursor_styles),
-                            false => Span::raw(""),
-                        },
-                    ]
-                } else {
-                    let current = input.cursor_position.x as usize - position.0;
-                    let (left, right) = content.split_at(current);
-
-                    vec![
-                        Span::styled(
-                            left.to_string(),
-                            Style {
-                                fg: translate_colour(style.foreground),
+                                fg: app.theme.syntax_color(style.foreground),
                                 ..Style::default()
                             },
-                        ),
-                        Span::styled(
-                            match right.get(0..1) {
-                                Some(c) => c,
-                                None => " ",
-                            },
-                            cursor_styles,
-                        ),
-                        match right.get(1..) {
-                            Some(c) => Span::styled(
-                                c,
-                                Style {
-                                    fg: translate_colour(style.foreground),
-                                    ..Style::default()
-                                },
-                            ),
-                            None => Span::raw(""),
-                        },
-                    ]
-                }
-            })
-            .collect();
-
-        lines.push(Line::from(spans.concat()));
-    }
+                        )
+                    })
+                    .collect(),
+                None => vec![Span::raw(line.to_string())],
+            };
+
+            decorate_line(spans, input, index, show_cursor, &app.theme)
+        })
+        .collect();
 
-    Paragraph::new(lines)
+    Paragraph::new(lines).scroll((u16::try_from(input.scroll).unwrap_or(u16::MAX), 0))
 }
 
-fn get_current_position(
-    vector: &Vec<(syntect::highlighting::Style, &str)>,
-    index: usize,
-) -> (usize, usize) {
-    let mut current = 0;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    for (_, content) in vector.get(0..index).unwrap() {
-        current += content.len();
+    fn contents(line: &Line) -> Vec<String> {
+        line.spans
+            .iter()
+            .map(|span| span.content.to_string())
+            .collect()
     }
 
-    return (current, current + vector[index].1.len());
+    #[test]
+    fn draws_the_cursor_over_a_multibyte_grapheme() {
+        let theme = Theme::default();
+        let mut input = Input::new("héllo 日本");
+
+        input.move_cursor_right();
+
+        assert_eq!(
+            contents(&prompt_line("/", &input, &theme)),
+            vec!["/", "h", "é", "llo 日本"]
+        );
+        assert_eq!(
+            prompt_line("/", &input, &theme).spans[2].style,
+            theme.cursor(false)
+        );
+
+        input.move_cursor_to_end_single_line();
+
+        assert_eq!(
+            contents(&prompt_line("/", &input, &theme)),
+            vec!["/", "héllo 日本", " "]
+        );
+    }
 }
@@ -0,0 +1,123 @@
+use std::io::Stdout;
+
+use ratatui::{
+    layout::Rect,
+    prelude::CrosstermBackend,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use crate::{
+    app::json_tree::{JsonTree, NodeKey, NodeKind},
+    config::theme::Theme,
+    plural,
+};
+
+const MAX_STRING_PREVIEW: usize = 120;
+
+fn node_line(tree: &JsonTree, index: usize, theme: &Theme) -> Line<'static> {
+    let node = &tree.nodes[index];
+    let dim = Style::default().fg(theme.muted);
+
+    let mut spans = vec![Span::raw("  ".repeat(node.depth))];
+
+    spans.push(Span::styled(
+        match (node.is_container(), tree.collapsed[index]) {
+            (true, true) => "▸ ",
+            (true, false) => "▾ ",
+            (false, _) => "  ",
+        },
+        dim,
+    ));
+
+    match &node.key {
+        NodeKey::Root => {}
+        NodeKey::Key(key) => {
+            spans.push(Span::styled(
+                key.clone(),
+                Style::default().fg(theme.json_key),
+            ));
+            spans.push(Span::raw(": "));
+        }
+        NodeKey::Index(i) => {
+            spans.push(Span::styled(format!("[{i}]"), dim));
+            spans.push(Span::raw(": "));
+        }
+    }
+
+    match &node.kind {
+        NodeKind::Object(count) => {
+            spans.push(Span::raw(if tree.collapsed[index] { "{…}" } else { "{" }));
+            spans.push(Span::styled(
+                format!(" object · {}", plural::count(*count, "key")),
+                dim,
+            ));
+        }
+        NodeKind::Array(count) => {
+            spans.push(Span::raw(if tree.collapsed[index] { "[…]" } else { "[" }));
+            spans.push(Span::styled(
+                format!(" array · {}", plural::count(*count, "item")),
+                dim,
+            ));
+        }
+        NodeKind::String(s) => {
+            let mut preview: String = s.chars().take(MAX_STRING_PREVIEW).collect();
+
+            if preview.len() < s.len() {
+                preview.push('…');
+            }
+
+            spans.push(Span::styled(
+                format!("{:?}", preview),
+                Style::default().fg(theme.json_string),
+            ));
+        }
+        NodeKind::Number(n) => {
+            spans.push(Span::styled(
+                n.clone(),
+                Style::default().fg(theme.json_number),
+            ));
+        }
+        NodeKind::Bool(b) => {
+            spans.push(Span::styled(
+                b.to_string(),
+                Style::default().fg(theme.json_bool),
+            ));
+        }
+        NodeKind::Null => spans.push(Span::styled("null", dim)),
+    }
+
+    Line::from(spans)
+}
+
+pub fn render_json_tree(
+    tree: &mut JsonTree,
+    block: Block,
+    theme: &Theme,
+    frame: &mut Frame<CrosstermBackend<Stdout>>,
+    area: Rect,
+) {
+    let height = usize::from(area.height.saturating_sub(2));
+
+    tree.scroll_to_selected(height);
+
+    let end = (tree.offset + height).min(tree.visible.len());
+
+    let lines: Vec<Line> = (tree.offset..end)
+        .map(|row| {
+            let mut line = node_line(tree, tree.visible[row], theme);
+
+            if row == tree.selected {
+                line.patch_style(theme.selected_row());
+            }
+
+            line
+        })
+        .collect();
+
+    let position = format!(" {}/{} ", tree.selected + 1, tree.visible.len());
+
+    frame.render_widget(Paragraph::new(lines).block(block.title(position)), area);
+}
@@ -0,0 +1,115 @@
+use std::{io::Stdout, str::FromStr};
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    prelude::CrosstermBackend,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::{app::RequestMethod, config::theme::Theme, mock::Mock, plural::count};
+
+use super::method_color;
+
+fn method_span(method: &str, theme: &Theme) -> Span<'static> {
+    let color = RequestMethod::from_str(method)
+        .map(|method| method_color(&method, theme))
+        .unwrap_or(theme.text);
+
+    Span::styled(format!("{method:<7}"), Style::default().fg(color))
+}
+
+fn status_color(status: u16, theme: &Theme) -> Color {
+    match status {
+        200..=299 => theme.status_success,
+        300..=399 => theme.status_redirect,
+        400..=499 => theme.status_client_error,
+        500..=599 => theme.status_server_error,
+        _ => theme.text,
+    }
+}
+
+fn block(title: String, theme: &Theme) -> Block<'static> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().fg(theme.text))
+        .title(title)
+}
+
+/// The routes served on the left and the requests received on the right,
+/// the latest at the bottom
+pub fn draw(frame: &mut Frame<CrosstermBackend<Stdout>>, mock: &Mock) {
+    let theme = &mock.theme;
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .horizontal_margin(1)
+        .split(frame.size());
+
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .split(chunks[0]);
+
+    let routes: Vec<Line> = mock
+        .routes
+        .iter()
+        .map(|route| {
+            Line::from(vec![
+                method_span(&route.method, theme),
+                Span::raw(route.path.clone()),
+                Span::styled(
+                    format!(" {}", count(route.examples.len(), "example")),
+                    Style::default().fg(theme.muted),
+                ),
+            ])
+        })
+        .collect();
+
+    let height = usize::from(panes[1].height.saturating_sub(2));
+
+    let hits: Vec<Line> = mock.hits[mock.hits.len().saturating_sub(height)..]
+        .iter()
+        .map(|hit| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", hit.time.format("%H:%M:%S")),
+                    Style::default().fg(theme.muted),
+                ),
+                method_span(&hit.method, theme),
+                Span::styled(
+                    format!("{} ", hit.status),
+                    Style::default().fg(status_color(hit.status, theme)),
+                ),
+                Span::raw(hit.path.clone()),
+                Span::styled(
+                    format!(" {} · {} ms", hit.detail, hit.took.as_millis()),
+                    Style::default().fg(theme.muted),
+                ),
+            ])
+        })
+        .collect();
+
+    let routes_title = format!("Mock · {}", mock.name);
+    let hits_title = format!("Requests · {}", count(mock.hits.len(), "request"));
+
+    frame.render_widget(
+        Paragraph::new(routes).block(block(routes_title, theme)),
+        panes[0],
+    );
+    frame.render_widget(
+        Paragraph::new(hits).block(block(hits_title, theme)),
+        panes[1],
+    );
+
+    let help = format!("Listening on http://{} · c clear · q quit", mock.address);
+
+    frame.render_widget(
+        Paragraph::new(help).block(block("Help".to_string(), theme)),
+        chunks[1],
+    );
+}
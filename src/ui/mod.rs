@@ -1,4 +1,7 @@
+mod hex;
 mod input;
+mod json_tree;
+pub mod mock;
 mod popup;
 mod request_tab;
 mod response;
@@ -16,28 +19,96 @@ use ratatui::{
 use request_tab::render_request_tab;
 use response::render_response;
 
-use crate::app::{App, AppBlock, InputMode, RequestMethod};
+use crate::{
+    app::{areas::Areas, App, AppBlock, InputMode, RequestMethod},
+    config::{
+        keymap::{format_sequence, Action, KeyContext},
+        layout::Pane,
+        theme::Theme,
+    },
+};
 
 use self::input::create_input;
 
-fn selectable_block(block: AppBlock, app: &App) -> Block {
-    let is_selected = block == app.selected_block && app.popup.is_none();
+/// Keys for the help, the command palette and quitting, or the keys typed
+/// so far of a longer sequence
+fn key_hints(app: &App) -> String {
+    if !app.pending_keys.is_empty() {
+        return format!("{} ...", format_sequence(&app.pending_keys));
+    }
+
+    [
+        (Action::Help, "help"),
+        (Action::CommandPalette, "commands"),
+        (Action::Quit, "quit"),
+    ]
+    .into_iter()
+    .filter_map(|(action, label)| {
+        let keys = app
+            .keymap
+            .keys_for(&[KeyContext::Normal], KeyContext::Normal, action);
+
+        keys.first().map(|key| format!("{key} {label}"))
+    })
+    .collect::<Vec<_>>()
+    .join(" · ")
+}
+
+/// The collection and environment the variables of the endpoint come from
+fn endpoint_title(app: &App) -> String {
+    if let Some(file) = app.http_file.as_ref() {
+        let name = file
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        return match app.http_request.and_then(|index| file.requests.get(index)) {
+            Some(request) => format!("Endpoint · {name} · {}", request.name),
+            None => format!("Endpoint · {name}"),
+        };
+    }
 
-    let border_style = Style::default().fg(if is_selected && app.input_mode == InputMode::Insert {
-        Color::Green
-    } else if is_selected {
-        Color::Blue
-    } else {
-        Color::White
-    });
+    let Some(collection) = app.collection.as_ref() else {
+        return "Endpoint".to_string();
+    };
+
+    let environment = app
+        .environment
+        .and_then(|index| collection.environments.get(index));
+
+    match environment {
+        Some(environment) => format!("Endpoint · {} · {}", collection.name, environment.name),
+        None => format!("Endpoint · {}", collection.name),
+    }
+}
+
+fn selectable_block(block: AppBlock, app: &App) -> Block<'static> {
+    let is_selected = block == app.selected_block && app.popup.is_none();
 
     Block::default()
         .borders(Borders::ALL)
-        .border_style(border_style)
-        .style(Style::default().fg(Color::White))
+        .border_style(
+            app.theme
+                .border(is_selected, app.input_mode == InputMode::Insert),
+        )
+        .style(Style::default().fg(app.theme.text))
+}
+
+fn method_color(method: &RequestMethod, theme: &Theme) -> Color {
+    match method {
+        RequestMethod::Get => theme.method_get,
+        RequestMethod::Post => theme.method_post,
+        RequestMethod::Put => theme.method_put,
+        RequestMethod::Delete => theme.method_delete,
+        RequestMethod::Patch => theme.method_patch,
+    }
 }
 
 pub fn draw(frame: &mut Frame<CrosstermBackend<Stdout>>, app: &mut App) {
+    // Parts of a hidden pane can't be clicked
+    app.areas = Areas::default();
+
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Length(0), Constraint::Min(0)])
@@ -60,42 +131,64 @@ pub fn draw(frame: &mut Frame<CrosstermBackend<Stdout>>, app: &mut App) {
         .constraints([Constraint::Length(method_size), Constraint::Min(0)])
         .split(main_chunks[0]);
 
+    // A maximized pane follows the selection to the other one
+    if let (Some(_), Some(pane)) = (app.layout.maximized, Pane::of(&app.selected_block)) {
+        app.layout.maximized = Some(pane);
+    }
+
+    let direction = match app.layout.is_vertical(frame.size().width) {
+        true => Direction::Vertical,
+        false => Direction::Horizontal,
+    };
+
     let content_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .direction(direction)
+        .constraints([
+            Constraint::Percentage(app.layout.split),
+            Constraint::Percentage(100 - app.layout.split),
+        ])
         .split(main_chunks[1]);
 
-    let endpoint_input = create_input(&app.endpoint, app, app.selected_block == AppBlock::Endpoint)
-        .block(selectable_block(AppBlock::Endpoint, app).title("Endpoint"));
+    let endpoint_input = create_input(
+        &app.endpoint,
+        app,
+        app.selected_block == AppBlock::Endpoint,
+        header_chunks[1].width.saturating_sub(2),
+    )
+    .block(selectable_block(AppBlock::Endpoint, app).title(endpoint_title(app)));
 
     let method_p = Paragraph::new(app.method.to_string())
         .block(selectable_block(AppBlock::Method, app))
-        .style(Style::default().fg(match app.method {
-            RequestMethod::Get => Color::Green,
-            RequestMethod::Post => Color::Blue,
-            RequestMethod::Put => Color::Yellow,
-            RequestMethod::Delete => Color::Red,
-            RequestMethod::Patch => Color::Magenta,
-        }))
+        .style(Style::default().fg(method_color(&app.method, &app.theme)))
         .alignment(Alignment::Center);
 
-    let help_p = Paragraph::new("Press 'q' to quit").block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::White))
-            .title("Help"),
-    );
+    let help_p = Paragraph::new(app.status_message.clone().unwrap_or_else(|| key_hints(app)))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border))
+                .title("Help"),
+        );
 
     frame.render_widget(method_p, header_chunks[0]);
     frame.render_widget(endpoint_input, header_chunks[1]);
 
-    render_request_tab(app, frame, content_chunks[0]);
+    app.areas.method = header_chunks[0];
+    app.areas.endpoint = header_chunks[1];
+
+    match app.layout.maximized {
+        Some(Pane::Request) => render_request_tab(app, frame, main_chunks[1]),
+        Some(Pane::Response) => render_response(app, frame, main_chunks[1]),
+        None => {
+            render_request_tab(app, frame, content_chunks[0]);
 
-    render_response(app, frame, content_chunks[1]);
+            render_response(app, frame, content_chunks[1]);
+        }
+    }
 
     frame.render_widget(help_p, main_chunks[2]);
 
-    if let Some(_) = app.popup {
+    if app.popup.is_some() {
         render_popup(app, frame);
     }
 }
@@ -3,22 +3,46 @@ use std::io::Stdout;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     prelude::CrosstermBackend,
-    style::{Color, Style},
-    widgets::{Block, Borders, Clear, Paragraph},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, Clear, Paragraph, Wrap,
+    },
     Frame,
 };
 
-use crate::app::{App, AppPopup, InputMode, OrderNavigation, RequestMethod};
+use unicode_width::UnicodeWidthStr;
 
-use super::input::create_input;
+use crate::{
+    app::{
+        diff::{DiffLine, Row},
+        App, AppPopup, InputMode, OrderNavigation,
+    },
+    config::theme::Theme,
+    diff::{Change, PathChange},
+    event::input::Input,
+};
+
+use super::{input::create_input, method_color};
+
+/// Rows listed at once by the command palette and pickers
+const MAX_FINDER_ROWS: u16 = 12;
+
+/// Characters of a JSON value shown in the changed paths of a diff
+const MAX_VALUE_WIDTH: usize = 40;
+
+pub fn render_popup(app: &mut App, frame: &mut Frame<'_, CrosstermBackend<Stdout>>) {
+    let mut items = Vec::new();
+    let mut help_height = None;
+    let mut diff_height = None;
 
-pub fn render_popup(app: &App, frame: &mut Frame<'_, CrosstermBackend<Stdout>>) {
     match app.popup.as_ref() {
         Some(AppPopup::ChangeMethod) => {
             let block = Block::default()
                 .title("Select method")
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::White));
+                .border_style(Style::default().fg(app.theme.border));
 
             let methods = app.method.get_order();
 
@@ -26,17 +50,11 @@ pub fn render_popup(app: &App, frame: &mut Frame<'_, CrosstermBackend<Stdout>>)
                 let cloned_method = method.clone();
 
                 let border_style = match cloned_method == app.method.clone() {
-                    true => Style::default().fg(Color::Green),
-                    false => Style::default().fg(Color::White),
+                    true => app.theme.emphasis(app.theme.highlight),
+                    false => Style::default().fg(app.theme.border),
                 };
 
-                let style = Style::default().fg(match cloned_method {
-                    RequestMethod::Get => Color::Green,
-                    RequestMethod::Post => Color::Blue,
-                    RequestMethod::Put => Color::Yellow,
-                    RequestMethod::Delete => Color::Red,
-                    RequestMethod::Patch => Color::Magenta,
-                });
+                let style = Style::default().fg(method_color(&cloned_method, &app.theme));
 
                 let block = Paragraph::new(cloned_method.to_string())
                     .style(style)
@@ -60,14 +78,14 @@ pub fn render_popup(app: &App, frame: &mut Frame<'_, CrosstermBackend<Stdout>>)
             frame.render_widget(block, area);
 
             methods_blocks.for_each(|(index, p)| {
-                frame.render_widget(
-                    p,
-                    Rect::new(area.x + 2, area.y + index as u16 * 3 + 1, width - 4, 3),
-                );
+                let item = Rect::new(area.x + 2, area.y + index as u16 * 3 + 1, width - 4, 3);
+
+                frame.render_widget(p, item);
+                items.push(item);
             });
 
             let help_p = Paragraph::new("Use j/k to navigate, Enter to select")
-                .style(Style::default().fg(Color::White))
+                .style(Style::default().fg(app.theme.text))
                 .alignment(Alignment::Center);
 
             frame.render_widget(
@@ -80,7 +98,7 @@ pub fn render_popup(app: &App, frame: &mut Frame<'_, CrosstermBackend<Stdout>>)
                 .title(form.title.clone())
                 .title_alignment(Alignment::Center)
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Blue));
+                .border_style(app.theme.border(true, false));
 
             let visible_fields = form.visible_fields();
 
@@ -89,23 +107,19 @@ pub fn render_popup(app: &App, frame: &mut Frame<'_, CrosstermBackend<Stdout>>)
             let area = centered_rect(70, height as u16, frame.size());
 
             let inputs = visible_fields.iter().enumerate().map(|(index, field)| {
-                let input = create_input(&field.input, &app, index == form.selected_field as usize)
-                    .block(
-                        Block::default()
-                            .borders(Borders::ALL)
-                            .border_style(Style::default().fg(
-                                if index == form.selected_field
-                                    && app.input_mode == InputMode::Insert
-                                {
-                                    Color::Green
-                                } else if index == form.selected_field {
-                                    Color::Blue
-                                } else {
-                                    Color::White
-                                },
-                            ))
-                            .title(field.label.clone()),
-                    );
+                let is_selected = index == form.selected_field;
+
+                let field_block = Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(
+                        app.theme
+                            .border(is_selected, app.input_mode == InputMode::Insert),
+                    )
+                    .title(field.label.clone());
+
+                let input =
+                    create_input(&field.input, app, is_selected, area.width.saturating_sub(6))
+                        .block(field_block);
 
                 (index, input)
             });
@@ -114,24 +128,307 @@ pub fn render_popup(app: &App, frame: &mut Frame<'_, CrosstermBackend<Stdout>>)
             frame.render_widget(block, area);
 
             inputs.for_each(|(index, p)| {
-                frame.render_widget(
-                    p,
-                    Rect::new(area.x + 2, area.y + index as u16 * 3 + 1, area.width - 4, 3),
-                );
+                let item = Rect::new(area.x + 2, area.y + index as u16 * 3 + 1, area.width - 4, 3);
+
+                frame.render_widget(p, item);
+                items.push(item);
             });
 
             frame.render_widget(
                 Paragraph::new("Press Enter to Accept Changes")
-                    .style(Style::default().fg(Color::White))
+                    .style(Style::default().fg(app.theme.text))
                     .alignment(Alignment::Center),
                 Rect::new(area.x + 2, area.y + height as u16 - 2, area.width - 4, 1),
             );
         }
+        Some(AppPopup::ConfirmSend(message)) => render_confirm(
+            app,
+            frame,
+            "Send anyway?",
+            message,
+            "Press y to send, n to go back",
+        ),
+        Some(AppPopup::ConfirmOverwrite(_, path)) => render_confirm(
+            app,
+            frame,
+            "Replace the file?",
+            &format!("{} already exists", path.display()),
+            "Press y to replace it, n to go back",
+        ),
+        Some(AppPopup::Help(help)) => {
+            let height = help.line_count() as u16 + 2;
+
+            let area = centered_rect(64, height, frame.size());
+
+            let block = Block::default()
+                .title("Help")
+                .title(
+                    Title::from(" esc to close ")
+                        .alignment(Alignment::Right)
+                        .position(Position::Bottom),
+                )
+                .borders(Borders::ALL)
+                .border_style(app.theme.border(true, false));
+
+            let keys_width = help
+                .sections
+                .iter()
+                .flat_map(|section| &section.entries)
+                .map(|(keys, _)| keys.width())
+                .max()
+                .unwrap_or(0);
+
+            let mut lines = Vec::new();
+
+            for section in &help.sections {
+                lines.push(Line::from(Span::styled(
+                    section.title.clone(),
+                    app.theme
+                        .emphasis(app.theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                )));
+
+                for (keys, description) in &section.entries {
+                    lines.push(Line::from(vec![
+                        Span::styled(
+                            format!("  {keys:keys_width$}  "),
+                            Style::default().fg(app.theme.highlight),
+                        ),
+                        Span::styled(description.clone(), Style::default().fg(app.theme.text)),
+                    ]));
+                }
+
+                lines.push(Line::default());
+            }
+
+            frame.render_widget(Clear, area);
+            frame.render_widget(
+                Paragraph::new(lines).block(block).scroll((help.scroll, 0)),
+                area,
+            );
+
+            help_height = Some(usize::from(area.height.saturating_sub(2)));
+        }
+        Some(AppPopup::CommandPalette(palette)) => {
+            let rows = palette
+                .matches()
+                .iter()
+                .map(|entry| (entry.action.description().to_string(), entry.keys.clone()))
+                .collect();
+
+            render_finder(
+                app,
+                frame,
+                "Commands",
+                &palette.input,
+                rows,
+                palette.selected,
+            );
+        }
+        Some(AppPopup::Picker(picker)) => {
+            let rows = picker
+                .matches()
+                .iter()
+                .map(|item| (item.label.clone(), item.detail.clone()))
+                .collect();
+
+            render_finder(
+                app,
+                frame,
+                &picker.title,
+                &picker.input,
+                rows,
+                picker.selected,
+            );
+        }
+        Some(AppPopup::Diff(diff)) => {
+            let size = frame.size();
+            let area = centered_rect(
+                size.width.saturating_sub(8),
+                size.height.saturating_sub(4),
+                size,
+            );
+
+            let hint = match diff.side_by_side {
+                true => " s unified · esc to close ",
+                false => " s side by side · esc to close ",
+            };
+
+            let block = Block::default()
+                .title(diff.title.clone())
+                .title(
+                    Title::from(hint)
+                        .alignment(Alignment::Right)
+                        .position(Position::Bottom),
+                )
+                .borders(Borders::ALL)
+                .border_style(app.theme.border(true, false));
+
+            let lines: Vec<Line> = match diff.side_by_side {
+                true => {
+                    let width = usize::from(area.width.saturating_sub(2) / 2);
+
+                    diff.rows()
+                        .into_iter()
+                        .map(|row| match row {
+                            Row::Full(line) => diff_line(line, &app.theme),
+                            Row::Sides(old, new) => Line::from(vec![
+                                side_span(old, width, &app.theme),
+                                side_span(new, width, &app.theme),
+                            ]),
+                        })
+                        .collect()
+                }
+                false => diff
+                    .lines
+                    .iter()
+                    .map(|line| diff_line(line, &app.theme))
+                    .collect(),
+            };
+
+            frame.render_widget(Clear, area);
+            frame.render_widget(
+                Paragraph::new(lines).block(block).scroll((diff.scroll, 0)),
+                area,
+            );
+
+            diff_height = Some(usize::from(area.height.saturating_sub(2)));
+        }
         None => {}
     }
+
+    if let (Some(AppPopup::Help(help)), Some(height)) = (app.popup.as_mut(), help_height) {
+        help.height = height;
+    }
+
+    if let (Some(AppPopup::Diff(diff)), Some(height)) = (app.popup.as_mut(), diff_height) {
+        diff.height = height;
+    }
+
+    app.areas.popup_items = items;
+}
+
+/// A question answered with `y` or `n`, its message wrapped
+fn render_confirm(
+    app: &App,
+    frame: &mut Frame<'_, CrosstermBackend<Stdout>>,
+    title: &str,
+    message: &str,
+    hint: &str,
+) {
+    let size = frame.size();
+
+    // Narrow terminals shrink the popup, the message wraps within it
+    let text_width = 60.min(size.width).saturating_sub(4).max(1);
+    let message_lines = message.chars().count() as u16 / text_width + 1;
+
+    let area = centered_rect(60, message_lines + 5, size);
+
+    let block = Block::default()
+        .title(title.to_string())
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(app.theme.emphasis(app.theme.accent));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    frame.render_widget(
+        Paragraph::new(message.to_string())
+            .style(Style::default().fg(app.theme.text))
+            .wrap(Wrap { trim: true }),
+        Rect::new(
+            area.x + 2,
+            area.y + 1,
+            area.width.saturating_sub(4),
+            (message_lines + 1).min(area.height.saturating_sub(2)),
+        ),
+    );
+
+    frame.render_widget(
+        Paragraph::new(hint.to_string())
+            .style(Style::default().fg(app.theme.text))
+            .alignment(Alignment::Center),
+        Rect::new(
+            area.x + 2,
+            (area.y + area.height).saturating_sub(2),
+            area.width.saturating_sub(4),
+            1.min(area.height),
+        ),
+    );
+}
+
+/// A query input over the rows matching it, each a label and a muted
+/// detail on the right
+fn render_finder(
+    app: &App,
+    frame: &mut Frame<'_, CrosstermBackend<Stdout>>,
+    title: &str,
+    input: &Input,
+    matches: Vec<(String, String)>,
+    selected: usize,
+) {
+    let rows = (matches.len().max(1) as u16).min(MAX_FINDER_ROWS);
+    let width = 64;
+
+    let area = centered_rect(width, rows + 5, frame.size());
+
+    let block = Block::default()
+        .title(title.to_string())
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(app.theme.border(true, false));
+
+    let query = create_input(input, app, true, area.width.saturating_sub(4)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(app.theme.border(true, true)),
+    );
+
+    let list_area = Rect::new(area.x + 1, area.y + 4, area.width - 2, rows);
+    let list_width = usize::from(list_area.width);
+    let offset = (selected + 1).saturating_sub(usize::from(rows));
+
+    let lines: Vec<Line> = match matches.is_empty() {
+        true => vec![Line::from(Span::styled(
+            " No matches",
+            Style::default().fg(app.theme.muted),
+        ))],
+        false => matches
+            .into_iter()
+            .enumerate()
+            .skip(offset)
+            .take(usize::from(rows))
+            .map(|(index, (label, detail))| {
+                let label = format!(" {label}");
+                let detail = format!("{detail} ");
+                let gap = list_width.saturating_sub(label.width() + detail.width());
+
+                let mut line = Line::from(vec![
+                    Span::styled(label, Style::default().fg(app.theme.text)),
+                    Span::raw(" ".repeat(gap)),
+                    Span::styled(detail, Style::default().fg(app.theme.muted)),
+                ]);
+
+                if index == selected {
+                    line.patch_style(app.theme.selected_row());
+                }
+
+                line
+            })
+            .collect(),
+    };
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+    frame.render_widget(query, Rect::new(area.x + 1, area.y + 1, area.width - 2, 3));
+    frame.render_widget(Paragraph::new(lines), list_area);
 }
 
 fn centered_rect(width: u16, height: u16, r: Rect) -> Rect {
+    let width = width.min(r.width);
+    let height = height.min(r.height);
+
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints(
@@ -156,3 +453,86 @@ fn centered_rect(width: u16, height: u16, r: Rect) -> Rect {
         )
         .split(popup_layout[1])[1]
 }
+
+/// A JSON value on one line, cut to `MAX_VALUE_WIDTH`
+fn compact(value: &serde_json::Value) -> String {
+    let text = value.to_string();
+
+    if text.chars().count() <= MAX_VALUE_WIDTH {
+        return text;
+    }
+
+    let mut truncated: String = text.chars().take(MAX_VALUE_WIDTH - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+fn change_style(change: &Change, theme: &Theme) -> (&'static str, Style) {
+    match change {
+        Change::Same(_) => ("  ", Style::default().fg(theme.text)),
+        Change::Removed(_) => ("- ", Style::default().fg(theme.error)),
+        Change::Added(_) => ("+ ", Style::default().fg(theme.status_success)),
+    }
+}
+
+/// A line of the unified diff, also the section titles, folds and changed
+/// paths of the side by side one
+fn diff_line(line: &DiffLine, theme: &Theme) -> Line<'static> {
+    match line {
+        DiffLine::Section(title) => Line::from(Span::styled(
+            title.clone(),
+            theme.emphasis(theme.accent).add_modifier(Modifier::BOLD),
+        )),
+        DiffLine::Change(change) => {
+            let (Change::Same(text) | Change::Removed(text) | Change::Added(text)) = change;
+            let (prefix, style) = change_style(change, theme);
+
+            Line::from(Span::styled(format!("{prefix}{text}"), style))
+        }
+        DiffLine::Folded(count) => Line::from(Span::styled(
+            format!("  ··· {count} unchanged lines"),
+            Style::default().fg(theme.muted),
+        )),
+        DiffLine::Path(PathChange::Changed(path, old, new)) => Line::from(Span::styled(
+            format!("~ {path}: {} → {}", compact(old), compact(new)),
+            Style::default().fg(theme.accent),
+        )),
+        DiffLine::Path(PathChange::Removed(path, value)) => Line::from(Span::styled(
+            format!("- {path}: {}", compact(value)),
+            Style::default().fg(theme.error),
+        )),
+        DiffLine::Path(PathChange::Added(path, value)) => Line::from(Span::styled(
+            format!("+ {path}: {}", compact(value)),
+            Style::default().fg(theme.status_success),
+        )),
+    }
+}
+
+/// One side of a side by side row, padded or cut to `width`
+fn side_span(change: Option<&Change>, width: usize, theme: &Theme) -> Span<'static> {
+    let Some(change) = change else {
+        return Span::raw(" ".repeat(width));
+    };
+
+    let (Change::Same(text) | Change::Removed(text) | Change::Added(text)) = change;
+    let (prefix, style) = change_style(change, theme);
+
+    let mut cell = String::new();
+    let mut cell_width = 0;
+
+    for c in format!("{prefix}{text}").chars() {
+        let c_width = unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
+
+        // Leave a column between the sides
+        if cell_width + c_width >= width {
+            break;
+        }
+
+        cell.push(c);
+        cell_width += c_width;
+    }
+
+    cell.push_str(&" ".repeat(width - cell_width));
+
+    Span::styled(cell, style)
+}
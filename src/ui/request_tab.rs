@@ -3,35 +3,83 @@ use std::io::Stdout;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     prelude::CrosstermBackend,
-    style::{Color, Style},
+    style::Style,
     text::Span,
-    widgets::{Paragraph, Row, Table, TableState, Tabs},
+    widgets::{
+        block::{Position, Title},
+        Paragraph, Row, Table, TableState, Tabs,
+    },
     Frame,
 };
 
-use crate::app::{App, AppBlock, BodyContentType, BodyType, OrderNavigation, RequestTab};
+use crate::{
+    app::{App, AppBlock, BodyContentType, BodyType, InputMode, OrderNavigation, RequestTab},
+    config::theme::Theme,
+};
 
 use super::{input::create_textarea, selectable_block};
 
-pub fn render_request_tab(app: &App, frame: &mut Frame<'_, CrosstermBackend<Stdout>>, area: Rect) {
+/// Where each tab title is drawn by `Tabs` inside a bordered block
+fn tab_title_areas(titles: &[Span], area: Rect) -> Vec<(RequestTab, Rect)> {
+    let mut x = area.x + 1;
+
+    RequestTab::Body
+        .get_order()
+        .into_iter()
+        .zip(titles)
+        .map(|(tab, title)| {
+            let width = u16::try_from(title.width()).unwrap_or(u16::MAX);
+            let title_area = Rect::new(x + 1, area.y + 1, width, 1);
+
+            // Padding on both sides and the divider
+            x += width + 3;
+
+            (tab, title_area)
+        })
+        .collect()
+}
+
+/// Rows of a key value table, below its padding and header
+fn table_rows_area(area: Rect) -> Rect {
+    Rect {
+        y: area.y + 4,
+        height: area.height.saturating_sub(6),
+        ..area
+    }
+}
+
+pub fn render_request_tab(
+    app: &mut App,
+    frame: &mut Frame<'_, CrosstermBackend<Stdout>>,
+    area: Rect,
+) {
     let request_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Length(3), Constraint::Min(0)])
         .split(area);
 
+    app.areas.request_tabs = request_chunks[0];
+    app.areas.request_content = request_chunks[1];
+    app.areas.body_editor = Rect::default();
+    app.areas.table_rows = Rect::default();
+
+    let text_style = Style::default().fg(app.theme.text);
+
     let request_tabs = vec![
-        Span::styled("Body", Style::default().fg(Color::White)),
-        Span::styled("Query", Style::default().fg(Color::White)),
-        Span::styled("Headers", Style::default().fg(Color::White)),
-        // Span::styled("Auth", Style::default().fg(Color::White)),
-        // Span::styled("Cookies", Style::default().fg(Color::White)),
+        Span::styled("Body", text_style),
+        Span::styled("Query", text_style),
+        Span::styled("Headers", text_style),
+        // Span::styled("Auth", text_style),
+        // Span::styled("Cookies", text_style),
     ];
 
+    app.areas.tab_titles = tab_title_areas(&request_tabs, request_chunks[0]);
+
     let tab = Tabs::new(request_tabs)
         .block(selectable_block(AppBlock::Request, app))
         .divider(Span::raw("|"))
         .select(app.request_tab.clone().get_index())
-        .highlight_style(Style::default().fg(Color::Green));
+        .highlight_style(app.theme.emphasis(app.theme.highlight));
 
     frame.render_widget(tab, request_chunks[0]);
 
@@ -69,44 +117,75 @@ pub fn render_request_tab(app: &App, frame: &mut Frame<'_, CrosstermBackend<Stdo
                     BodyType::Xml => "XML",
                 })
                 .block(selectable_block(AppBlock::RequestContent, app).title("Type"))
-                .style(Style::default().fg(Color::Yellow))
+                .style(Style::default().fg(app.theme.accent))
                 .alignment(Alignment::Center);
 
                 frame.render_widget(content_type_format_p, content_type_chunks[1]);
 
-                let raw_body_input = create_textarea(&app.raw_body, app)
-                    .block(selectable_block(AppBlock::RequestContent, app).title("Body"));
+                let is_editing = app.input_mode == InputMode::Insert
+                    && app.selected_block == AppBlock::RequestContent;
+
+                let mut body_block = selectable_block(AppBlock::RequestContent, app).title(
+                    match is_editing && app.raw_body.is_normal_mode() {
+                        true => "Body [NORMAL]",
+                        false => "Body",
+                    },
+                );
+
+                if let Some(error) = app.body_error() {
+                    body_block = body_block.title(
+                        Title::from(Span::styled(
+                            format!(" {error} "),
+                            Style::default().fg(app.theme.error),
+                        ))
+                        .position(Position::Bottom),
+                    );
+                }
+
+                app.areas.body_editor = body_chunks[0];
+                app.raw_body
+                    .scroll_to_cursor(body_chunks[0].height.saturating_sub(2).into());
+
+                let raw_body_input = create_textarea(&app.raw_body, app).block(body_block);
 
                 frame.render_widget(raw_body_input, body_chunks[0]);
             } else {
-                let table = create_kv_table(app.body_form.clone().into_iter().collect()).block(
-                    selectable_block(AppBlock::RequestContent, app)
-                        .title("Body")
-                        .padding(ratatui::widgets::Padding::new(1, 1, 1, 1)),
-                );
+                let table =
+                    create_kv_table(app.body_form.clone().into_iter().collect(), &app.theme).block(
+                        selectable_block(AppBlock::RequestContent, app)
+                            .title("Body")
+                            .padding(ratatui::widgets::Padding::new(1, 1, 1, 1)),
+                    );
 
                 let mut state = TableState::default();
 
                 state.select(Some(app.selected_form_field.into()));
 
                 frame.render_stateful_widget(table, body_chunks[0], &mut state);
+
+                app.areas.table_rows = table_rows_area(body_chunks[0]);
+                app.areas.table_offset = state.offset();
             }
         }
         RequestTab::Headers => {
-            let table = create_kv_table(app.headers.clone().into_iter().collect()).block(
-                selectable_block(AppBlock::RequestContent, app)
-                    .title("Headers")
-                    .padding(ratatui::widgets::Padding::new(1, 1, 1, 1)),
-            );
+            let table = create_kv_table(app.headers.clone().into_iter().collect(), &app.theme)
+                .block(
+                    selectable_block(AppBlock::RequestContent, app)
+                        .title("Headers")
+                        .padding(ratatui::widgets::Padding::new(1, 1, 1, 1)),
+                );
 
             let mut state = TableState::default();
 
             state.select(Some(app.selected_header.into()));
 
             frame.render_stateful_widget(table, request_chunks[1], &mut state);
+
+            app.areas.table_rows = table_rows_area(request_chunks[1]);
+            app.areas.table_offset = state.offset();
         }
         RequestTab::Query => {
-            let table = create_kv_table(app.query_params.clone()).block(
+            let table = create_kv_table(app.query_params.clone(), &app.theme).block(
                 selectable_block(AppBlock::RequestContent, app)
                     .title("Query Parameters")
                     .padding(ratatui::widgets::Padding::new(1, 1, 1, 1)),
@@ -117,26 +196,29 @@ pub fn render_request_tab(app: &App, frame: &mut Frame<'_, CrosstermBackend<Stdo
             state.select(Some(app.selected_query_param.into()));
 
             frame.render_stateful_widget(table, request_chunks[1], &mut state);
+
+            app.areas.table_rows = table_rows_area(request_chunks[1]);
+            app.areas.table_offset = state.offset();
         }
         _ => {}
     }
 }
 
-fn create_kv_table(pairs: Vec<(String, String)>) -> Table<'static> {
+fn create_kv_table(pairs: Vec<(String, String)>, theme: &Theme) -> Table<'static> {
     let rows: Vec<Row> = pairs
         .iter()
         .map(|(key, value)| {
-            Row::new(vec![key.clone(), value.clone()]).style(Style::default().fg(Color::White))
+            Row::new(vec![key.clone(), value.clone()]).style(Style::default().fg(theme.text))
         })
         .collect();
 
     Table::new(rows)
         .header(
             Row::new(vec!["Key", "Value"])
-                .style(Style::default().fg(Color::Yellow))
+                .style(Style::default().fg(theme.accent))
                 .bottom_margin(1),
         )
         .widths(&[Constraint::Percentage(50), Constraint::Percentage(50)])
-        .highlight_style(Style::default().fg(Color::Green))
+        .highlight_style(theme.emphasis(theme.highlight))
         .highlight_symbol(">> ")
 }
@@ -1,55 +1,148 @@
 use std::io::Stdout;
 
+use cached::proc_macro::cached;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     prelude::CrosstermBackend,
-    style::{Color, Style},
-    widgets::{Block, Borders, Paragraph},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, Paragraph,
+    },
     Frame,
 };
 
-use crate::app::{App, AppBlock};
+use crate::{
+    app::{search::SearchMatch, App, AppBlock, DisplayKey, InputMode, ResponseView},
+    config::theme::ColorMode,
+    plural::count,
+};
+
+use super::{
+    hex::render_hex,
+    input::{create_input, prompt_line},
+    json_tree::render_json_tree,
+    selectable_block, syntax,
+};
 
-use super::{selectable_block, syntax};
+/// Lines of contract violations shown under the response, the rest are
+/// counted
+const MAX_VIOLATIONS: usize = 6;
 
 pub fn render_response(app: &mut App, frame: &mut Frame<CrosstermBackend<Stdout>>, area: Rect) {
+    let violations = app
+        .violations
+        .as_ref()
+        .filter(|_| app.response.is_some() && !app.is_loading);
+
+    let violations_height = match violations {
+        Some(violations) if !violations.is_empty() => {
+            violations.len().min(MAX_VIOLATIONS) as u16 + 2
+        }
+        _ => 0,
+    };
+
     let response_chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(violations_height),
+            Constraint::Length(3),
+        ])
         .split(area);
 
-    match app.response.as_ref() {
-        Some(r) => {
-            let lines_count = u16::try_from(r.text.lines().count()).unwrap_or(1);
-            let max_x = if lines_count > response_chunks[0].height {
-                lines_count - (response_chunks[0].height - 2)
-            } else {
-                0
-            };
+    app.areas.response_filter = response_chunks[0];
+    app.areas.response = response_chunks[1];
+
+    let (text, filter_error) = app.response_display();
+
+    render_filter(app, frame, response_chunks[0], filter_error);
+
+    match app.response.as_ref().map(|r| r.status_code) {
+        Some(status_code) => {
+            let mut response_block = selectable_block(AppBlock::Response, app).title(
+                if app.response_pretty || app.response_view == ResponseView::Tree {
+                    "Response"
+                } else {
+                    "Response (raw)"
+                },
+            );
+
+            let matches = app.response_search_matches();
+
+            if let Some(title) = search_counter(app, &matches) {
+                response_block = response_block.title(title);
+            }
 
-            app.response_scroll.0 = app.response_scroll.0.clamp(0, max_x);
+            if let Some(prompt) = search_prompt(app) {
+                response_block =
+                    response_block.title(Title::from(prompt).position(Position::Bottom));
+            }
 
-            let lines = syntax::highlight_response(r.text.clone(), r.content_type.clone());
+            match (app.response.as_ref(), app.response_tree.as_mut()) {
+                (Some(r), _) if r.is_binary => {
+                    render_hex(
+                        &mut app.response_hex,
+                        &r.body,
+                        response_block,
+                        &app.theme,
+                        frame,
+                        response_chunks[1],
+                    );
+                }
+                (_, Some(tree)) if app.response_view == ResponseView::Tree => {
+                    render_json_tree(tree, response_block, &app.theme, frame, response_chunks[1]);
+                }
+                _ => {
+                    let lines_count = u16::try_from(text.lines().count()).unwrap_or(1);
+                    // The lines that fit inside the borders of the body
+                    let visible = response_chunks[1].height.saturating_sub(2);
+                    let max_x = lines_count.saturating_sub(visible);
 
-            let response_p = Paragraph::new(lines)
-                .block(selectable_block(AppBlock::Response, app).title("Response"))
-                // .wrap(Wrap { trim: false })
-                .scroll(app.response_scroll);
+                    app.response_scroll.0 = app.response_scroll.0.min(max_x);
 
-            let status_code_style = Style::default().fg(match r.status_code {
-                200..=299 => Color::Green,
-                300..=399 => Color::Blue,
-                400..=499 => Color::Yellow,
-                500..=599 => Color::Red,
-                _ => Color::White,
+                    let lines = syntax::highlight_matches(
+                        response_lines(app),
+                        &matches.unwrap_or_default(),
+                        app.response_search.current,
+                        &app.theme,
+                    );
+
+                    let response_p = Paragraph::new(lines)
+                        .block(response_block)
+                        // .wrap(Wrap { trim: false })
+                        .scroll(app.response_scroll);
+
+                    frame.render_widget(response_p, response_chunks[1]);
+                }
+            }
+
+            let status_code_style = Style::default().fg(match status_code {
+                200..=299 => app.theme.status_success,
+                300..=399 => app.theme.status_redirect,
+                400..=499 => app.theme.status_client_error,
+                500..=599 => app.theme.status_server_error,
+                _ => app.theme.text,
             });
 
-            let status_code_text = if app.is_loading {
-                "Loading...".to_string()
-            } else {
-                r.status_code.to_string()
+            let status_code_text = match (app.is_loading, &app.violations) {
+                (true, _) => "Loading...".to_string(),
+                (false, Some(violations)) if violations.is_empty() => {
+                    format!("{status_code} · matches the spec")
+                }
+                (false, Some(violations)) => format!(
+                    "{status_code} · {}",
+                    count(violations.len(), "contract violation")
+                ),
+                (false, None) => status_code.to_string(),
             };
 
+            if violations_height > 0 {
+                render_violations(app, frame, response_chunks[2]);
+            }
+
             let status_code_p = Paragraph::new(status_code_text)
                 .block(
                     Block::default()
@@ -59,8 +152,7 @@ pub fn render_response(app: &mut App, frame: &mut Frame<CrosstermBackend<Stdout>
                 .style(status_code_style)
                 .alignment(Alignment::Center);
 
-            frame.render_widget(response_p, response_chunks[0]);
-            frame.render_widget(status_code_p, response_chunks[1]);
+            frame.render_widget(status_code_p, response_chunks[3]);
         }
         None => {
             let helper_text = Paragraph::new("Created with love by @fedeya")
@@ -75,12 +167,137 @@ pub fn render_response(app: &mut App, frame: &mut Frame<CrosstermBackend<Stdout>
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::White)),
+                    .border_style(Style::default().fg(app.theme.border)),
             )
             .alignment(Alignment::Center);
 
-            frame.render_widget(helper_text, response_chunks[0]);
-            frame.render_widget(status_blank, response_chunks[1]);
+            frame.render_widget(helper_text, response_chunks[1]);
+            frame.render_widget(status_blank, response_chunks[3]);
         }
     }
 }
+
+/// What the response gets wrong according to the spec of the collection
+fn render_violations(app: &App, frame: &mut Frame<CrosstermBackend<Stdout>>, area: Rect) {
+    let violations = app.violations.as_deref().unwrap_or_default();
+
+    let mut lines: Vec<Line> = violations
+        .iter()
+        .take(match violations.len() > MAX_VIOLATIONS {
+            true => MAX_VIOLATIONS - 1,
+            false => MAX_VIOLATIONS,
+        })
+        .map(|violation| Line::from(violation.as_str()))
+        .collect();
+
+    if violations.len() > MAX_VIOLATIONS {
+        lines.push(Line::styled(
+            format!("and {} more", violations.len() - lines.len()),
+            Style::default().fg(app.theme.muted),
+        ));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.error))
+        .title("Contract");
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .style(Style::default().fg(app.theme.text)),
+        area,
+    );
+}
+
+fn search_counter(app: &App, matches: &Result<Vec<SearchMatch>, String>) -> Option<Span<'static>> {
+    if !app.response_search.is_active() || app.response_view == ResponseView::Tree {
+        return None;
+    }
+
+    Some(match matches {
+        Ok(matches) if matches.is_empty() => {
+            Span::styled("[no matches]", Style::default().fg(app.theme.error))
+        }
+        Ok(matches) => Span::styled(
+            format!(
+                "[{}/{}]",
+                app.response_search.current.min(matches.len() - 1) + 1,
+                matches.len()
+            ),
+            Style::default().fg(app.theme.accent),
+        ),
+        Err(e) => Span::styled(format!("[{e}]"), Style::default().fg(app.theme.error)),
+    })
+}
+
+/// The displayed response highlighted, cached by what it's made of rather
+/// than by its text as it's drawn on every frame
+#[cached(
+    size = 8,
+    key = "(DisplayKey, Option<String>, ColorMode)",
+    convert = "{ (app.display_key(), app.theme.syntax_name.clone(), app.theme.mode) }"
+)]
+fn response_lines(app: &App) -> Vec<Line<'static>> {
+    let content_type = app
+        .response
+        .as_ref()
+        .map(|r| r.content_type.as_str())
+        .unwrap_or_default();
+
+    syntax::highlight_response(&app.response_display().0, content_type, &app.theme)
+}
+
+/// The `/query` line shown while searching
+fn search_prompt(app: &App) -> Option<Line<'static>> {
+    let is_editing =
+        app.input_mode == InputMode::Insert && app.selected_block == AppBlock::Response;
+
+    if !is_editing {
+        return None;
+    }
+
+    Some(prompt_line("/", &app.response_search.input, &app.theme))
+}
+
+fn render_filter(
+    app: &App,
+    frame: &mut Frame<CrosstermBackend<Stdout>>,
+    area: Rect,
+    error: Option<String>,
+) {
+    let is_selected = app.selected_block == AppBlock::ResponseFilter;
+
+    let mut block = selectable_block(AppBlock::ResponseFilter, app).title("Filter");
+
+    if let Some(error) = error {
+        block = block.title(
+            Title::from(Span::styled(error, Style::default().fg(app.theme.error)))
+                .position(Position::Bottom),
+        );
+    }
+
+    if app.response_filter.text.is_empty() && !(is_selected && app.input_mode == InputMode::Insert)
+    {
+        let placeholder = Paragraph::new(".items[0].name or $..id")
+            .style(
+                Style::default()
+                    .fg(app.theme.muted)
+                    .add_modifier(Modifier::ITALIC),
+            )
+            .block(block);
+
+        frame.render_widget(placeholder, area);
+        return;
+    }
+
+    let filter_input = create_input(
+        &app.response_filter,
+        app,
+        is_selected,
+        area.width.saturating_sub(2),
+    )
+    .block(block);
+
+    frame.render_widget(filter_input, area);
+}
@@ -1,32 +1,30 @@
-use cached::proc_macro::cached;
+use crate::{app::search::SearchMatch, config::theme::Theme, content_type};
 use once_cell::sync::Lazy;
 use ratatui::{
-    style::{Color, Style},
+    style::Style,
     text::{Line, Span},
 };
-use syntect::{
-    easy::HighlightLines, highlighting::ThemeSet, parsing::SyntaxSet, util::LinesWithEndings,
-};
+
+use syntect::{easy::HighlightLines, parsing::SyntaxSet, util::LinesWithEndings};
 
 pub static PS: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
-pub static TS: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
-
-#[cached]
-pub fn highlight_response(response: String, content_type: String) -> Vec<Line<'static>> {
-    let syntax_name = match content_type.as_str() {
-        "application/json" => "json",
-        "application/xml" => "xml",
-        "text/html" => "html",
-        "text/plain" => "txt",
-        _ => "txt",
+
+pub fn highlight_response(response: &str, content_type: &str, theme: &Theme) -> Vec<Line<'static>> {
+    let Some(syntax_theme) = theme.syntax.as_ref() else {
+        return response
+            .lines()
+            .map(|line| Line::from(line.to_string()))
+            .collect();
     };
 
+    let syntax_name = content_type::classify(content_type).syntax_extension();
+
     let syntax = PS.find_syntax_by_extension(syntax_name).unwrap();
-    let mut h = HighlightLines::new(syntax, &TS.themes["base16-ocean.dark"]);
+    let mut h = HighlightLines::new(syntax, syntax_theme);
 
     let mut lines: Vec<Line> = Vec::new();
 
-    for line in LinesWithEndings::from(response.as_str()) {
+    for line in LinesWithEndings::from(response) {
         let ranges: Vec<(syntect::highlighting::Style, &str)> =
             h.highlight_line(line, &PS).unwrap();
 
@@ -38,7 +36,7 @@ pub fn highlight_response(response: String, content_type: String) -> Vec<Line<'s
                 Span::styled(
                     content.to_string(),
                     Style {
-                        fg: translate_colour(style.foreground),
+                        fg: theme.syntax_color(style.foreground),
                         ..Style::default()
                     },
                 )
@@ -51,9 +49,81 @@ pub fn highlight_response(response: String, content_type: String) -> Vec<Line<'s
     lines
 }
 
-pub fn translate_colour(syntect_color: syntect::highlighting::Color) -> Option<Color> {
-    match syntect_color {
-        syntect::highlighting::Color { r, g, b, a } if a > 0 => Some(Color::Rgb(r, g, b)),
-        _ => None,
+/// Paint search matches over already highlighted lines, the focused match
+/// gets a different color
+pub fn highlight_matches(
+    lines: Vec<Line<'static>>,
+    matches: &[SearchMatch],
+    current: usize,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    if matches.is_empty() {
+        return lines;
     }
+
+    let mut matches_iter = matches.iter().enumerate().peekable();
+
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(line_index, line)| {
+            let mut line_matches = Vec::new();
+
+            while let Some((i, m)) = matches_iter.peek() {
+                if m.line != line_index {
+                    break;
+                }
+
+                line_matches.push((*i == current, m.start, m.end));
+                matches_iter.next();
+            }
+
+            if line_matches.is_empty() {
+                return line;
+            }
+
+            let mut spans = Vec::new();
+            let mut offset = 0;
+
+            for span in line.spans {
+                let content = span.content.to_string();
+                let span_end = offset + content.len();
+
+                let mut boundaries = vec![offset, span_end];
+
+                for (_, start, end) in &line_matches {
+                    for b in [*start, *end] {
+                        if b > offset && b < span_end {
+                            boundaries.push(b);
+                        }
+                    }
+                }
+
+                boundaries.sort_unstable();
+                boundaries.dedup();
+
+                for piece in boundaries.windows(2) {
+                    let (start, end) = (piece[0], piece[1]);
+
+                    let text = &content[start - offset..end - offset];
+
+                    let style = match line_matches
+                        .iter()
+                        .find(|(_, s, e)| start >= *s && end <= *e)
+                    {
+                        Some((is_current, _, _)) => {
+                            span.style.patch(theme.search_match(*is_current))
+                        }
+                        None => span.style,
+                    };
+
+                    spans.push(Span::styled(text.to_string(), style));
+                }
+
+                offset = span_end;
+            }
+
+            Line::from(spans)
+        })
+        .collect()
 }
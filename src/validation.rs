@@ -0,0 +1,155 @@
+use cached::proc_macro::cached;
+use quick_xml::{events::Event, Reader};
+
+use crate::content_type::ContentKind;
+
+#[derive(Clone, Debug)]
+pub struct BodyError {
+    pub message: String,
+    /// 1-based
+    pub line: usize,
+    /// 1-based, counted in characters
+    pub column: usize,
+}
+
+impl std::fmt::Display for BodyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (line {}, column {})",
+            self.message, self.line, self.column
+        )
+    }
+}
+
+fn position_at(text: &str, byte_offset: usize) -> (usize, usize) {
+    let before = &text[..byte_offset.min(text.len())];
+
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map(|l| l.chars().count())
+        .unwrap_or(0)
+        + 1;
+
+    (line, column)
+}
+
+fn validate_json(text: &str) -> Result<(), BodyError> {
+    serde_json::from_str::<serde_json::Value>(text)
+        .map(|_| ())
+        .map_err(|e| {
+            let message = e.to_string();
+
+            BodyError {
+                // serde_json appends the position to the message
+                message: message
+                    .split(" at line ")
+                    .next()
+                    .unwrap_or(&message)
+                    .to_string(),
+                line: e.line(),
+                column: e.column(),
+            }
+        })
+}
+
+fn validate_xml(text: &str) -> Result<(), BodyError> {
+    let mut reader = Reader::from_str(text);
+    let mut open_elements = Vec::new();
+
+    loop {
+        let error = match reader.read_event() {
+            Ok(Event::Start(element)) => {
+                open_elements.push((
+                    String::from_utf8_lossy(element.name().as_ref()).to_string(),
+                    reader.buffer_position() as usize,
+                ));
+                continue;
+            }
+            Ok(Event::End(_)) => {
+                open_elements.pop();
+                continue;
+            }
+            Ok(Event::Eof) => match open_elements.pop() {
+                Some((name, position)) => (format!("unclosed element <{name}>"), position),
+                None => return Ok(()),
+            },
+            Ok(_) => continue,
+            Err(e) => (e.to_string(), reader.error_position() as usize),
+        };
+
+        let (message, position) = error;
+        let (line, column) = position_at(text, position);
+
+        return Err(BodyError {
+            message,
+            line,
+            column,
+        });
+    }
+}
+
+/// Check that a request body is well formed for its type, empty bodies are
+/// always valid
+#[cached(size = 8)]
+pub fn validate_body(text: String, kind: ContentKind) -> Result<(), BodyError> {
+    if text.trim().is_empty() {
+        return Ok(());
+    }
+
+    match kind {
+        ContentKind::Json => validate_json(&text),
+        ContentKind::Xml => validate_xml(&text),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(text: &str, kind: ContentKind) -> (String, usize, usize) {
+        let error = validate_body(text.to_string(), kind).unwrap_err();
+
+        (error.message, error.line, error.column)
+    }
+
+    #[test]
+    fn accepts_valid_and_empty_bodies() {
+        assert!(validate_body("{\"a\": [1]}".to_string(), ContentKind::Json).is_ok());
+        assert!(validate_body("<a><b/></a>".to_string(), ContentKind::Xml).is_ok());
+        assert!(validate_body("  \n".to_string(), ContentKind::Json).is_ok());
+        assert!(validate_body("not checked".to_string(), ContentKind::Text).is_ok());
+    }
+
+    #[test]
+    fn reports_json_errors_where_they_are() {
+        assert_eq!(
+            error("{\n  \"a\": 1,\n  \"b\" 2\n}", ContentKind::Json),
+            ("expected `:`".to_string(), 3, 7)
+        );
+        assert_eq!(
+            error("[1, 2", ContentKind::Json),
+            ("EOF while parsing a list".to_string(), 1, 5)
+        );
+    }
+
+    #[test]
+    fn reports_xml_errors_where_they_are() {
+        let (_, line, column) = error("<a>\n  <é></b>\n</a>", ContentKind::Xml);
+
+        assert_eq!((line, column), (2, 6));
+        assert_eq!(
+            error("<a>\n  <b>text</b>", ContentKind::Xml),
+            ("unclosed element <a>".to_string(), 1, 4)
+        );
+    }
+
+    #[test]
+    fn counts_columns_in_characters() {
+        assert_eq!(position_at("é\nàb", 5), (2, 2));
+        assert_eq!(position_at("ab", 10), (1, 3));
+    }
+}